@@ -0,0 +1,2 @@
+DROP TRIGGER IF EXISTS ftd_transfer_trigger_notify ON ftd_transfer;
+DROP FUNCTION IF EXISTS ftd_transfer_notify;
@@ -0,0 +1,14 @@
+CREATE OR REPLACE FUNCTION ftd_transfer_notify()
+    RETURNS TRIGGER AS
+$$
+BEGIN
+    PERFORM pg_notify('ftd_transfer', NEW.id::TEXT);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER ftd_transfer_trigger_notify
+    AFTER INSERT
+    ON ftd_transfer
+    FOR EACH ROW
+EXECUTE FUNCTION ftd_transfer_notify();
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS ftd_alert_evaluator_state;
+DROP TABLE IF EXISTS ftd_alert CASCADE;
+DROP TABLE IF EXISTS ftd_watch_entry CASCADE;
@@ -0,0 +1,62 @@
+-- the timestamps are compared with the transfer timestamps in milliseconds since the epoch, so
+-- they are stored with their time zone
+CREATE TABLE IF NOT EXISTS ftd_watch_entry
+(
+    id             SERIAL PRIMARY KEY,
+    address        VARCHAR(64)              NOT NULL,
+    direction      VARCHAR(16)              NOT NULL DEFAULT 'both',
+    threshold      VARCHAR(128)             NOT NULL DEFAULT '0',
+    label          VARCHAR(2048),
+    webhook_url    VARCHAR(2048),
+    webhook_secret VARCHAR(128),
+    is_active      BOOLEAN                  NOT NULL DEFAULT true,
+    created_at     TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    updated_at     TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    CONSTRAINT ftd_watch_entry_c_direction CHECK (direction IN ('incoming', 'outgoing', 'both'))
+);
+
+CREATE INDEX IF NOT EXISTS ftd_watch_entry_idx_address
+    ON ftd_watch_entry (address);
+
+CREATE TABLE IF NOT EXISTS ftd_alert
+(
+    id                     SERIAL PRIMARY KEY,
+    watch_entry_id         INTEGER                  NOT NULL,
+    transfer_id            INTEGER                  NOT NULL,
+    delivery_status        VARCHAR(16)              NOT NULL,
+    delivery_attempt_count INTEGER                  NOT NULL DEFAULT 0,
+    next_delivery_at       TIMESTAMP WITH TIME ZONE,
+    delivered_at           TIMESTAMP WITH TIME ZONE,
+    last_delivery_error    TEXT,
+    created_at             TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    updated_at             TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    CONSTRAINT ftd_alert_u_watch_entry_id_transfer_id UNIQUE (watch_entry_id, transfer_id),
+    CONSTRAINT ftd_alert_c_delivery_status CHECK (delivery_status IN ('pending', 'delivered', 'failed', 'skipped')),
+    CONSTRAINT ftd_alert_fk_watch_entry_id
+        FOREIGN KEY (watch_entry_id)
+            REFERENCES ftd_watch_entry (id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    CONSTRAINT ftd_alert_fk_transfer_id
+        FOREIGN KEY (transfer_id)
+            REFERENCES ftd_transfer (id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS ftd_alert_idx_watch_entry_id
+    ON ftd_alert (watch_entry_id);
+CREATE INDEX IF NOT EXISTS ftd_alert_idx_delivery_status_next_delivery_at
+    ON ftd_alert (delivery_status, next_delivery_at);
+
+CREATE TABLE IF NOT EXISTS ftd_alert_evaluator_state
+(
+    id                         INTEGER PRIMARY KEY,
+    last_processed_transfer_id INTEGER                     NOT NULL,
+    created_at                 TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    updated_at                 TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+-- start at the latest transfer so that the transfer history is not evaluated
+INSERT INTO ftd_alert_evaluator_state(id, last_processed_transfer_id)
+SELECT 1, COALESCE(MAX(id), 0) FROM ftd_transfer;
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS ftd_label CASCADE;
@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS ftd_label
+(
+    id         SERIAL PRIMARY KEY,
+    address    VARCHAR(64)                 NOT NULL,
+    name       VARCHAR(2048)               NOT NULL,
+    category   VARCHAR(32)                 NOT NULL,
+    source     VARCHAR(256)                NOT NULL,
+    confidence REAL                        NOT NULL DEFAULT 1,
+    labeled_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    CONSTRAINT ftd_label_u_address_name_source UNIQUE (address, name, source),
+    CONSTRAINT ftd_label_c_category CHECK (category IN ('exchange', 'bridge', 'scam', 'treasury', 'validator', 'other')),
+    CONSTRAINT ftd_label_c_confidence CHECK (confidence >= 0 AND confidence <= 1)
+);
+
+CREATE INDEX IF NOT EXISTS ftd_label_idx_address
+    ON ftd_label (address);
+CREATE INDEX IF NOT EXISTS ftd_label_idx_name
+    ON ftd_label USING GIN (name gin_trgm_ops);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS ftd_api_key CASCADE;
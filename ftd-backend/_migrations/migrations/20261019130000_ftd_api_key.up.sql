@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS ftd_api_key
+(
+    id                    SERIAL PRIMARY KEY,
+    name                  VARCHAR(256)                NOT NULL,
+    key_hash              VARCHAR(64)                 NOT NULL,
+    key_prefix            VARCHAR(16)                 NOT NULL,
+    scope                 VARCHAR(32)                 NOT NULL DEFAULT 'standard',
+    rate_limit_per_minute INTEGER                     NOT NULL,
+    burst                 INTEGER                     NOT NULL,
+    is_active             BOOLEAN                     NOT NULL DEFAULT true,
+    created_at            TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    updated_at            TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    CONSTRAINT ftd_api_key_u_key_hash UNIQUE (key_hash),
+    CONSTRAINT ftd_api_key_c_scope CHECK (scope IN ('standard', 'premium')),
+    CONSTRAINT ftd_api_key_c_rate_limit_per_minute CHECK (rate_limit_per_minute > 0),
+    CONSTRAINT ftd_api_key_c_burst CHECK (burst > 0)
+);
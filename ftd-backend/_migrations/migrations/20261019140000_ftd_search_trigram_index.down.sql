@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS ftd_identity_idx_display_trgm;
+DROP INDEX IF EXISTS ftd_identity_idx_legal_trgm;
+DROP INDEX IF EXISTS ftd_identity_idx_web_trgm;
+DROP INDEX IF EXISTS ftd_identity_idx_email_trgm;
+DROP INDEX IF EXISTS ftd_identity_idx_twitter_trgm;
+DROP INDEX IF EXISTS ftd_sub_identity_idx_sub_display_trgm;
+DROP INDEX IF EXISTS ftd_subscan_account_idx_display_trgm;
+DROP INDEX IF EXISTS ftd_subscan_account_idx_merkle_science_tag_name_trgm;
@@ -0,0 +1,18 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS ftd_identity_idx_display_trgm
+    ON ftd_identity USING GIN (display gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS ftd_identity_idx_legal_trgm
+    ON ftd_identity USING GIN (legal gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS ftd_identity_idx_web_trgm
+    ON ftd_identity USING GIN (web gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS ftd_identity_idx_email_trgm
+    ON ftd_identity USING GIN (email gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS ftd_identity_idx_twitter_trgm
+    ON ftd_identity USING GIN (twitter gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS ftd_sub_identity_idx_sub_display_trgm
+    ON ftd_sub_identity USING GIN (sub_display gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS ftd_subscan_account_idx_display_trgm
+    ON ftd_subscan_account USING GIN (display gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS ftd_subscan_account_idx_merkle_science_tag_name_trgm
+    ON ftd_subscan_account USING GIN (merkle_science_tag_name gin_trgm_ops);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS ftd_identity_change CASCADE;
@@ -0,0 +1,41 @@
+CREATE TABLE IF NOT EXISTS ftd_identity_change
+(
+    id              SERIAL PRIMARY KEY,
+    address         VARCHAR(64)                 NOT NULL,
+    change_type     VARCHAR(32)                 NOT NULL,
+    display         VARCHAR(2048),
+    legal           VARCHAR(2048),
+    web             VARCHAR(2048),
+    riot            VARCHAR(2048),
+    email           VARCHAR(2048),
+    twitter         VARCHAR(2048),
+    is_confirmed    BOOLEAN,
+    is_invalid      BOOLEAN,
+    super_address   VARCHAR(64),
+    sub_display     VARCHAR(256),
+    block_hash      VARCHAR(66)                 NOT NULL,
+    block_number    BIGINT                      NOT NULL,
+    block_timestamp BIGINT                      NOT NULL,
+    created_at      TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    CONSTRAINT ftd_identity_change_c_change_type CHECK (change_type IN ('identity_set', 'identity_cleared', 'sub_identity_set', 'sub_identity_cleared'))
+);
+
+CREATE INDEX IF NOT EXISTS ftd_identity_change_idx_address_block_number
+    ON ftd_identity_change (address, block_number);
+CREATE INDEX IF NOT EXISTS ftd_identity_change_idx_address_block_timestamp
+    ON ftd_identity_change (address, block_timestamp);
+CREATE INDEX IF NOT EXISTS ftd_identity_change_idx_block_timestamp
+    ON ftd_identity_change (block_timestamp);
+
+-- the current identities are the initial entries of the log, observed at the last identity update:
+-- block hash, number and timestamp all come from the updater state, the timestamp being the time
+-- the state was saved for that block since the People chain block timestamps are not stored
+INSERT INTO ftd_identity_change (address, change_type, display, legal, web, riot, email, twitter, is_confirmed, is_invalid, block_hash, block_number, block_timestamp)
+SELECT i.address, 'identity_set', i.display, i.legal, i.web, i.riot, i.email, i.twitter, i.is_confirmed, i.is_invalid, s.block_hash, s.block_number, (EXTRACT(EPOCH FROM s.updated_at) * 1000)::BIGINT
+FROM ftd_identity i, ftd_identity_transfer_updater_state s
+WHERE s.id = 1;
+
+INSERT INTO ftd_identity_change (address, change_type, super_address, sub_display, block_hash, block_number, block_timestamp)
+SELECT si.address, 'sub_identity_set', si.super_address, si.sub_display, s.block_hash, s.block_number, (EXTRACT(EPOCH FROM s.updated_at) * 1000)::BIGINT
+FROM ftd_sub_identity si, ftd_identity_transfer_updater_state s
+WHERE s.id = 1;
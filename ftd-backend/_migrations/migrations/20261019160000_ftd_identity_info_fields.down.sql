@@ -0,0 +1,19 @@
+ALTER TABLE ftd_identity_transfer_updater_state
+    DROP COLUMN IF EXISTS is_identity_baseline_pending;
+
+DROP INDEX IF EXISTS ftd_identity_idx_github_trgm;
+DROP INDEX IF EXISTS ftd_identity_idx_discord_trgm;
+
+ALTER TABLE ftd_identity_change
+    DROP COLUMN IF EXISTS github,
+    DROP COLUMN IF EXISTS discord,
+    DROP COLUMN IF EXISTS image,
+    DROP COLUMN IF EXISTS pgp_fingerprint,
+    DROP COLUMN IF EXISTS hashed_fields;
+
+ALTER TABLE ftd_identity
+    DROP COLUMN IF EXISTS github,
+    DROP COLUMN IF EXISTS discord,
+    DROP COLUMN IF EXISTS image,
+    DROP COLUMN IF EXISTS pgp_fingerprint,
+    DROP COLUMN IF EXISTS hashed_fields;
@@ -0,0 +1,26 @@
+ALTER TABLE ftd_identity
+    ADD COLUMN IF NOT EXISTS github          VARCHAR(256),
+    ADD COLUMN IF NOT EXISTS discord         VARCHAR(256),
+    ADD COLUMN IF NOT EXISTS image           VARCHAR(256),
+    ADD COLUMN IF NOT EXISTS pgp_fingerprint VARCHAR(42),
+    ADD COLUMN IF NOT EXISTS hashed_fields   JSONB NOT NULL DEFAULT '{}';
+
+ALTER TABLE ftd_identity_change
+    ADD COLUMN IF NOT EXISTS github          VARCHAR(2048),
+    ADD COLUMN IF NOT EXISTS discord         VARCHAR(2048),
+    ADD COLUMN IF NOT EXISTS image           VARCHAR(2048),
+    ADD COLUMN IF NOT EXISTS pgp_fingerprint VARCHAR(42),
+    ADD COLUMN IF NOT EXISTS hashed_fields   JSONB;
+
+-- the stored identities lack the new fields until the next identity update, which saves them as
+-- the baseline without logging identity changes
+ALTER TABLE ftd_identity_transfer_updater_state
+    ADD COLUMN IF NOT EXISTS is_identity_baseline_pending BOOLEAN NOT NULL DEFAULT FALSE;
+UPDATE ftd_identity_transfer_updater_state
+SET is_identity_baseline_pending = EXISTS (SELECT 1 FROM ftd_identity)
+WHERE id = 1;
+
+CREATE INDEX IF NOT EXISTS ftd_identity_idx_github_trgm
+    ON ftd_identity USING GIN (github gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS ftd_identity_idx_discord_trgm
+    ON ftd_identity USING GIN (discord gin_trgm_ops);
@@ -0,0 +1,7 @@
+DROP TABLE IF EXISTS ftd_registrar CASCADE;
+
+ALTER TABLE ftd_identity_change
+    DROP COLUMN IF EXISTS judgements;
+
+ALTER TABLE ftd_identity
+    DROP COLUMN IF EXISTS judgements;
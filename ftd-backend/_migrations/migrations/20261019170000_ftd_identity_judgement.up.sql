@@ -0,0 +1,21 @@
+ALTER TABLE ftd_identity
+    ADD COLUMN IF NOT EXISTS judgements JSONB NOT NULL DEFAULT '[]';
+
+ALTER TABLE ftd_identity_change
+    ADD COLUMN IF NOT EXISTS judgements JSONB;
+
+-- the stored identities lack the judgements until the next identity update, which saves them as
+-- the baseline without logging identity changes
+UPDATE ftd_identity_transfer_updater_state
+SET is_identity_baseline_pending = EXISTS (SELECT 1 FROM ftd_identity)
+WHERE id = 1;
+
+CREATE TABLE IF NOT EXISTS ftd_registrar
+(
+    registrar_index INTEGER PRIMARY KEY,
+    address         VARCHAR(64)                 NOT NULL,
+    fee             VARCHAR(128)                NOT NULL,
+    fields          BIGINT                      NOT NULL,
+    created_at      TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS ftd_username_authority CASCADE;
+DROP TABLE IF EXISTS ftd_username CASCADE;
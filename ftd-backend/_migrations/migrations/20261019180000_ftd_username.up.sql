@@ -0,0 +1,31 @@
+CREATE TABLE IF NOT EXISTS ftd_username
+(
+    username   VARCHAR(64) PRIMARY KEY,
+    address    VARCHAR(64)                 NOT NULL,
+    suffix     VARCHAR(16)                 NOT NULL,
+    provider   VARCHAR(32)                 NOT NULL,
+    deposit    VARCHAR(128),
+    is_primary BOOLEAN                     NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    CONSTRAINT ftd_username_fk_address
+        FOREIGN KEY (address)
+            REFERENCES ftd_account (address)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    CONSTRAINT ftd_username_c_provider CHECK (provider IN ('allocation', 'authority_deposit', 'system'))
+);
+
+CREATE INDEX IF NOT EXISTS ftd_username_idx_address
+    ON ftd_username (address);
+CREATE INDEX IF NOT EXISTS ftd_username_idx_username_trgm
+    ON ftd_username USING GIN (username gin_trgm_ops);
+
+CREATE TABLE IF NOT EXISTS ftd_username_authority
+(
+    suffix     VARCHAR(16) PRIMARY KEY,
+    address    VARCHAR(64)                 NOT NULL,
+    allocation INTEGER                     NOT NULL,
+    created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS ftd_identity_change_idx_is_backfill;
+
+ALTER TABLE ftd_identity_change
+    DROP CONSTRAINT IF EXISTS ftd_identity_change_c_chain,
+    DROP COLUMN IF EXISTS chain,
+    DROP COLUMN IF EXISTS is_backfill;
@@ -0,0 +1,9 @@
+ALTER TABLE ftd_identity_change
+    ADD COLUMN IF NOT EXISTS chain       VARCHAR(16) NOT NULL DEFAULT 'people',
+    ADD COLUMN IF NOT EXISTS is_backfill BOOLEAN     NOT NULL DEFAULT FALSE,
+    ADD CONSTRAINT ftd_identity_change_c_chain CHECK (chain IN ('relay', 'people'));
+
+CREATE INDEX IF NOT EXISTS ftd_identity_change_idx_is_backfill
+    ON ftd_identity_change (is_backfill);
+CREATE INDEX IF NOT EXISTS ftd_identity_change_idx_chain_block_number
+    ON ftd_identity_change (chain, block_number);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS ftd_relation_updater_state CASCADE;
+DROP TABLE IF EXISTS ftd_account_relation_change CASCADE;
+DROP TABLE IF EXISTS ftd_account_relation CASCADE;
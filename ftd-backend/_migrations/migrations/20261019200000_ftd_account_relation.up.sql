@@ -0,0 +1,63 @@
+CREATE TABLE IF NOT EXISTS ftd_account_relation
+(
+    id                 SERIAL PRIMARY KEY,
+    from_address       VARCHAR(64)                 NOT NULL,
+    to_address         VARCHAR(64)                 NOT NULL,
+    relation_type      VARCHAR(32)                 NOT NULL,
+    proxy_type         VARCHAR(64),
+    proxy_delay        INTEGER,
+    multisig_threshold INTEGER,
+    block_number       BIGINT                      NOT NULL,
+    created_at         TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    updated_at         TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    CONSTRAINT ftd_account_relation_fk_from_address
+        FOREIGN KEY (from_address)
+            REFERENCES ftd_account (address)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    CONSTRAINT ftd_account_relation_fk_to_address
+        FOREIGN KEY (to_address)
+            REFERENCES ftd_account (address)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    CONSTRAINT ftd_account_relation_c_relation_type CHECK (relation_type IN ('proxy', 'multisig_member', 'staking_controller', 'staking_payee'))
+);
+
+-- proxies of different types between the same accounts are separate relations
+CREATE UNIQUE INDEX IF NOT EXISTS ftd_account_relation_u_from_to_type
+    ON ftd_account_relation (from_address, to_address, relation_type, COALESCE(proxy_type, ''));
+CREATE INDEX IF NOT EXISTS ftd_account_relation_idx_to_address
+    ON ftd_account_relation (to_address);
+
+CREATE TABLE IF NOT EXISTS ftd_account_relation_change
+(
+    id                 SERIAL PRIMARY KEY,
+    from_address       VARCHAR(64)                 NOT NULL,
+    to_address         VARCHAR(64)                 NOT NULL,
+    relation_type      VARCHAR(32)                 NOT NULL,
+    proxy_type         VARCHAR(64),
+    proxy_delay        INTEGER,
+    multisig_threshold INTEGER,
+    is_removed         BOOLEAN                     NOT NULL,
+    block_number       BIGINT                      NOT NULL,
+    created_at         TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+    CONSTRAINT ftd_account_relation_change_c_relation_type CHECK (relation_type IN ('proxy', 'multisig_member', 'staking_controller', 'staking_payee'))
+);
+
+CREATE INDEX IF NOT EXISTS ftd_account_relation_change_idx_from_address
+    ON ftd_account_relation_change (from_address);
+CREATE INDEX IF NOT EXISTS ftd_account_relation_change_idx_to_address
+    ON ftd_account_relation_change (to_address);
+
+CREATE TABLE IF NOT EXISTS ftd_relation_updater_state
+(
+    id            INTEGER PRIMARY KEY,
+    block_hash    VARCHAR(64)                 NOT NULL,
+    block_number  BIGINT                      NOT NULL,
+    is_successful BOOLEAN                     NOT NULL,
+    error_log     TEXT,
+    updated_at    TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+INSERT INTO ftd_relation_updater_state(id, block_hash, block_number, is_successful, error_log)
+VALUES (1, '', 0, false, NULL);
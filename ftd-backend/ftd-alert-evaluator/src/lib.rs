@@ -0,0 +1,185 @@
+use async_trait::async_trait;
+use ftd_config::Config;
+use ftd_persistence::relational::RelationalStorage;
+use ftd_service::Service;
+use ftd_types::api::alert::AlertDeliveryStatus;
+use lazy_static::lazy_static;
+use webhook::WebhookClient;
+
+mod metrics;
+mod webhook;
+
+lazy_static! {
+    static ref CONFIG: Config = Config::default();
+}
+
+const DELIVERY_BATCH_SIZE: u16 = 100;
+
+#[derive(Default)]
+pub struct AlertEvaluator;
+
+impl AlertEvaluator {
+    /// Saves an alert for every active watch entry that matches the transfer. Watch entries
+    /// created after the transfer are not evaluated against it. An alert that cannot be saved
+    /// does not hold up the other watch entries.
+    async fn evaluate_transfer(&self, storage: &RelationalStorage, id: i32) -> anyhow::Result<()> {
+        let Some(transfer) = storage.get_api_transfer_by_id(id).await? else {
+            log::warn!("Transfer id {id} not found.");
+            return Ok(());
+        };
+        let addresses = [transfer.from_address.clone(), transfer.to_address.clone()];
+        for watch_entry in storage
+            .get_active_watch_entries_by_addresses(&addresses, transfer.timestamp)
+            .await?
+        {
+            if !watch_entry
+                .direction
+                .matches(&watch_entry.address, &transfer)
+                || transfer.amount < watch_entry.threshold
+            {
+                continue;
+            }
+            let delivery_status = if watch_entry.webhook_url.is_some() {
+                AlertDeliveryStatus::Pending
+            } else {
+                AlertDeliveryStatus::Skipped
+            };
+            match storage
+                .save_alert(watch_entry.id as i32, id, delivery_status)
+                .await
+            {
+                Ok(Some(alert_id)) => {
+                    log::info!(
+                        "Transfer {id} triggered alert {alert_id} for watch entry {}.",
+                        watch_entry.id
+                    );
+                    metrics::alert_count().inc();
+                }
+                Ok(None) => (),
+                Err(error) => log::error!(
+                    "Error while saving the alert of transfer {id} for watch entry {}: {error:?}",
+                    watch_entry.id
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// Delivers an alert. A failed delivery is retried with exponential backoff until the
+    /// maximum number of attempts is reached.
+    async fn deliver_alert(
+        &self,
+        storage: &RelationalStorage,
+        webhook_client: &WebhookClient,
+        alert_id: i32,
+        webhook_url: &str,
+        webhook_secret: &str,
+    ) -> anyhow::Result<()> {
+        let Some(alert) = storage.get_alert_by_id(alert_id).await? else {
+            return Ok(());
+        };
+        match webhook_client
+            .deliver(webhook_url, webhook_secret, &alert)
+            .await
+        {
+            Ok(()) => {
+                log::info!("Delivered alert {alert_id}.");
+                storage.set_alert_delivered(alert_id).await?;
+                metrics::delivery_success_count().inc();
+            }
+            Err(error) => {
+                metrics::delivery_failure_count().inc();
+                let attempt_count = alert.delivery_attempt_count + 1;
+                let maybe_retry_delay_seconds =
+                    if attempt_count < CONFIG.alert_evaluator.max_delivery_attempts {
+                        Some(
+                            CONFIG.alert_evaluator.retry_base_delay_seconds
+                                * 2u64.pow(attempt_count - 1),
+                        )
+                    } else {
+                        None
+                    };
+                log::warn!(
+                    "Delivery attempt {attempt_count} of alert {alert_id} failed: {error:?}"
+                );
+                storage
+                    .set_alert_delivery_failed(
+                        alert_id,
+                        &format!("{error:?}"),
+                        maybe_retry_delay_seconds,
+                    )
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Delivers the pending alerts that are due. An alert that cannot be delivered or updated
+    /// does not hold up the others.
+    async fn deliver_alerts(
+        &self,
+        storage: &RelationalStorage,
+        webhook_client: &WebhookClient,
+    ) -> anyhow::Result<()> {
+        for (alert_id, webhook_url, webhook_secret) in storage
+            .get_due_alert_deliveries(DELIVERY_BATCH_SIZE)
+            .await?
+        {
+            if let Err(error) = self
+                .deliver_alert(
+                    storage,
+                    webhook_client,
+                    alert_id,
+                    &webhook_url,
+                    &webhook_secret,
+                )
+                .await
+            {
+                log::error!("Error while delivering alert {alert_id}: {error:?}");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait(? Send)]
+impl Service for AlertEvaluator {
+    fn get_metrics_server_addr() -> (&'static str, u16) {
+        (
+            CONFIG.metrics.host.as_str(),
+            CONFIG.metrics.alert_evaluator_port,
+        )
+    }
+
+    async fn run(&'static self) -> anyhow::Result<()> {
+        log::info!("Alert evaluator started.");
+        let storage = RelationalStorage::new().await?;
+        let webhook_client = WebhookClient::new(&CONFIG)?;
+        let sleep_seconds = CONFIG.alert_evaluator.sleep_seconds;
+        loop {
+            let last_processed_id = storage
+                .get_alert_evaluator_last_processed_transfer_id()
+                .await?;
+            let start_id = last_processed_id + 1;
+            let max_id = storage.get_max_transfer_id().await?;
+            if max_id >= start_id {
+                log::info!("Evaluate transfers {start_id}-{max_id}.");
+                for id in start_id..=max_id {
+                    // a transfer that cannot be evaluated is skipped so that it does not stop
+                    // the evaluation of the following transfers
+                    if let Err(error) = self.evaluate_transfer(&storage, id).await {
+                        log::error!("Error while evaluating transfer {id}: {error:?}");
+                    }
+                    storage
+                        .set_alert_evaluator_last_processed_transfer_id(id)
+                        .await?;
+                    metrics::processed_transfer_id().set(id as i64);
+                }
+            }
+            if let Err(error) = self.deliver_alerts(&storage, &webhook_client).await {
+                log::error!("Error while delivering alerts: {error:?}");
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(sleep_seconds)).await;
+        }
+    }
+}
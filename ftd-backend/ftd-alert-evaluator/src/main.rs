@@ -0,0 +1,12 @@
+use ftd_alert_evaluator::AlertEvaluator;
+use ftd_service::Service;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref SERVICE: AlertEvaluator = AlertEvaluator;
+}
+
+#[tokio::main]
+async fn main() {
+    SERVICE.start().await;
+}
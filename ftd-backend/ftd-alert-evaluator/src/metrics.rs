@@ -0,0 +1,52 @@
+use ftd_metrics::registry::{IntCounter, IntGauge};
+use once_cell::sync::Lazy;
+
+const METRIC_PREFIX: &str = "ftd_alert_evaluator";
+
+pub fn processed_transfer_id() -> IntGauge {
+    static METER: Lazy<IntGauge> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_gauge(
+            METRIC_PREFIX,
+            "processed_transfer_id",
+            "Id of the last evaluated transfer",
+        )
+        .unwrap()
+    });
+    METER.clone()
+}
+
+pub fn alert_count() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_counter(
+            METRIC_PREFIX,
+            "alert_count",
+            "The number of alerts triggered",
+        )
+        .unwrap()
+    });
+    METER.clone()
+}
+
+pub fn delivery_success_count() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_counter(
+            METRIC_PREFIX,
+            "delivery_success_count",
+            "The number of successful webhook deliveries",
+        )
+        .unwrap()
+    });
+    METER.clone()
+}
+
+pub fn delivery_failure_count() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_counter(
+            METRIC_PREFIX,
+            "delivery_failure_count",
+            "The number of failed webhook delivery attempts",
+        )
+        .unwrap()
+    });
+    METER.clone()
+}
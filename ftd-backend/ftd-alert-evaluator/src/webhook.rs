@@ -0,0 +1,56 @@
+use ftd_config::Config;
+use ftd_types::api::alert::Alert;
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use sha2::Sha256;
+
+/// Header that carries the hex-encoded HMAC-SHA256 of the request body, keyed with the
+/// webhook secret of the watch entry, in the `sha256=<hex>` format.
+const SIGNATURE_HEADER: &str = "X-FTD-Signature";
+const ALERT_ID_HEADER: &str = "X-FTD-Alert-Id";
+
+pub(crate) struct WebhookClient {
+    http_client: Client,
+}
+
+impl WebhookClient {
+    pub(crate) fn new(config: &Config) -> anyhow::Result<Self> {
+        Ok(Self {
+            http_client: Client::builder()
+                .timeout(std::time::Duration::from_secs(
+                    config.alert_evaluator.webhook_timeout_seconds,
+                ))
+                .build()?,
+        })
+    }
+
+    fn sign(secret: &str, body: &[u8]) -> anyhow::Result<String> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())?;
+        mac.update(body);
+        Ok(format!(
+            "sha256={}",
+            hex::encode(mac.finalize().into_bytes())
+        ))
+    }
+
+    /// Posts the alert as JSON, signed with the secret. Any non-2xx response is an error.
+    pub(crate) async fn deliver(
+        &self,
+        url: &str,
+        secret: &str,
+        alert: &Alert,
+    ) -> anyhow::Result<()> {
+        let body = serde_json::to_vec(alert)?;
+        let signature = Self::sign(secret, &body)?;
+        self.http_client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature)
+            .header(ALERT_ID_HEADER, alert.id.to_string())
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
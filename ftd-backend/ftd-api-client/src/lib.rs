@@ -0,0 +1,315 @@
+//! Typed client for the FTD HTTP API. Requests and responses use the `ftd_types::api` types
+//! that the API service's OpenAPI document (`/openapi.json`) is generated from.
+#![warn(clippy::disallowed_types)]
+use ftd_types::api::account::{Account, AccountDetails, AccountGraph};
+use ftd_types::api::alert::{Alert, CreatedWatchEntry, NewWatchEntry, WatchEntry};
+use ftd_types::api::identity::{HistoricalIdentity, IdentityChange, Registrar};
+use ftd_types::api::label::{Label, LabelCategory, NewLabel};
+use ftd_types::api::transfer::Transfer;
+use ftd_types::err::{ServiceError, ServiceErrorCode};
+use reqwest::{Client, RequestBuilder};
+use serde::de::DeserializeOwned;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+#[derive(thiserror::Error, Clone, Debug)]
+pub enum APIClientError {
+    #[error("API error {status} {:?}: {}", error.code, error.description)]
+    Service { status: u16, error: ServiceError },
+}
+
+/// Non-JSON account graph export formats.
+#[derive(Clone, Copy, Debug)]
+pub enum GraphExportFormat {
+    GraphML,
+    Gexf,
+    NodesCsv,
+    EdgesCsv,
+}
+
+impl Display for GraphExportFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let format = match self {
+            Self::GraphML => "graphml",
+            Self::Gexf => "gexf",
+            Self::NodesCsv => "csv-nodes",
+            Self::EdgesCsv => "csv-edges",
+        };
+        write!(f, "{format}")
+    }
+}
+
+/// Header for the API key, required by all endpoints when the service requires API keys.
+const API_KEY_HEADER: &str = "X-FTD-API-Key";
+/// Header for the admin key, required by the label, watchlist and alert endpoints.
+const ADMIN_KEY_HEADER: &str = "X-FTD-Admin-Key";
+
+pub struct APIClient {
+    base_url: String,
+    http_client: Client,
+    maybe_api_key: Option<String>,
+    maybe_admin_key: Option<String>,
+}
+
+impl APIClient {
+    pub fn new(base_url: &str, request_timeout_seconds: u64) -> anyhow::Result<Self> {
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http_client: Client::builder()
+                .gzip(true)
+                .brotli(true)
+                .timeout(Duration::from_secs(request_timeout_seconds))
+                .build()?,
+            maybe_api_key: None,
+            maybe_admin_key: None,
+        })
+    }
+
+    /// Sets the API key sent with every request.
+    pub fn with_api_key(mut self, api_key: &str) -> Self {
+        self.maybe_api_key = Some(api_key.to_string());
+        self
+    }
+
+    /// Sets the admin key sent to the admin endpoints.
+    pub fn with_admin_key(mut self, admin_key: &str) -> Self {
+        self.maybe_admin_key = Some(admin_key.to_string());
+        self
+    }
+
+    fn with_admin_key_header(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.maybe_admin_key {
+            Some(admin_key) => request.header(ADMIN_KEY_HEADER, admin_key),
+            None => request,
+        }
+    }
+
+    async fn send(&self, request: RequestBuilder) -> anyhow::Result<reqwest::Response> {
+        let request = match &self.maybe_api_key {
+            Some(api_key) => request.header(API_KEY_HEADER, api_key),
+            None => request,
+        };
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let error = response.json::<ServiceError>().await.unwrap_or_else(|_| {
+            ServiceError::new(ServiceErrorCode::InternalError, status.as_str())
+        });
+        Err(APIClientError::Service {
+            status: status.as_u16(),
+            error,
+        }
+        .into())
+    }
+
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> anyhow::Result<T> {
+        let request = self
+            .http_client
+            .get(format!("{}{path}", self.base_url))
+            .query(query);
+        Ok(self.send(request).await?.json::<T>().await?)
+    }
+
+    /// `at` is an optional block number or RFC 3339 timestamp for the account balances.
+    pub async fn search_accounts(
+        &self,
+        sanitized_query: &str,
+        original_query: &str,
+        maybe_at: Option<&str>,
+    ) -> anyhow::Result<Vec<Account>> {
+        let mut query = vec![
+            ("sanitizedQuery", sanitized_query),
+            ("originalQuery", original_query),
+        ];
+        if let Some(at) = maybe_at {
+            query.push(("at", at));
+        }
+        self.get_json("/account", &query).await
+    }
+
+    pub async fn get_account_details(
+        &self,
+        address: &str,
+        maybe_at: Option<&str>,
+    ) -> anyhow::Result<AccountDetails> {
+        let query: Vec<(&str, &str)> = maybe_at.map(|at| ("at", at)).into_iter().collect();
+        self.get_json(&format!("/account/{address}"), &query).await
+    }
+
+    /// `include_relations` adds the proxy, multisig and staking relations of the account.
+    pub async fn get_account_graph(
+        &self,
+        address: &str,
+        maybe_at: Option<&str>,
+        include_relations: bool,
+    ) -> anyhow::Result<AccountGraph> {
+        let mut query: Vec<(&str, &str)> = maybe_at.map(|at| ("at", at)).into_iter().collect();
+        if include_relations {
+            query.push(("relations", "true"));
+        }
+        self.get_json(&format!("/account/{address}/graph"), &query)
+            .await
+    }
+
+    pub async fn get_identity_timeline(
+        &self,
+        address: &str,
+    ) -> anyhow::Result<Vec<IdentityChange>> {
+        self.get_json(&format!("/account/{address}/identity/timeline"), &[])
+            .await
+    }
+
+    /// `at` is an optional People chain block number or RFC 3339 timestamp.
+    pub async fn get_historical_identity(
+        &self,
+        address: &str,
+        maybe_at: Option<&str>,
+    ) -> anyhow::Result<HistoricalIdentity> {
+        let query: Vec<(&str, &str)> = maybe_at.map(|at| ("at", at)).into_iter().collect();
+        self.get_json(&format!("/account/{address}/identity"), &query)
+            .await
+    }
+
+    pub async fn get_registrars(&self) -> anyhow::Result<Vec<Registrar>> {
+        self.get_json("/identity/registrar", &[]).await
+    }
+
+    /// Returns the account graph as a GraphML, GEXF or CSV document.
+    pub async fn export_account_graph(
+        &self,
+        address: &str,
+        format: GraphExportFormat,
+        maybe_at: Option<&str>,
+        include_relations: bool,
+    ) -> anyhow::Result<String> {
+        let format = format.to_string();
+        let mut query = vec![("format", format.as_str())];
+        if let Some(at) = maybe_at {
+            query.push(("at", at));
+        }
+        if include_relations {
+            query.push(("relations", "true"));
+        }
+        let request = self
+            .http_client
+            .get(format!("{}/account/{address}/graph", self.base_url))
+            .query(&query);
+        Ok(self.send(request).await?.text().await?)
+    }
+
+    pub async fn get_transfers(&self, from: &str, to: &str) -> anyhow::Result<Vec<Transfer>> {
+        self.get_json("/transfer", &[("from", from), ("to", to)])
+            .await
+    }
+
+    pub async fn get_watch_entries(&self) -> anyhow::Result<Vec<WatchEntry>> {
+        let request = self
+            .with_admin_key_header(self.http_client.get(format!("{}/watchlist", self.base_url)));
+        Ok(self.send(request).await?.json::<Vec<WatchEntry>>().await?)
+    }
+
+    pub async fn create_watch_entry(
+        &self,
+        new_watch_entry: &NewWatchEntry,
+    ) -> anyhow::Result<CreatedWatchEntry> {
+        let request = self.with_admin_key_header(
+            self.http_client
+                .post(format!("{}/watchlist", self.base_url))
+                .json(new_watch_entry),
+        );
+        Ok(self
+            .send(request)
+            .await?
+            .json::<CreatedWatchEntry>()
+            .await?)
+    }
+
+    pub async fn delete_watch_entry(&self, id: u32) -> anyhow::Result<()> {
+        let request = self.with_admin_key_header(
+            self.http_client
+                .delete(format!("{}/watchlist/{id}", self.base_url)),
+        );
+        self.send(request).await?;
+        Ok(())
+    }
+
+    /// Alert history, latest first. `before_id` is the id of the last alert of the previous
+    /// page.
+    pub async fn get_alerts(
+        &self,
+        maybe_watch_entry_id: Option<u32>,
+        maybe_address: Option<&str>,
+        maybe_before_id: Option<u32>,
+        maybe_limit: Option<u16>,
+    ) -> anyhow::Result<Vec<Alert>> {
+        let watch_entry_id = maybe_watch_entry_id.map(|id| id.to_string());
+        let before_id = maybe_before_id.map(|id| id.to_string());
+        let limit = maybe_limit.map(|limit| limit.to_string());
+        let mut query = Vec::new();
+        if let Some(watch_entry_id) = watch_entry_id.as_deref() {
+            query.push(("watchEntryId", watch_entry_id));
+        }
+        if let Some(address) = maybe_address {
+            query.push(("address", address));
+        }
+        if let Some(before_id) = before_id.as_deref() {
+            query.push(("beforeId", before_id));
+        }
+        if let Some(limit) = limit.as_deref() {
+            query.push(("limit", limit));
+        }
+        let request = self.with_admin_key_header(
+            self.http_client
+                .get(format!("{}/alert", self.base_url))
+                .query(&query),
+        );
+        Ok(self.send(request).await?.json::<Vec<Alert>>().await?)
+    }
+
+    pub async fn get_labels(
+        &self,
+        maybe_category: Option<LabelCategory>,
+        maybe_source: Option<&str>,
+    ) -> anyhow::Result<Vec<Label>> {
+        let category = maybe_category.map(|category| category.to_string());
+        let mut query = Vec::new();
+        if let Some(category) = category.as_deref() {
+            query.push(("category", category));
+        }
+        if let Some(source) = maybe_source {
+            query.push(("source", source));
+        }
+        self.get_json("/label", &query).await
+    }
+
+    pub async fn save_label(&self, new_label: &NewLabel) -> anyhow::Result<Label> {
+        let request = self.with_admin_key_header(
+            self.http_client
+                .post(format!("{}/label", self.base_url))
+                .json(new_label),
+        );
+        Ok(self.send(request).await?.json::<Label>().await?)
+    }
+
+    pub async fn delete_label(&self, id: u32) -> anyhow::Result<()> {
+        let request = self.with_admin_key_header(
+            self.http_client
+                .delete(format!("{}/label/{id}", self.base_url)),
+        );
+        self.send(request).await?;
+        Ok(())
+    }
+
+    pub async fn get_openapi_json(&self) -> anyhow::Result<String> {
+        let request = self
+            .http_client
+            .get(format!("{}/openapi.json", self.base_url));
+        Ok(self.send(request).await?.text().await?)
+    }
+}
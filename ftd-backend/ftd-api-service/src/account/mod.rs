@@ -1,244 +1,352 @@
-use crate::{ResultResponse, ServiceState, CONFIG};
-use actix_web::{get, web, HttpResponse};
-use ftd_substrate_client::SubstrateClient;
-use ftd_types::api::account::{Account, AccountGraph};
-use ftd_types::err::ServiceError;
+use crate::address::{normalize_address, parse_address, validate_ss58_prefix, with_ss58_prefix};
+use crate::auth::check_premium_scope;
+use crate::block::{resolve_at_parameter, ResolvedBlock};
+use crate::cache::CachedResponse;
+use crate::export::{get_account_graph_response, GraphExportFormat};
+use crate::{substrate, ResultResponse, ServiceState, CONFIG};
+use actix_web::{get, web, HttpRequest, HttpResponse};
+use ftd_persistence::ACCOUNT_SEARCH_MAX_RESULT_COUNT;
+use ftd_service::err::APIError;
+use ftd_types::api::account::{Account, AccountDetails, AccountGraph};
+use ftd_types::err::{ServiceError, SubscanError};
 use ftd_types::substrate::account_id::AccountId;
-use ftd_types::substrate::chain::Chain;
 use rustc_hash::FxHashSet as HashSet;
 use serde::Deserialize;
 use std::str::FromStr;
+use utoipa::IntoParams;
 
-async fn set_account_balances(accounts: &mut [Account]) -> anyhow::Result<()> {
-    let substrate_client = SubstrateClient::new(
-        &CONFIG.substrate.rpc_url,
-        CONFIG.substrate.connection_timeout_seconds,
-        CONFIG.substrate.request_timeout_seconds,
-    )
-    .await?;
+/// Maximum number of Subscan accounts fetched from Subscan for a graph response.
+const GRAPH_SUBSCAN_FETCH_LIMIT: usize = 3;
+
+async fn set_account_balances(
+    state: &ServiceState,
+    accounts: &mut [Account],
+    maybe_block: Option<&ResolvedBlock>,
+) -> anyhow::Result<()> {
     let account_ids: Vec<AccountId> = accounts
         .iter()
         .map(|account| AccountId::from_str(account.address.as_str()).unwrap())
         .collect();
-    let balance_map = substrate_client.get_balances(&account_ids, None).await?;
-    // get balances
+    let balance_map = substrate::get_balances(
+        &state.substrate_client,
+        &state.balance_cache,
+        &account_ids,
+        maybe_block,
+    )
+    .await?;
+    for (account, account_id) in accounts.iter_mut().zip(account_ids.iter()) {
+        account.balance = balance_map.get(account_id).cloned().flatten();
+    }
+    Ok(())
+}
+
+async fn set_account_labels(state: &ServiceState, accounts: &mut [Account]) -> anyhow::Result<()> {
+    let addresses: Vec<String> = accounts
+        .iter()
+        .map(|account| account.address.clone())
+        .collect();
+    let labels = state
+        .relational_storage
+        .get_labels_by_addresses(&addresses)
+        .await?;
     for account in accounts.iter_mut() {
-        let account_id = AccountId::from_str(account.address.as_str()).unwrap();
-        account.balance = balance_map.get(&account_id).cloned();
+        account.labels = labels
+            .iter()
+            .filter(|label| label.address == account.address)
+            .cloned()
+            .collect();
     }
     Ok(())
 }
 
-#[derive(Deserialize)]
+/// Gets the accounts with their identities and Subscan accounts in a constant number of
+/// queries. Subscan accounts missing in the database are fetched from Subscan for at most
+/// `max_subscan_fetch_count` accounts. A failed fetch leaves the Subscan account empty, except
+/// for an exceeded Subscan quota, which fails the call.
+async fn get_accounts(
+    state: &ServiceState,
+    addresses: &[String],
+    max_subscan_fetch_count: usize,
+) -> anyhow::Result<Vec<Account>> {
+    let mut accounts = state
+        .relational_storage
+        .get_accounts_by_addresses(addresses)
+        .await?;
+    for account in accounts
+        .iter_mut()
+        .filter(|account| account.subscan_account.is_none())
+        .take(max_subscan_fetch_count)
+    {
+        let address = account.address.as_str();
+        match state.subscan_client.get_account(address).await {
+            Ok(subscan_account_search_result) => {
+                state
+                    .relational_storage
+                    .save_subscan_account(&subscan_account_search_result.data.account)
+                    .await?;
+                account.subscan_account = Some(subscan_account_search_result.data.account);
+            }
+            Err(error) => {
+                if let Some(SubscanError::QuotaExceeded) = error.downcast_ref::<SubscanError>() {
+                    return Err(error);
+                }
+                log::error!("Error while getting Subscan account {address}: {error:?}");
+            }
+        }
+    }
+    Ok(accounts)
+}
+
+/// Fails with invalid parameter if the search page ends after the last ranked result.
+pub(crate) fn validate_search_page(offset: u32, limit: u16) -> Result<(), APIError> {
+    if offset as u64 + limit as u64 > ACCOUNT_SEARCH_MAX_RESULT_COUNT as u64 {
+        return Err(APIError::InvalidParameter(format!(
+            "Offset plus limit should be at most {ACCOUNT_SEARCH_MAX_RESULT_COUNT}."
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Deserialize, IntoParams)]
 #[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
 pub(crate) struct AccountSearchParameters {
+    /// Search query, trimmed and stripped of special characters by the client.
     sanitized_query: String,
+    /// Search query as entered by the user, used for the address search.
     original_query: String,
+    /// Block number or RFC 3339 timestamp for the account balances.
+    at: Option<String>,
+    /// SS58 prefix of the addresses in the response, the prefix of the chain by default.
+    ss58_prefix: Option<u16>,
+    /// Number of ranked results to skip, for pagination. The offset plus the limit is at most
+    /// 500.
+    offset: Option<u32>,
+    /// Maximum number of results, at most the configured account search limit.
+    limit: Option<u16>,
 }
 
+#[utoipa::path(
+    tag = "account",
+    params(AccountSearchParameters),
+    responses(
+        (status = 200, description = "Accounts matching the query, most relevant first. Identity, sub-identity, Subscan and Merkle Science names, labels and addresses are ranked by trigram similarity, with a boost for the exact matches and the confirmed identities.", body = Vec<Account>),
+        (status = 304, description = "Not modified since the response with the `If-None-Match` ETag."),
+        (status = 400, description = "Invalid query, page, block reference or SS58 prefix.", body = ServiceError),
+        (status = 404, description = "Block at `at` not found.", body = ServiceError),
+        (status = 500, description = "Internal server error.", body = ServiceError),
+    ),
+)]
 #[get("/account")]
 pub(crate) async fn account_search_service(
+    request: HttpRequest,
     query: web::Query<AccountSearchParameters>,
     state: web::Data<ServiceState>,
 ) -> ResultResponse {
     let sanitized_query = query.sanitized_query.trim().to_string();
     if sanitized_query.is_empty() {
-        return Ok(
-            HttpResponse::BadRequest().json(ServiceError::from("Query should not be empty."))
-        );
+        return Err(APIError::InvalidParameter(
+            "Query should not be empty.".to_string(),
+        ));
     }
-    let limit = if sanitized_query.len() < 5 {
-        CONFIG.api.account_search_limit / 2
-    } else {
-        CONFIG.api.account_search_limit
-    };
-    // search by display
-    let mut accounts: Vec<Account> = state
+    validate_ss58_prefix(query.ss58_prefix)?;
+    let offset = query.offset.unwrap_or(0);
+    let limit = query
+        .limit
+        .unwrap_or(CONFIG.api.account_search_limit)
+        .clamp(1, CONFIG.api.account_search_limit);
+    validate_search_page(offset, limit)?;
+    let cache_key = format!(
+        "search:{sanitized_query}:{}:{}:{:?}:{offset}:{limit}",
+        query.original_query,
+        query.at.as_deref().unwrap_or_default(),
+        query.ss58_prefix,
+    );
+    if let Some(cached_response) = state.response_cache.get(&cache_key) {
+        return Ok(cached_response.respond(&request));
+    }
+    let maybe_block = resolve_at_parameter(&state, query.at.as_deref()).await?;
+    // full address of any SS58 prefix or account id hex is converted to the chain's format
+    let address_query =
+        normalize_address(query.original_query.as_str()).unwrap_or_else(|| sanitized_query.clone());
+    let addresses: Vec<String> = state
         .relational_storage
-        .search_identities(sanitized_query.as_str(), limit)
+        .search_accounts_ranked(
+            sanitized_query.as_str(),
+            address_query.as_str(),
+            limit,
+            offset,
+        )
         .await?
-        .iter()
-        .map(|identity| Account {
-            address: identity.address.clone(),
-            identity: Some(identity.clone()),
-            sub_identity: None,
-            super_identity: None,
-            balance: None,
-            subscan_account: None,
-        })
+        .into_iter()
+        .map(|(address, _)| address)
         .collect();
-    // get sub accounts
-    if (accounts.len() as u16) < limit {
-        for i in 0..accounts.len() {
-            let account = accounts.get(i).unwrap();
-            let mut sub_accounts = Vec::new();
-            if let Some(super_identity) = &account.identity {
-                state
-                    .relational_storage
-                    .get_sub_identities(account.address.as_str())
-                    .await?
-                    .iter()
-                    .for_each(|sub_identity| {
-                        sub_accounts.push(Account {
-                            address: sub_identity.address.clone(),
-                            identity: None,
-                            sub_identity: Some(sub_identity.clone()),
-                            super_identity: Some(super_identity.clone()),
-                            balance: None,
-                            subscan_account: None,
-                        })
-                    });
-            }
-            accounts.append(&mut sub_accounts);
-            if (accounts.len() as u16) < CONFIG.api.account_search_limit {
-                break;
-            }
-        }
-    }
-    // search by sub display
-    if (accounts.len() as u16) < limit {
-        let limit = limit - (accounts.len() as u16);
-        let sub_identities = state
-            .relational_storage
-            .search_sub_identities(sanitized_query.as_str(), limit)
-            .await?;
-        // get super identities
-        for sub_identity in sub_identities.iter() {
-            if accounts
-                .iter()
-                .any(|account| account.address == sub_identity.address)
-            {
-                continue;
-            }
-            let super_identity = state
-                .relational_storage
-                .get_identity_by_address(sub_identity.super_address.as_str())
-                .await?;
-            accounts.push(Account {
-                address: sub_identity.address.clone(),
-                identity: None,
-                sub_identity: Some(sub_identity.clone()),
-                super_identity,
-                balance: None,
-                subscan_account: None,
-            });
-        }
-    }
-    // search by address
-    if (accounts.len() as u16) < limit {
-        let limit = limit - (accounts.len() as u16);
-        // if full non-Polkadot address, then convert to Polkadot address
-        let original_query = query.original_query.as_str();
-        let query = if let Ok(account_id) = AccountId::from_ss58_check(original_query) {
-            let polkadot = Chain::Polkadot;
-            let format = polkadot.get_ss58_address_format();
-            account_id.to_ss58_check_with_version(format.prefix())
-        } else {
-            sanitized_query
-        };
-        let addresses = state
-            .relational_storage
-            .search_addresses(query.as_str(), limit)
-            .await?;
-        addresses.iter().for_each(|address| {
-            if !accounts
-                .iter()
-                .any(|account| account.address.as_str() == address)
-            {
-                accounts.push(Account {
-                    address: address.clone(),
-                    identity: None,
-                    sub_identity: None,
-                    super_identity: None,
-                    balance: None,
-                    subscan_account: None,
-                });
-            }
-        })
-    }
-    set_account_balances(&mut accounts).await?;
-    Ok(HttpResponse::Ok().json(accounts))
+    let mut accounts = get_accounts(&state, &addresses, 0).await?;
+    set_account_balances(&state, &mut accounts, maybe_block.as_ref()).await?;
+    set_account_labels(&state, &mut accounts).await?;
+    let accounts = with_ss58_prefix(accounts, query.ss58_prefix);
+    let cached_response = state.response_cache.insert(
+        &cache_key,
+        CachedResponse::json(&accounts)?,
+        addresses.into_iter().collect(),
+    );
+    Ok(cached_response.respond(&request))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub(crate) struct AccountDetailsParameters {
+    /// SS58 address or account id hex.
+    address: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AccountDetailsQueryParameters {
+    /// Block number or RFC 3339 timestamp for the account balance.
+    at: Option<String>,
+    /// SS58 prefix of the addresses in the response, the prefix of the chain by default.
+    ss58_prefix: Option<u16>,
+}
+
+#[utoipa::path(
+    tag = "account",
+    params(AccountDetailsParameters, AccountDetailsQueryParameters),
+    responses(
+        (status = 200, description = "Account details and transfer statistics.", body = AccountDetails),
+        (status = 400, description = "Invalid address, block reference or SS58 prefix.", body = ServiceError),
+        (status = 404, description = "Block at `at` not found.", body = ServiceError),
+        (status = 500, description = "Internal server error.", body = ServiceError),
+    ),
+)]
+#[get("/account/{address}")]
+pub(crate) async fn account_details_service(
+    path: web::Path<AccountDetailsParameters>,
+    query: web::Query<AccountDetailsQueryParameters>,
+    state: web::Data<ServiceState>,
+) -> ResultResponse {
+    let address = parse_address(path.address.as_str(), "address")?;
+    validate_ss58_prefix(query.ss58_prefix)?;
+    let maybe_block = resolve_at_parameter(&state, query.at.as_deref()).await?;
+    let mut accounts = get_accounts(&state, std::slice::from_ref(&address), 1).await?;
+    set_account_balances(&state, &mut accounts, maybe_block.as_ref()).await?;
+    set_account_labels(&state, &mut accounts).await?;
+    let account = accounts.remove(0);
+    let stats = state
+        .relational_storage
+        .get_account_stats(address.as_str())
+        .await?;
+    Ok(HttpResponse::Ok().json(with_ss58_prefix(
+        AccountDetails { account, stats },
+        query.ss58_prefix,
+    )))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
 pub(crate) struct AccountGraphParameters {
+    /// SS58 address or account id hex.
     address: String,
 }
 
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AccountGraphQueryParameters {
+    /// One of `json`, `graphml`, `gexf`, `csv-nodes` or `csv-edges`. Takes precedence over
+    /// the `Accept` header.
+    format: Option<String>,
+    /// Block number or RFC 3339 timestamp for the account balances.
+    at: Option<String>,
+    /// SS58 prefix of the addresses in the response, the prefix of the chain by default.
+    ss58_prefix: Option<u16>,
+    /// Whether to include the proxy, multisig and staking relations of the account and the
+    /// related accounts. `false` by default.
+    relations: Option<bool>,
+}
+
+#[utoipa::path(
+    tag = "account",
+    params(AccountGraphParameters, AccountGraphQueryParameters),
+    responses(
+        (status = 200, description = "Transfer graph around the account, with its `PROXY_OF`, `MULTISIG_MEMBER`, `CONTROLLER_OF` and `PAYEE_OF` relations when requested.", content(
+            (AccountGraph = "application/json"),
+            (String = "application/graphml+xml"),
+            (String = "application/gexf+xml"),
+            (String = "text/csv"),
+        )),
+        (status = 304, description = "Not modified since the response with the `If-None-Match` ETag."),
+        (status = 400, description = "Invalid address, format, block reference or SS58 prefix.", body = ServiceError),
+        (status = 403, description = "Export formats other than JSON require a premium API key.", body = ServiceError),
+        (status = 404, description = "Block at `at` not found.", body = ServiceError),
+        (status = 500, description = "Internal server error.", body = ServiceError),
+    ),
+)]
 #[get("/account/{address}/graph")]
 pub(crate) async fn account_graph_service(
+    request: HttpRequest,
     path: web::Path<AccountGraphParameters>,
+    query: web::Query<AccountGraphQueryParameters>,
     state: web::Data<ServiceState>,
 ) -> ResultResponse {
-    if AccountId::from_str(path.address.as_str()).is_err() {
-        return Ok(HttpResponse::BadRequest().json(ServiceError::from("Invalid address.")));
+    let address = parse_address(path.address.as_str(), "address")?;
+    validate_ss58_prefix(query.ss58_prefix)?;
+    let format = GraphExportFormat::from_request(query.format.as_deref(), request.headers())
+        .map_err(APIError::InvalidParameter)?;
+    if format != GraphExportFormat::Json {
+        check_premium_scope(&request)?;
+    }
+    let include_relations = query.relations.unwrap_or(false);
+    let cache_key = format!(
+        "graph:{address}:{format:?}:{}:{:?}:{include_relations}",
+        query.at.as_deref().unwrap_or_default(),
+        query.ss58_prefix,
+    );
+    if let Some(cached_response) = state.response_cache.get(&cache_key) {
+        return Ok(cached_response.respond(&request));
     }
+    let maybe_block = resolve_at_parameter(&state, query.at.as_deref()).await?;
     let transfer_volumes = state
         .graph_storage
-        .get_transfer_volumes_for_account(path.address.as_str(), CONFIG.api.graph_search_limit)
+        .get_transfer_volumes_for_account(address.as_str(), CONFIG.api.graph_search_limit)
         .await?;
+    let relations = if include_relations {
+        state
+            .graph_storage
+            .get_account_relations_for_account(address.as_str(), CONFIG.api.graph_search_limit)
+            .await?
+    } else {
+        Vec::new()
+    };
     let mut addresses = HashSet::default();
     transfer_volumes.iter().for_each(|transfer_volume| {
         addresses.insert(transfer_volume.from.clone());
         addresses.insert(transfer_volume.to.clone());
     });
-    let mut accounts = Vec::new();
-    let mut fetched_subscan_account_count = 0;
-    for address in addresses.iter() {
-        let identity = state
-            .relational_storage
-            .get_identity_by_address(address)
-            .await?;
-        let sub_identity = state
-            .relational_storage
-            .get_sub_identity_by_address(address)
-            .await?;
-        let super_identity = if let Some(sub_identity) = &sub_identity {
-            state
-                .relational_storage
-                .get_identity_by_address(sub_identity.super_address.as_str())
-                .await?
-        } else {
-            None
-        };
-
-        let subscan_account = if let Some(subscan_account) = state
-            .relational_storage
-            .get_subscan_account_by_address(address)
-            .await?
-        {
-            Some(subscan_account)
-        } else if fetched_subscan_account_count < 3 {
-            match state.subscan_client.get_account(address).await {
-                Ok(subscan_account_search_result) => {
-                    state
-                        .relational_storage
-                        .save_subscan_account(&subscan_account_search_result.data.account)
-                        .await?;
-                    fetched_subscan_account_count += 1;
-                    Some(subscan_account_search_result.data.account)
-                }
-                Err(error) => {
-                    log::error!("Error while getting Subscan account {address}: {error:?}");
-                    fetched_subscan_account_count += 1;
-                    None
-                }
-            }
-        } else {
-            None
-        };
-        accounts.push(Account {
-            address: address.to_string(),
-            identity,
-            sub_identity,
-            super_identity,
-            balance: None,
-            subscan_account,
-        })
-    }
-    set_account_balances(&mut accounts).await?;
-    Ok(HttpResponse::Ok().json(AccountGraph {
-        accounts,
-        transfer_volumes,
-    }))
+    relations.iter().for_each(|relation| {
+        addresses.insert(relation.from.clone());
+        addresses.insert(relation.to.clone());
+    });
+    let addresses: Vec<String> = addresses.into_iter().collect();
+    let mut accounts = get_accounts(&state, &addresses, GRAPH_SUBSCAN_FETCH_LIMIT).await?;
+    set_account_balances(&state, &mut accounts, maybe_block.as_ref()).await?;
+    set_account_labels(&state, &mut accounts).await?;
+    let cached_response = state.response_cache.insert(
+        &cache_key,
+        get_account_graph_response(
+            &with_ss58_prefix(
+                AccountGraph {
+                    accounts,
+                    transfer_volumes,
+                    relations,
+                },
+                query.ss58_prefix,
+            ),
+            format,
+            path.address.as_str(),
+        )?,
+        addresses.into_iter().collect(),
+    );
+    Ok(cached_response.respond(&request))
 }
@@ -0,0 +1,181 @@
+use crate::CONFIG;
+use ftd_service::err::APIError;
+use ftd_types::api::account::{Account, AccountDetails, AccountGraph};
+use ftd_types::api::identity::{
+    HistoricalIdentity, Identity, IdentityChange, Registrar, SubIdentity,
+};
+use ftd_types::api::label::Label;
+use ftd_types::api::relation::AccountRelation;
+use ftd_types::api::transfer::Transfer;
+use ftd_types::graph::TransferVolume;
+use ftd_types::subscan::SubscanAccount;
+use ftd_types::substrate::account_id::AccountId;
+use ftd_types::substrate::chain::Chain;
+use lazy_static::lazy_static;
+use std::str::FromStr;
+
+/// SS58 prefixes are 14-bit.
+const MAX_SS58_PREFIX: u16 = 16_383;
+
+lazy_static! {
+    pub(crate) static ref CHAIN: Chain = Chain::from_str(&CONFIG.substrate.chain).unwrap();
+}
+
+/// Parses an SS58 address of any prefix or an account id hex, and encodes it in the SS58
+/// format of the configured chain, the format of the stored addresses.
+pub(crate) fn normalize_address(address: &str) -> Option<String> {
+    CHAIN.normalize_address(address)
+}
+
+/// `normalize_address` for the request parameters, with the parameter name in the error.
+pub(crate) fn parse_address(address: &str, name: &str) -> Result<String, APIError> {
+    normalize_address(address)
+        .ok_or_else(|| APIError::InvalidAddress(format!("Invalid {name} {address}.")))
+}
+
+/// Validates the `ss58Prefix` query parameter.
+pub(crate) fn validate_ss58_prefix(maybe_ss58_prefix: Option<u16>) -> Result<(), APIError> {
+    match maybe_ss58_prefix {
+        Some(ss58_prefix) if ss58_prefix > MAX_SS58_PREFIX => Err(APIError::InvalidParameter(
+            format!("SS58 prefix should be at most {MAX_SS58_PREFIX}, got {ss58_prefix}."),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn encode_address(address: &mut String, ss58_prefix: u16) {
+    if let Ok(account_id) = AccountId::from_str(address) {
+        *address = account_id.to_ss58_check_with_version(ss58_prefix);
+    }
+}
+
+/// Response objects whose addresses can be rendered in a requested SS58 format.
+pub(crate) trait EncodeAddresses {
+    fn encode_addresses(&mut self, ss58_prefix: u16);
+}
+
+/// Renders the addresses of the response in the requested SS58 format, if any.
+pub(crate) fn with_ss58_prefix<T: EncodeAddresses>(
+    mut value: T,
+    maybe_ss58_prefix: Option<u16>,
+) -> T {
+    if let Some(ss58_prefix) = maybe_ss58_prefix {
+        value.encode_addresses(ss58_prefix);
+    }
+    value
+}
+
+impl<T: EncodeAddresses> EncodeAddresses for Vec<T> {
+    fn encode_addresses(&mut self, ss58_prefix: u16) {
+        self.iter_mut()
+            .for_each(|value| value.encode_addresses(ss58_prefix));
+    }
+}
+
+impl<T: EncodeAddresses> EncodeAddresses for Option<T> {
+    fn encode_addresses(&mut self, ss58_prefix: u16) {
+        if let Some(value) = self {
+            value.encode_addresses(ss58_prefix);
+        }
+    }
+}
+
+impl EncodeAddresses for Identity {
+    fn encode_addresses(&mut self, ss58_prefix: u16) {
+        encode_address(&mut self.address, ss58_prefix);
+    }
+}
+
+impl EncodeAddresses for SubIdentity {
+    fn encode_addresses(&mut self, ss58_prefix: u16) {
+        encode_address(&mut self.address, ss58_prefix);
+        encode_address(&mut self.super_address, ss58_prefix);
+    }
+}
+
+impl EncodeAddresses for IdentityChange {
+    fn encode_addresses(&mut self, ss58_prefix: u16) {
+        encode_address(&mut self.address, ss58_prefix);
+        self.identity.encode_addresses(ss58_prefix);
+        self.sub_identity.encode_addresses(ss58_prefix);
+    }
+}
+
+impl EncodeAddresses for HistoricalIdentity {
+    fn encode_addresses(&mut self, ss58_prefix: u16) {
+        encode_address(&mut self.address, ss58_prefix);
+        self.identity.encode_addresses(ss58_prefix);
+        self.sub_identity.encode_addresses(ss58_prefix);
+    }
+}
+
+impl EncodeAddresses for Registrar {
+    fn encode_addresses(&mut self, ss58_prefix: u16) {
+        encode_address(&mut self.address, ss58_prefix);
+    }
+}
+
+impl EncodeAddresses for SubscanAccount {
+    fn encode_addresses(&mut self, ss58_prefix: u16) {
+        encode_address(&mut self.address, ss58_prefix);
+        encode_address(&mut self.account_display.address, ss58_prefix);
+    }
+}
+
+impl EncodeAddresses for Label {
+    fn encode_addresses(&mut self, ss58_prefix: u16) {
+        encode_address(&mut self.address, ss58_prefix);
+    }
+}
+
+impl EncodeAddresses for Account {
+    fn encode_addresses(&mut self, ss58_prefix: u16) {
+        encode_address(&mut self.address, ss58_prefix);
+        self.identity.encode_addresses(ss58_prefix);
+        self.sub_identity.encode_addresses(ss58_prefix);
+        self.super_identity.encode_addresses(ss58_prefix);
+        self.subscan_account.encode_addresses(ss58_prefix);
+        self.labels.encode_addresses(ss58_prefix);
+    }
+}
+
+impl EncodeAddresses for Transfer {
+    fn encode_addresses(&mut self, ss58_prefix: u16) {
+        encode_address(&mut self.from_address, ss58_prefix);
+        encode_address(&mut self.to_address, ss58_prefix);
+    }
+}
+
+impl EncodeAddresses for TransferVolume {
+    fn encode_addresses(&mut self, ss58_prefix: u16) {
+        encode_address(&mut self.from, ss58_prefix);
+        encode_address(&mut self.to, ss58_prefix);
+    }
+}
+
+impl EncodeAddresses for AccountGraph {
+    fn encode_addresses(&mut self, ss58_prefix: u16) {
+        self.accounts.encode_addresses(ss58_prefix);
+        self.transfer_volumes.encode_addresses(ss58_prefix);
+        self.relations.encode_addresses(ss58_prefix);
+    }
+}
+
+impl EncodeAddresses for AccountRelation {
+    fn encode_addresses(&mut self, ss58_prefix: u16) {
+        encode_address(&mut self.from, ss58_prefix);
+        encode_address(&mut self.to, ss58_prefix);
+    }
+}
+
+impl EncodeAddresses for AccountDetails {
+    fn encode_addresses(&mut self, ss58_prefix: u16) {
+        self.account.encode_addresses(ss58_prefix);
+        self.stats
+            .largest_incoming_transfer
+            .encode_addresses(ss58_prefix);
+        self.stats
+            .largest_outgoing_transfer
+            .encode_addresses(ss58_prefix);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::address::parse_address;
+use crate::auth::check_admin_key;
+use crate::{ResultResponse, ServiceState};
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse};
+use ftd_service::err::APIError;
+use ftd_types::api::alert::{Alert, CreatedWatchEntry, NewWatchEntry, WatchEntry};
+use ftd_types::err::ServiceError;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+const DEFAULT_ALERT_LIMIT: u16 = 50;
+const MAX_ALERT_LIMIT: u16 = 500;
+
+#[utoipa::path(
+    tag = "alert",
+    params(("X-FTD-Admin-Key" = String, Header, description = "Admin key.")),
+    responses(
+        (status = 200, description = "All watch entries.", body = Vec<WatchEntry>),
+        (status = 401, description = "Invalid admin key.", body = ServiceError),
+        (status = 500, description = "Internal server error.", body = ServiceError),
+    ),
+)]
+#[get("/watchlist")]
+pub(crate) async fn watch_entry_list_service(
+    request: HttpRequest,
+    state: web::Data<ServiceState>,
+) -> ResultResponse {
+    check_admin_key(&request)?;
+    Ok(HttpResponse::Ok().json(state.relational_storage.get_watch_entries().await?))
+}
+
+#[utoipa::path(
+    tag = "alert",
+    request_body = NewWatchEntry,
+    params(("X-FTD-Admin-Key" = String, Header, description = "Admin key.")),
+    responses(
+        (status = 201, description = "Created watch entry, the only response that contains the webhook secret.", body = CreatedWatchEntry),
+        (status = 400, description = "Invalid address or webhook URL.", body = ServiceError),
+        (status = 401, description = "Invalid admin key.", body = ServiceError),
+        (status = 500, description = "Internal server error.", body = ServiceError),
+    ),
+)]
+#[post("/watchlist")]
+pub(crate) async fn watch_entry_create_service(
+    request: HttpRequest,
+    new_watch_entry: web::Json<NewWatchEntry>,
+    state: web::Data<ServiceState>,
+) -> ResultResponse {
+    check_admin_key(&request)?;
+    let address = parse_address(new_watch_entry.address.as_str(), "address")?;
+    if let Some(webhook_url) = &new_watch_entry.webhook_url {
+        if !webhook_url.starts_with("https://") && !webhook_url.starts_with("http://") {
+            return Err(APIError::InvalidParameter(
+                "Webhook URL should be an HTTP(S) URL.".to_string(),
+            ));
+        }
+    }
+    let webhook_secret = new_watch_entry
+        .webhook_url
+        .as_ref()
+        .map(|_| hex::encode(rand::random::<[u8; 32]>()));
+    let watch_entry = state
+        .relational_storage
+        .save_watch_entry(
+            address.as_str(),
+            new_watch_entry.direction,
+            new_watch_entry.threshold,
+            new_watch_entry.label.as_deref(),
+            new_watch_entry.webhook_url.as_deref(),
+            webhook_secret.as_deref(),
+        )
+        .await?;
+    Ok(HttpResponse::Created().json(CreatedWatchEntry {
+        watch_entry,
+        webhook_secret,
+    }))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub(crate) struct WatchEntryParameters {
+    /// Watch entry id.
+    id: i32,
+}
+
+#[utoipa::path(
+    tag = "alert",
+    params(
+        WatchEntryParameters,
+        ("X-FTD-Admin-Key" = String, Header, description = "Admin key."),
+    ),
+    responses(
+        (status = 204, description = "Watch entry and its alerts deleted."),
+        (status = 401, description = "Invalid admin key.", body = ServiceError),
+        (status = 404, description = "Watch entry not found.", body = ServiceError),
+        (status = 500, description = "Internal server error.", body = ServiceError),
+    ),
+)]
+#[delete("/watchlist/{id}")]
+pub(crate) async fn watch_entry_delete_service(
+    request: HttpRequest,
+    path: web::Path<WatchEntryParameters>,
+    state: web::Data<ServiceState>,
+) -> ResultResponse {
+    check_admin_key(&request)?;
+    if state.relational_storage.delete_watch_entry(path.id).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(APIError::NotFound("Watch entry not found.".to_string()))
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub(crate) struct AlertListQueryParameters {
+    /// Only the alerts of this watch entry.
+    watch_entry_id: Option<i32>,
+    /// Only the alerts of the watch entries for this SS58 address or account id hex.
+    address: Option<String>,
+    /// Only the alerts older than this alert id, for pagination.
+    before_id: Option<i32>,
+    /// Maximum number of alerts, 50 by default and at most 500.
+    limit: Option<u16>,
+}
+
+#[utoipa::path(
+    tag = "alert",
+    params(
+        AlertListQueryParameters,
+        ("X-FTD-Admin-Key" = String, Header, description = "Admin key."),
+    ),
+    responses(
+        (status = 200, description = "Alert history, latest first.", body = Vec<Alert>),
+        (status = 400, description = "Invalid address.", body = ServiceError),
+        (status = 401, description = "Invalid admin key.", body = ServiceError),
+        (status = 500, description = "Internal server error.", body = ServiceError),
+    ),
+)]
+#[get("/alert")]
+pub(crate) async fn alert_list_service(
+    request: HttpRequest,
+    query: web::Query<AlertListQueryParameters>,
+    state: web::Data<ServiceState>,
+) -> ResultResponse {
+    check_admin_key(&request)?;
+    let maybe_address = query
+        .address
+        .as_deref()
+        .map(|address| parse_address(address, "address"))
+        .transpose()?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_ALERT_LIMIT)
+        .clamp(1, MAX_ALERT_LIMIT);
+    Ok(HttpResponse::Ok().json(
+        state
+            .relational_storage
+            .get_alerts(
+                query.watch_entry_id,
+                maybe_address.as_deref(),
+                query.before_id,
+                limit,
+            )
+            .await?,
+    ))
+}
@@ -0,0 +1,122 @@
+use crate::auth::{check_admin_key, generate_api_key, get_api_key_prefix};
+use crate::{ResultResponse, ServiceState, CONFIG};
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse};
+use ftd_service::err::APIError;
+use ftd_types::api::api_key::{APIKey, CreatedAPIKey, NewAPIKey};
+use ftd_types::err::ServiceError;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[utoipa::path(
+    tag = "api_key",
+    params(("X-FTD-Admin-Key" = String, Header, description = "Admin key.")),
+    responses(
+        (status = 200, description = "All API keys.", body = Vec<APIKey>),
+        (status = 401, description = "Invalid admin key.", body = ServiceError),
+        (status = 500, description = "Internal server error.", body = ServiceError),
+    ),
+)]
+#[get("/api-key")]
+pub(crate) async fn api_key_list_service(
+    request: HttpRequest,
+    state: web::Data<ServiceState>,
+) -> ResultResponse {
+    check_admin_key(&request)?;
+    Ok(HttpResponse::Ok().json(state.relational_storage.get_api_keys().await?))
+}
+
+#[utoipa::path(
+    tag = "api_key",
+    request_body = NewAPIKey,
+    params(("X-FTD-Admin-Key" = String, Header, description = "Admin key.")),
+    responses(
+        (status = 201, description = "Created API key, the only response that contains the key.", body = CreatedAPIKey),
+        (status = 400, description = "Invalid name or rate limit.", body = ServiceError),
+        (status = 401, description = "Invalid admin key.", body = ServiceError),
+        (status = 500, description = "Internal server error.", body = ServiceError),
+    ),
+)]
+#[post("/api-key")]
+pub(crate) async fn api_key_create_service(
+    request: HttpRequest,
+    new_api_key: web::Json<NewAPIKey>,
+    state: web::Data<ServiceState>,
+) -> ResultResponse {
+    check_admin_key(&request)?;
+    let name = new_api_key.name.trim();
+    if name.is_empty() {
+        return Err(APIError::InvalidParameter(
+            "API key name should not be empty.".to_string(),
+        ));
+    }
+    let rate_limit_per_minute = new_api_key
+        .rate_limit_per_minute
+        .unwrap_or(CONFIG.api.default_rate_limit_per_minute);
+    let burst = new_api_key
+        .burst
+        .unwrap_or(CONFIG.api.default_rate_limit_burst);
+    // stored as INTEGER
+    let max_rate_limit = i32::MAX as u32;
+    if !(1..=max_rate_limit).contains(&rate_limit_per_minute)
+        || !(1..=max_rate_limit).contains(&burst)
+    {
+        return Err(APIError::InvalidParameter(format!(
+            "Rate limit and burst should be between 1 and {max_rate_limit}."
+        )));
+    }
+    let (key, key_hash) = generate_api_key();
+    let api_key = state
+        .relational_storage
+        .save_api_key(
+            name,
+            &key_hash,
+            &get_api_key_prefix(&key),
+            new_api_key.scope,
+            rate_limit_per_minute,
+            burst,
+        )
+        .await?;
+    state
+        .api_key_store
+        .reload(&state.relational_storage)
+        .await?;
+    Ok(HttpResponse::Created().json(CreatedAPIKey { api_key, key }))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub(crate) struct APIKeyParameters {
+    /// API key id.
+    id: i32,
+}
+
+#[utoipa::path(
+    tag = "api_key",
+    params(
+        APIKeyParameters,
+        ("X-FTD-Admin-Key" = String, Header, description = "Admin key."),
+    ),
+    responses(
+        (status = 204, description = "API key revoked."),
+        (status = 401, description = "Invalid admin key.", body = ServiceError),
+        (status = 404, description = "API key not found.", body = ServiceError),
+        (status = 500, description = "Internal server error.", body = ServiceError),
+    ),
+)]
+#[delete("/api-key/{id}")]
+pub(crate) async fn api_key_delete_service(
+    request: HttpRequest,
+    path: web::Path<APIKeyParameters>,
+    state: web::Data<ServiceState>,
+) -> ResultResponse {
+    check_admin_key(&request)?;
+    if state.relational_storage.delete_api_key(path.id).await? {
+        state
+            .api_key_store
+            .reload(&state.relational_storage)
+            .await?;
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(APIError::NotFound("API key not found.".to_string()))
+    }
+}
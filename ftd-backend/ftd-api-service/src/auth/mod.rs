@@ -0,0 +1,231 @@
+use crate::{ServiceState, CONFIG};
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::{web, HttpMessage, HttpRequest, ResponseError};
+use ftd_persistence::relational::RelationalStorage;
+use ftd_service::err::APIError;
+use ftd_types::api::api_key::{APIKey, APIKeyScope};
+use rustc_hash::FxHashMap as HashMap;
+use sha2::{Digest, Sha256};
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+
+/// Header that carries the admin key for the label, watchlist, alert and API key endpoints.
+pub(crate) const ADMIN_KEY_HEADER: &str = "X-FTD-Admin-Key";
+/// Header that carries the API key.
+pub(crate) const API_KEY_HEADER: &str = "X-FTD-API-Key";
+/// Metrics label for the requests without an API key.
+pub(crate) const ANONYMOUS_METRICS_LABEL: &str = "anonymous";
+const API_KEY_PREFIX_LENGTH: usize = 12;
+
+/// Compares in constant time to avoid leaking the key through response timing.
+fn is_equal(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b.iter()).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn has_admin_key(request: &HttpRequest) -> bool {
+    let admin_key = CONFIG.api.admin_key.as_str();
+    !admin_key.is_empty()
+        && request
+            .headers()
+            .get(ADMIN_KEY_HEADER)
+            .map(|value| is_equal(value.as_bytes(), admin_key.as_bytes()))
+            .unwrap_or(false)
+}
+
+/// Fails with unauthorized if the request doesn't carry the configured admin key. Admin
+/// endpoints are disabled when no admin key is configured.
+pub(crate) fn check_admin_key(request: &HttpRequest) -> Result<(), APIError> {
+    if has_admin_key(request) {
+        Ok(())
+    } else {
+        Err(APIError::Unauthorized("Invalid admin key.".to_string()))
+    }
+}
+
+/// Fails with forbidden unless the request is made with a premium API key or the admin key.
+/// Requests without an API key have the standard scope.
+pub(crate) fn check_premium_scope(request: &HttpRequest) -> Result<(), APIError> {
+    match request.extensions().get::<RequestAPIKey>() {
+        Some(request_api_key) if request_api_key.0.scope == APIKeyScope::Premium => Ok(()),
+        _ if has_admin_key(request) => Ok(()),
+        _ => Err(APIError::Forbidden(
+            "This endpoint requires a premium API key.".to_string(),
+        )),
+    }
+}
+
+/// Returns a new random key and its hash.
+pub(crate) fn generate_api_key() -> (String, String) {
+    let key = format!("ftd_{}", hex::encode(rand::random::<[u8; 32]>()));
+    let key_hash = hash_api_key(&key);
+    (key, key_hash)
+}
+
+pub(crate) fn get_api_key_prefix(key: &str) -> String {
+    key.chars().take(API_KEY_PREFIX_LENGTH).collect()
+}
+
+fn hash_api_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+/// The API key of the request, stored in the request extensions by `authenticate`.
+#[derive(Clone)]
+pub(crate) struct RequestAPIKey(pub(crate) Arc<APIKey>);
+
+/// Requests are rate limited per API key, or per client IP address without an API key.
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+enum RateLimitKey {
+    APIKey(u32),
+    Anonymous(IpAddr),
+}
+
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// Active API keys by hash, reloaded from the database periodically, and the rate limit token
+/// buckets of the API keys and the anonymous clients.
+#[derive(Default)]
+pub(crate) struct APIKeyStore {
+    api_keys: RwLock<HashMap<String, Arc<APIKey>>>,
+    token_buckets: Mutex<HashMap<RateLimitKey, TokenBucket>>,
+}
+
+impl APIKeyStore {
+    pub(crate) async fn reload(
+        &self,
+        relational_storage: &RelationalStorage,
+    ) -> anyhow::Result<()> {
+        let api_keys: HashMap<String, Arc<APIKey>> = relational_storage
+            .get_active_api_keys_with_hashes()
+            .await?
+            .into_iter()
+            .map(|(key_hash, api_key)| (key_hash, Arc::new(api_key)))
+            .collect();
+        // an anonymous bucket that has had the time to refill is the same as a new one
+        let anonymous_refill_seconds = CONFIG.api.default_rate_limit_burst as f64 * 60.0
+            / CONFIG.api.default_rate_limit_per_minute as f64;
+        let mut token_buckets = self.token_buckets.lock().unwrap();
+        token_buckets.retain(|rate_limit_key, token_bucket| match rate_limit_key {
+            RateLimitKey::APIKey(id) => api_keys.values().any(|api_key| api_key.id == *id),
+            RateLimitKey::Anonymous(_) => {
+                token_bucket.updated_at.elapsed().as_secs_f64() < anonymous_refill_seconds
+            }
+        });
+        *self.api_keys.write().unwrap() = api_keys;
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> Option<Arc<APIKey>> {
+        self.api_keys
+            .read()
+            .unwrap()
+            .get(&hash_api_key(key))
+            .cloned()
+    }
+
+    /// Takes a token from the bucket of the key, which refills at the given rate limit up to
+    /// the burst size. Returns the seconds until the next token if the bucket is empty.
+    fn take_token(
+        &self,
+        rate_limit_key: RateLimitKey,
+        rate_limit_per_minute: u32,
+        burst: u32,
+    ) -> Result<(), u64> {
+        let refill_per_second = rate_limit_per_minute as f64 / 60.0;
+        let capacity = burst as f64;
+        let mut token_buckets = self.token_buckets.lock().unwrap();
+        let token_bucket = token_buckets.entry(rate_limit_key).or_insert(TokenBucket {
+            tokens: capacity,
+            updated_at: Instant::now(),
+        });
+        let now = Instant::now();
+        token_bucket.tokens = (token_bucket.tokens
+            + now.duration_since(token_bucket.updated_at).as_secs_f64() * refill_per_second)
+            .min(capacity);
+        token_bucket.updated_at = now;
+        if token_bucket.tokens >= 1.0 {
+            token_bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(((1.0 - token_bucket.tokens) / refill_per_second).ceil() as u64)
+        }
+    }
+}
+
+/// Endpoints that don't need an API key.
+fn is_public(request: &ServiceRequest) -> bool {
+    let path = request.path();
+    request.method() == Method::OPTIONS
+        || path == "/openapi.json"
+        || (path == "/graphql" && request.method() == Method::GET)
+}
+
+/// Middleware that resolves the API key of the request and applies its rate limit. Requests
+/// without an API key, if allowed, are rate limited per client IP address with the default
+/// rate limit. Requests with the admin key and the public endpoints are let through.
+pub(crate) async fn authenticate(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, actix_web::Error> {
+    if is_public(&request) || has_admin_key(request.request()) {
+        return Ok(next.call(request).await?.map_into_left_body());
+    }
+    let Some(state) = request.app_data::<web::Data<ServiceState>>() else {
+        return Ok(next.call(request).await?.map_into_left_body());
+    };
+    let api_key_store = state.api_key_store.clone();
+    let maybe_key = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok());
+    let result = match maybe_key {
+        None if !CONFIG.api.api_key_required => match request.peer_addr() {
+            Some(peer_addr) => api_key_store
+                .take_token(
+                    RateLimitKey::Anonymous(peer_addr.ip()),
+                    CONFIG.api.default_rate_limit_per_minute,
+                    CONFIG.api.default_rate_limit_burst,
+                )
+                .map_err(|retry_after_seconds| APIError::RateLimited {
+                    retry_after_seconds,
+                }),
+            None => Err(APIError::Unauthorized("API key required.".to_string())),
+        },
+        None => Err(APIError::Unauthorized("API key required.".to_string())),
+        Some(key) => match api_key_store.get(key) {
+            None => Err(APIError::Unauthorized("Invalid API key.".to_string())),
+            Some(api_key) => {
+                let result = api_key_store.take_token(
+                    RateLimitKey::APIKey(api_key.id),
+                    api_key.rate_limit_per_minute,
+                    api_key.burst,
+                );
+                request.extensions_mut().insert(RequestAPIKey(api_key));
+                result.map_err(|retry_after_seconds| APIError::RateLimited {
+                    retry_after_seconds,
+                })
+            }
+        },
+    };
+    match result {
+        Ok(()) => Ok(next.call(request).await?.map_into_left_body()),
+        Err(error) => Ok(request
+            .into_response(error.error_response())
+            .map_into_right_body()),
+    }
+}
+
+/// Metrics label of the API key of the request.
+pub(crate) fn get_metrics_label(request: &HttpRequest) -> String {
+    match request.extensions().get::<RequestAPIKey>() {
+        Some(request_api_key) => request_api_key.0.name.clone(),
+        None => ANONYMOUS_METRICS_LABEL.to_string(),
+    }
+}
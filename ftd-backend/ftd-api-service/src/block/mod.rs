@@ -0,0 +1,85 @@
+use crate::ServiceState;
+use ftd_service::err::APIError;
+use std::str::FromStr;
+
+/// Point in chain history given in the `at` query parameter, either as a block number or
+/// as an RFC 3339 timestamp such as `2024-05-01T12:00:00Z`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum BlockReference {
+    Number(u64),
+    Timestamp(u64),
+}
+
+impl FromStr for BlockReference {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(block_number) = s.parse::<u64>() {
+            return Ok(Self::Number(block_number));
+        }
+        match chrono::DateTime::parse_from_rfc3339(s) {
+            Ok(date_time) if date_time.timestamp_millis() >= 0 => {
+                Ok(Self::Timestamp(date_time.timestamp_millis() as u64))
+            }
+            _ => Err(format!(
+                "Invalid block reference {s}. Expected a block number or an RFC 3339 timestamp."
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ResolvedBlock {
+    pub hash: String,
+    pub number: u64,
+}
+
+/// Resolves a block reference to a block hash through the indexed blocks. Block numbers
+/// outside the indexed range are resolved through the Substrate node.
+pub(crate) async fn resolve_block(
+    state: &ServiceState,
+    reference: BlockReference,
+) -> anyhow::Result<Option<ResolvedBlock>> {
+    let maybe_hash_and_number = match reference {
+        BlockReference::Number(number) => {
+            if let Some(hash) = state.relational_storage.get_block_hash(number).await? {
+                Some((hash, number))
+            } else {
+                let substrate_client = state.substrate_client.get_client().await?;
+                match substrate_client.get_block_hash(number).await {
+                    Ok(hash) => Some((hash, number)),
+                    Err(error) => {
+                        log::warn!("Cannot get hash of block {number}: {error:?}");
+                        None
+                    }
+                }
+            }
+        }
+        BlockReference::Timestamp(timestamp) => {
+            state
+                .relational_storage
+                .get_block_hash_and_number_at_timestamp(timestamp)
+                .await?
+        }
+    };
+    Ok(maybe_hash_and_number.map(|(hash, number)| ResolvedBlock {
+        hash: format!("0x{}", hash.trim_start_matches("0x").to_lowercase()),
+        number,
+    }))
+}
+
+/// Parses and resolves the optional `at` query parameter.
+pub(crate) async fn resolve_at_parameter(
+    state: &ServiceState,
+    maybe_at: Option<&str>,
+) -> Result<Option<ResolvedBlock>, APIError> {
+    let Some(at) = maybe_at else {
+        return Ok(None);
+    };
+    let reference = BlockReference::from_str(at).map_err(APIError::InvalidParameter)?;
+    match resolve_block(state, reference).await? {
+        Some(block) => Ok(Some(block)),
+        None => Err(APIError::NotFound(format!("Cannot find block at {at}."))),
+    }
+}
@@ -0,0 +1,257 @@
+use crate::{metrics, CONFIG};
+use actix_web::http::header;
+use actix_web::web::Bytes;
+use actix_web::{HttpRequest, HttpResponse};
+use ftd_persistence::graph::GraphStorage;
+use ftd_persistence::relational::RelationalStorage;
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The whole cache is cleared instead when the graph updater has processed more transfers than
+/// this since the last check.
+const MAX_INVALIDATION_TRANSFER_COUNT: i32 = 10_000;
+
+/// Response body with the headers needed to serve it again.
+pub(crate) struct CachedResponse {
+    body: Bytes,
+    content_type: String,
+    maybe_content_disposition: Option<String>,
+    etag: String,
+}
+
+impl CachedResponse {
+    pub(crate) fn new(
+        body: Bytes,
+        content_type: &str,
+        maybe_content_disposition: Option<String>,
+    ) -> Self {
+        let etag = format!("\"{}\"", hex::encode(&Sha256::digest(&body)[..16]));
+        Self {
+            body,
+            content_type: content_type.to_string(),
+            maybe_content_disposition,
+            etag,
+        }
+    }
+
+    pub(crate) fn json<T: Serialize>(value: &T) -> anyhow::Result<Self> {
+        Ok(Self::new(
+            Bytes::from(serde_json::to_vec(value)?),
+            "application/json",
+            None,
+        ))
+    }
+
+    /// Responds with 304 if the request's `If-None-Match` header matches the ETag.
+    pub(crate) fn respond(&self, request: &HttpRequest) -> HttpResponse {
+        let is_not_modified = request
+            .headers()
+            .get(header::IF_NONE_MATCH)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(|etag| etag.trim().trim_start_matches("W/"))
+                    .any(|etag| etag == "*" || etag == self.etag)
+            })
+            .unwrap_or(false);
+        if is_not_modified {
+            return HttpResponse::NotModified()
+                .insert_header((header::ETAG, self.etag.clone()))
+                .finish();
+        }
+        let mut response = HttpResponse::Ok();
+        response
+            .content_type(self.content_type.as_str())
+            .insert_header((header::ETAG, self.etag.clone()));
+        if let Some(content_disposition) = &self.maybe_content_disposition {
+            response.insert_header((header::CONTENT_DISPOSITION, content_disposition.clone()));
+        }
+        response.body(self.body.clone())
+    }
+}
+
+struct CacheEntry {
+    response: Arc<CachedResponse>,
+    /// Accounts in the response, the entry is invalidated when any of them has new transfers.
+    addresses: HashSet<String>,
+    inserted_at: Instant,
+    access_tick: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    /// Keys by access tick, least recently used first.
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl CacheState {
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.access_tick);
+        }
+    }
+}
+
+/// In-process LRU cache with TTL for the account search and graph responses.
+pub(crate) struct ResponseCache {
+    capacity: usize,
+    ttl: Duration,
+    state: Mutex<CacheState>,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<Arc<CachedResponse>> {
+        let mut state = self.state.lock().unwrap();
+        let is_expired = match state.entries.get(key) {
+            Some(entry) => entry.inserted_at.elapsed() >= self.ttl,
+            None => {
+                metrics::response_cache_miss_count().inc();
+                return None;
+            }
+        };
+        if is_expired {
+            state.remove(key);
+            metrics::response_cache_miss_count().inc();
+            return None;
+        }
+        state.tick += 1;
+        let tick = state.tick;
+        let entry = state.entries.get_mut(key).unwrap();
+        let previous_tick = std::mem::replace(&mut entry.access_tick, tick);
+        let response = entry.response.clone();
+        state.recency.remove(&previous_tick);
+        state.recency.insert(tick, key.to_string());
+        metrics::response_cache_hit_count().inc();
+        Some(response)
+    }
+
+    /// Caches the response, evicting the least recently used entry if the cache is full.
+    pub(crate) fn insert(
+        &self,
+        key: &str,
+        response: CachedResponse,
+        addresses: HashSet<String>,
+    ) -> Arc<CachedResponse> {
+        let response = Arc::new(response);
+        if self.capacity == 0 {
+            return response;
+        }
+        let mut state = self.state.lock().unwrap();
+        state.remove(key);
+        while state.entries.len() >= self.capacity {
+            let Some((_, evicted_key)) = state.recency.pop_first() else {
+                break;
+            };
+            state.entries.remove(&evicted_key);
+        }
+        state.tick += 1;
+        let tick = state.tick;
+        state.recency.insert(tick, key.to_string());
+        state.entries.insert(
+            key.to_string(),
+            CacheEntry {
+                response: response.clone(),
+                addresses,
+                inserted_at: Instant::now(),
+                access_tick: tick,
+            },
+        );
+        metrics::response_cache_entry_count().set(state.entries.len() as i64);
+        response
+    }
+
+    /// Removes the entries that contain any of the given accounts.
+    fn invalidate_addresses(&self, addresses: &HashSet<String>) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let keys: Vec<String> = state
+            .entries
+            .iter()
+            .filter(|(_, entry)| !entry.addresses.is_disjoint(addresses))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in keys.iter() {
+            state.remove(key);
+        }
+        metrics::response_cache_entry_count().set(state.entries.len() as i64);
+        keys.len()
+    }
+
+    fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.entries.clear();
+        state.recency.clear();
+        metrics::response_cache_entry_count().set(0);
+    }
+}
+
+/// Invalidates the cached responses of the accounts with new transfers whenever the graph
+/// updater advances.
+pub(crate) async fn invalidate_on_graph_updates(
+    relational_storage: Arc<RelationalStorage>,
+    graph_storage: Arc<GraphStorage>,
+    response_cache: Arc<ResponseCache>,
+) {
+    let mut maybe_last_transfer_id: Option<i32> = None;
+    loop {
+        match graph_storage.get_state().await {
+            Ok(graph_updater_state) => {
+                let transfer_id = graph_updater_state.last_processed_transfer_id;
+                match maybe_last_transfer_id {
+                    Some(last_transfer_id) if transfer_id <= last_transfer_id => (),
+                    Some(last_transfer_id)
+                        if transfer_id - last_transfer_id > MAX_INVALIDATION_TRANSFER_COUNT =>
+                    {
+                        log::info!("Clear the response cache after transfer {transfer_id}.");
+                        response_cache.clear();
+                    }
+                    Some(last_transfer_id) => {
+                        match relational_storage
+                            .get_transfer_addresses_in_id_range(last_transfer_id + 1, transfer_id)
+                            .await
+                        {
+                            Ok(addresses) => {
+                                let addresses: HashSet<String> = addresses.into_iter().collect();
+                                let invalidated_count =
+                                    response_cache.invalidate_addresses(&addresses);
+                                log::debug!(
+                                    "Invalidated {invalidated_count} cached responses for transfers {}-{transfer_id}.",
+                                    last_transfer_id + 1,
+                                );
+                            }
+                            Err(error) => {
+                                log::error!(
+                                    "Error while getting the transfer addresses for cache invalidation: {error:?}"
+                                );
+                                response_cache.clear();
+                            }
+                        }
+                    }
+                    None => (),
+                }
+                maybe_last_transfer_id = Some(transfer_id);
+            }
+            Err(error) => {
+                log::error!("Error while getting the graph updater state: {error:?}");
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(
+            CONFIG.api.response_cache_invalidation_seconds,
+        ))
+        .await;
+    }
+}
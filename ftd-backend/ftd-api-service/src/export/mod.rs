@@ -0,0 +1,512 @@
+use crate::cache::CachedResponse;
+use crate::CONFIG;
+use actix_web::http::header::{self, HeaderMap};
+use actix_web::web::Bytes;
+use ftd_types::api::account::{Account, AccountGraph};
+use ftd_types::api::label::Label;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum GraphExportFormat {
+    Json,
+    GraphML,
+    Gexf,
+    NodesCsv,
+    EdgesCsv,
+}
+
+impl GraphExportFormat {
+    /// The `format` query parameter takes precedence over the `Accept` header.
+    pub(crate) fn from_request(
+        maybe_format: Option<&str>,
+        headers: &HeaderMap,
+    ) -> Result<Self, String> {
+        if let Some(format) = maybe_format {
+            return match format.trim().to_lowercase().as_str() {
+                "json" => Ok(Self::Json),
+                "graphml" => Ok(Self::GraphML),
+                "gexf" => Ok(Self::Gexf),
+                "csv-nodes" => Ok(Self::NodesCsv),
+                "csv-edges" => Ok(Self::EdgesCsv),
+                _ => Err(format!(
+                    "Unknown format {format}. Expected one of json, graphml, gexf, csv-nodes, csv-edges."
+                )),
+            };
+        }
+        let accept = headers
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        for media_type in accept.split(',') {
+            let media_type = media_type.split(';').next().unwrap_or_default().trim();
+            match media_type {
+                "application/graphml+xml" => return Ok(Self::GraphML),
+                "application/gexf+xml" => return Ok(Self::Gexf),
+                "text/csv" => return Ok(Self::NodesCsv),
+                "application/json" | "*/*" => return Ok(Self::Json),
+                _ => (),
+            }
+        }
+        Ok(Self::Json)
+    }
+
+    fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::GraphML => "application/graphml+xml; charset=utf-8",
+            Self::Gexf => "application/gexf+xml; charset=utf-8",
+            Self::NodesCsv | Self::EdgesCsv => "text/csv; charset=utf-8",
+        }
+    }
+
+    fn file_extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::GraphML => "graphml",
+            Self::Gexf => "gexf",
+            Self::NodesCsv => "nodes.csv",
+            Self::EdgesCsv => "edges.csv",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum AttributeType {
+    String,
+    Boolean,
+    Long,
+    Double,
+}
+
+impl AttributeType {
+    /// Same type names are used in both GraphML and GEXF.
+    fn name(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Boolean => "boolean",
+            Self::Long => "long",
+            Self::Double => "double",
+        }
+    }
+}
+
+const NODE_ATTRIBUTES: &[(&str, AttributeType)] = &[
+    ("label", AttributeType::String),
+    ("identityDisplay", AttributeType::String),
+    ("identityConfirmed", AttributeType::Boolean),
+    ("subDisplay", AttributeType::String),
+    ("superDisplay", AttributeType::String),
+    ("subscanDisplay", AttributeType::String),
+    ("merkleScienceTagType", AttributeType::String),
+    ("merkleScienceTagSubtype", AttributeType::String),
+    ("merkleScienceTagName", AttributeType::String),
+    ("labels", AttributeType::String),
+    ("labelCategories", AttributeType::String),
+    ("balanceFree", AttributeType::Double),
+    ("balanceReserved", AttributeType::Double),
+    ("balanceFrozen", AttributeType::Double),
+];
+
+const EDGE_ATTRIBUTES: &[(&str, AttributeType)] = &[
+    ("count", AttributeType::Long),
+    ("volume", AttributeType::Double),
+    ("type", AttributeType::String),
+    ("proxyType", AttributeType::String),
+    ("proxyDelay", AttributeType::Long),
+    ("multisigThreshold", AttributeType::Long),
+];
+
+/// Transfer volume or account relation edge of the exported graph.
+struct Edge<'a> {
+    id: String,
+    source: &'a str,
+    target: &'a str,
+    /// Transfer volume in tokens.
+    maybe_weight: Option<f64>,
+    /// Values are aligned with `EDGE_ATTRIBUTES`.
+    attribute_values: Vec<Option<String>>,
+}
+
+/// Planck amount to token amount, to be used as numeric weights in graph tools.
+fn to_token_amount(amount: u128) -> f64 {
+    amount as f64 / 10f64.powi(CONFIG.substrate.token_decimals as i32)
+}
+
+fn get_account_label(account: &Account) -> String {
+    if let Some(display) = account
+        .identity
+        .as_ref()
+        .and_then(|identity| identity.display.clone())
+    {
+        return display;
+    }
+    if let Some(sub_identity) = &account.sub_identity {
+        let super_display = account
+            .super_identity
+            .as_ref()
+            .and_then(|identity| identity.display.clone());
+        match (super_display, sub_identity.sub_display.clone()) {
+            (Some(super_display), Some(sub_display)) => {
+                return format!("{super_display} / {sub_display}")
+            }
+            (Some(super_display), None) => return super_display,
+            (None, Some(sub_display)) => return sub_display,
+            (None, None) => (),
+        }
+    }
+    if let Some(label) = account.labels.first() {
+        return label.name.clone();
+    }
+    if let Some(display) = get_subscan_display(account) {
+        return display;
+    }
+    account.address.clone()
+}
+
+fn get_subscan_display(account: &Account) -> Option<String> {
+    let subscan_account = account.subscan_account.as_ref()?;
+    subscan_account
+        .account_display
+        .display
+        .clone()
+        .or_else(|| {
+            subscan_account
+                .account_display
+                .merkle
+                .as_ref()
+                .map(|merkle| merkle.tag_name.clone())
+        })
+        .or_else(|| subscan_account.display.clone())
+}
+
+/// Distinct values of the account's labels, separated by semicolons.
+fn get_label_values(account: &Account, get_value: fn(&Label) -> String) -> Option<String> {
+    let mut values: Vec<String> = Vec::new();
+    for value in account.labels.iter().map(get_value) {
+        if !values.contains(&value) {
+            values.push(value);
+        }
+    }
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.join("; "))
+    }
+}
+
+/// Values are aligned with `NODE_ATTRIBUTES`.
+fn get_node_attribute_values(account: &Account) -> Vec<Option<String>> {
+    let merkle = account
+        .subscan_account
+        .as_ref()
+        .and_then(|subscan_account| subscan_account.account_display.merkle.as_ref());
+    vec![
+        Some(get_account_label(account)),
+        account
+            .identity
+            .as_ref()
+            .and_then(|identity| identity.display.clone()),
+        account
+            .identity
+            .as_ref()
+            .map(|identity| identity.is_confirmed.to_string()),
+        account
+            .sub_identity
+            .as_ref()
+            .and_then(|sub_identity| sub_identity.sub_display.clone()),
+        account
+            .super_identity
+            .as_ref()
+            .and_then(|identity| identity.display.clone()),
+        get_subscan_display(account),
+        merkle.and_then(|merkle| merkle.tag_type.clone()),
+        merkle.and_then(|merkle| merkle.tag_subtype.clone()),
+        merkle.map(|merkle| merkle.tag_name.clone()),
+        get_label_values(account, |label| label.name.clone()),
+        get_label_values(account, |label| label.category.to_string()),
+        account
+            .balance
+            .as_ref()
+            .map(|balance| to_token_amount(balance.free).to_string()),
+        account
+            .balance
+            .as_ref()
+            .map(|balance| to_token_amount(balance.reserved).to_string()),
+        account
+            .balance
+            .as_ref()
+            .map(|balance| to_token_amount(balance.frozen).to_string()),
+    ]
+}
+
+/// Transfer volume edges followed by the relation edges. Relation edges have no numeric ids,
+/// their ids are built from their types and accounts.
+fn get_edges(graph: &AccountGraph) -> Vec<Edge<'_>> {
+    let mut edges: Vec<Edge> = graph
+        .transfer_volumes
+        .iter()
+        .map(|transfer_volume| Edge {
+            id: transfer_volume.id.to_string(),
+            source: &transfer_volume.from,
+            target: &transfer_volume.to,
+            maybe_weight: Some(to_token_amount(transfer_volume.volume)),
+            attribute_values: vec![
+                Some(transfer_volume.count.to_string()),
+                Some(to_token_amount(transfer_volume.volume).to_string()),
+                Some("TRANSFER".to_string()),
+                None,
+                None,
+                None,
+            ],
+        })
+        .collect();
+    for relation in graph.relations.iter() {
+        let edge_type = relation.relation_type.graph_edge_type();
+        let mut id = format!("{edge_type}:{}:{}", relation.from, relation.to);
+        if let Some(proxy_type) = &relation.proxy_type {
+            id.push(':');
+            id.push_str(proxy_type);
+        }
+        edges.push(Edge {
+            id,
+            source: &relation.from,
+            target: &relation.to,
+            maybe_weight: None,
+            attribute_values: vec![
+                None,
+                None,
+                Some(edge_type.to_string()),
+                relation.proxy_type.clone(),
+                relation.proxy_delay.map(|delay| delay.to_string()),
+                relation
+                    .multisig_threshold
+                    .map(|threshold| threshold.to_string()),
+            ],
+        });
+    }
+    edges
+}
+
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // characters not allowed in XML 1.0
+            c if (c as u32) < 0x20 && !matches!(c, '\t' | '\n' | '\r') => (),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_graphml(graph: &AccountGraph) -> String {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xsi:schemaLocation=\"http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd\">\n");
+    for (name, attribute_type) in NODE_ATTRIBUTES {
+        xml.push_str(&format!(
+            "  <key id=\"n_{name}\" for=\"node\" attr.name=\"{name}\" attr.type=\"{}\"/>\n",
+            attribute_type.name(),
+        ));
+    }
+    for (name, attribute_type) in EDGE_ATTRIBUTES {
+        xml.push_str(&format!(
+            "  <key id=\"e_{name}\" for=\"edge\" attr.name=\"{name}\" attr.type=\"{}\"/>\n",
+            attribute_type.name(),
+        ));
+    }
+    xml.push_str("  <graph id=\"G\" edgedefault=\"directed\">\n");
+    for account in graph.accounts.iter() {
+        xml.push_str(&format!(
+            "    <node id=\"{}\">\n",
+            xml_escape(&account.address)
+        ));
+        for ((name, _), value) in NODE_ATTRIBUTES
+            .iter()
+            .zip(get_node_attribute_values(account))
+        {
+            if let Some(value) = value {
+                xml.push_str(&format!(
+                    "      <data key=\"n_{name}\">{}</data>\n",
+                    xml_escape(&value)
+                ));
+            }
+        }
+        xml.push_str("    </node>\n");
+    }
+    for edge in get_edges(graph) {
+        xml.push_str(&format!(
+            "    <edge id=\"{}\" source=\"{}\" target=\"{}\">\n",
+            xml_escape(&edge.id),
+            xml_escape(edge.source),
+            xml_escape(edge.target),
+        ));
+        for ((name, _), value) in EDGE_ATTRIBUTES.iter().zip(edge.attribute_values) {
+            if let Some(value) = value {
+                xml.push_str(&format!(
+                    "      <data key=\"e_{name}\">{}</data>\n",
+                    xml_escape(&value)
+                ));
+            }
+        }
+        xml.push_str("    </edge>\n");
+    }
+    xml.push_str("  </graph>\n");
+    xml.push_str("</graphml>\n");
+    xml
+}
+
+fn to_gexf(graph: &AccountGraph) -> String {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">\n");
+    xml.push_str(&format!(
+        "  <meta><creator>followthedot.live</creator><description>{} transfer graph</description></meta>\n",
+        xml_escape(&CONFIG.substrate.chain_display),
+    ));
+    xml.push_str("  <graph mode=\"static\" defaultedgetype=\"directed\">\n");
+    xml.push_str("    <attributes class=\"node\">\n");
+    for (name, attribute_type) in NODE_ATTRIBUTES.iter().skip(1) {
+        xml.push_str(&format!(
+            "      <attribute id=\"{name}\" title=\"{name}\" type=\"{}\"/>\n",
+            attribute_type.name(),
+        ));
+    }
+    xml.push_str("    </attributes>\n");
+    xml.push_str("    <attributes class=\"edge\">\n");
+    for (name, attribute_type) in EDGE_ATTRIBUTES {
+        xml.push_str(&format!(
+            "      <attribute id=\"{name}\" title=\"{name}\" type=\"{}\"/>\n",
+            attribute_type.name(),
+        ));
+    }
+    xml.push_str("    </attributes>\n");
+    xml.push_str("    <nodes>\n");
+    for account in graph.accounts.iter() {
+        let values = get_node_attribute_values(account);
+        xml.push_str(&format!(
+            "      <node id=\"{}\" label=\"{}\">\n",
+            xml_escape(&account.address),
+            xml_escape(values[0].as_deref().unwrap_or(account.address.as_str())),
+        ));
+        xml.push_str("        <attvalues>\n");
+        // label is a node property in Gexf, not an attribute
+        for ((name, _), value) in NODE_ATTRIBUTES.iter().zip(values).skip(1) {
+            if let Some(value) = value {
+                xml.push_str(&format!(
+                    "          <attvalue for=\"{name}\" value=\"{}\"/>\n",
+                    xml_escape(&value)
+                ));
+            }
+        }
+        xml.push_str("        </attvalues>\n");
+        xml.push_str("      </node>\n");
+    }
+    xml.push_str("    </nodes>\n");
+    xml.push_str("    <edges>\n");
+    for edge in get_edges(graph) {
+        // relation edges have the default weight
+        let weight = edge
+            .maybe_weight
+            .map(|weight| format!(" weight=\"{weight}\""))
+            .unwrap_or_default();
+        xml.push_str(&format!(
+            "      <edge id=\"{}\" source=\"{}\" target=\"{}\"{weight}>\n",
+            xml_escape(&edge.id),
+            xml_escape(edge.source),
+            xml_escape(edge.target),
+        ));
+        xml.push_str("        <attvalues>\n");
+        for ((name, _), value) in EDGE_ATTRIBUTES.iter().zip(edge.attribute_values) {
+            if let Some(value) = value {
+                xml.push_str(&format!(
+                    "          <attvalue for=\"{name}\" value=\"{}\"/>\n",
+                    xml_escape(&value)
+                ));
+            }
+        }
+        xml.push_str("        </attvalues>\n");
+        xml.push_str("      </edge>\n");
+    }
+    xml.push_str("    </edges>\n");
+    xml.push_str("  </graph>\n");
+    xml.push_str("</gexf>\n");
+    xml
+}
+
+fn to_nodes_csv(graph: &AccountGraph) -> String {
+    let mut csv = String::from("id");
+    for (name, _) in NODE_ATTRIBUTES {
+        csv.push(',');
+        csv.push_str(name);
+    }
+    csv.push('\n');
+    for account in graph.accounts.iter() {
+        csv.push_str(&csv_escape(&account.address));
+        for value in get_node_attribute_values(account) {
+            csv.push(',');
+            csv.push_str(&csv_escape(value.as_deref().unwrap_or_default()));
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+fn to_edges_csv(graph: &AccountGraph) -> String {
+    let mut csv = String::from("id,source,target");
+    for (name, _) in EDGE_ATTRIBUTES {
+        csv.push(',');
+        csv.push_str(name);
+    }
+    csv.push('\n');
+    for edge in get_edges(graph) {
+        csv.push_str(&format!(
+            "{},{},{}",
+            csv_escape(&edge.id),
+            csv_escape(edge.source),
+            csv_escape(edge.target),
+        ));
+        for value in edge.attribute_values {
+            csv.push(',');
+            csv.push_str(&csv_escape(value.as_deref().unwrap_or_default()));
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Builds the response for an account graph in the requested format. `file_name` is used
+/// as the attachment name for the non-JSON formats.
+pub(crate) fn get_account_graph_response(
+    graph: &AccountGraph,
+    format: GraphExportFormat,
+    file_name: &str,
+) -> anyhow::Result<CachedResponse> {
+    let body = match format {
+        GraphExportFormat::Json => return CachedResponse::json(graph),
+        GraphExportFormat::GraphML => to_graphml(graph),
+        GraphExportFormat::Gexf => to_gexf(graph),
+        GraphExportFormat::NodesCsv => to_nodes_csv(graph),
+        GraphExportFormat::EdgesCsv => to_edges_csv(graph),
+    };
+    Ok(CachedResponse::new(
+        Bytes::from(body),
+        format.content_type(),
+        Some(format!(
+            "attachment; filename=\"{file_name}.{}\"",
+            format.file_extension()
+        )),
+    ))
+}
@@ -0,0 +1,224 @@
+//! Data loaders that batch the per-account lookups of a GraphQL query into single storage
+//! and RPC calls.
+use crate::{substrate, ServiceState};
+use async_graphql::dataloader::Loader;
+use ftd_types::api::identity::{Identity, SubIdentity};
+use ftd_types::api::label::Label;
+use ftd_types::api::transfer::Transfer;
+use ftd_types::graph::TransferVolume;
+use ftd_types::subscan::SubscanAccount;
+use ftd_types::substrate::account_id::AccountId;
+use ftd_types::substrate::balance::Balance;
+use rustc_hash::FxHashMap as HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+// async-graphql loaders have to return a std HashMap
+#[allow(clippy::disallowed_types)]
+type LoaderResult<K, V> = Result<std::collections::HashMap<K, V>, Arc<anyhow::Error>>;
+
+pub(crate) struct IdentityLoader(pub ServiceState);
+
+impl Loader<String> for IdentityLoader {
+    type Value = Identity;
+    type Error = Arc<anyhow::Error>;
+
+    async fn load(&self, addresses: &[String]) -> LoaderResult<String, Self::Value> {
+        Ok(self
+            .0
+            .relational_storage
+            .get_identities_by_addresses(addresses)
+            .await?
+            .into_iter()
+            .map(|identity| (identity.address.clone(), identity))
+            .collect())
+    }
+}
+
+pub(crate) struct SubIdentityLoader(pub ServiceState);
+
+impl Loader<String> for SubIdentityLoader {
+    type Value = SubIdentity;
+    type Error = Arc<anyhow::Error>;
+
+    async fn load(&self, addresses: &[String]) -> LoaderResult<String, Self::Value> {
+        Ok(self
+            .0
+            .relational_storage
+            .get_sub_identities_by_addresses(addresses)
+            .await?
+            .into_iter()
+            .map(|sub_identity| (sub_identity.address.clone(), sub_identity))
+            .collect())
+    }
+}
+
+pub(crate) struct PrimaryUsernameLoader(pub ServiceState);
+
+impl Loader<String> for PrimaryUsernameLoader {
+    type Value = String;
+    type Error = Arc<anyhow::Error>;
+
+    async fn load(&self, addresses: &[String]) -> LoaderResult<String, Self::Value> {
+        Ok(self
+            .0
+            .relational_storage
+            .get_primary_usernames_by_addresses(addresses)
+            .await?
+            .into_iter()
+            .collect())
+    }
+}
+
+pub(crate) struct LabelLoader(pub ServiceState);
+
+impl Loader<String> for LabelLoader {
+    type Value = Vec<Label>;
+    type Error = Arc<anyhow::Error>;
+
+    async fn load(&self, addresses: &[String]) -> LoaderResult<String, Self::Value> {
+        #[allow(clippy::disallowed_types)]
+        let mut label_map: std::collections::HashMap<String, Vec<Label>> = Default::default();
+        for label in self
+            .0
+            .relational_storage
+            .get_labels_by_addresses(addresses)
+            .await?
+        {
+            label_map
+                .entry(label.address.clone())
+                .or_default()
+                .push(label);
+        }
+        Ok(label_map)
+    }
+}
+
+/// Loads only the Subscan accounts that are already stored, Subscan is never queried from
+/// GraphQL.
+pub(crate) struct SubscanAccountLoader(pub ServiceState);
+
+impl Loader<String> for SubscanAccountLoader {
+    type Value = SubscanAccount;
+    type Error = Arc<anyhow::Error>;
+
+    async fn load(&self, addresses: &[String]) -> LoaderResult<String, Self::Value> {
+        Ok(self
+            .0
+            .relational_storage
+            .get_subscan_accounts_by_addresses(addresses)
+            .await?
+            .into_iter()
+            .map(|account| (account.address.clone(), account))
+            .collect())
+    }
+}
+
+/// Loads the current balances through the shared balance cache.
+pub(crate) struct BalanceLoader(pub ServiceState);
+
+impl Loader<String> for BalanceLoader {
+    type Value = Balance;
+    type Error = Arc<anyhow::Error>;
+
+    async fn load(&self, addresses: &[String]) -> LoaderResult<String, Self::Value> {
+        let account_ids: Vec<AccountId> = addresses
+            .iter()
+            .filter_map(|address| AccountId::from_str(address).ok())
+            .collect();
+        let balance_map = substrate::get_balances(
+            &self.0.substrate_client,
+            &self.0.balance_cache,
+            &account_ids,
+            None,
+        )
+        .await?;
+        Ok(addresses
+            .iter()
+            .filter_map(|address| {
+                let account_id = AccountId::from_str(address).ok()?;
+                let balance = balance_map.get(&account_id)?.clone()?;
+                Some((address.clone(), balance))
+            })
+            .collect())
+    }
+}
+
+/// Loads up to the given number of transfer volumes per account.
+pub(crate) struct TransferVolumeLoader(pub ServiceState);
+
+impl Loader<(String, u16)> for TransferVolumeLoader {
+    type Value = Vec<TransferVolume>;
+    type Error = Arc<anyhow::Error>;
+
+    async fn load(&self, keys: &[(String, u16)]) -> LoaderResult<(String, u16), Self::Value> {
+        let limit = keys.iter().map(|(_, limit)| *limit).max().unwrap_or(0);
+        let mut addresses: Vec<String> = keys.iter().map(|(address, _)| address.clone()).collect();
+        addresses.sort();
+        addresses.dedup();
+        let transfer_volume_map = self
+            .0
+            .graph_storage
+            .get_transfer_volumes_for_accounts(&addresses, limit)
+            .await?;
+        Ok(keys
+            .iter()
+            .map(|(address, limit)| {
+                let transfer_volumes = transfer_volume_map
+                    .get(address)
+                    .map(|transfer_volumes| {
+                        transfer_volumes
+                            .iter()
+                            .take(*limit as usize)
+                            .cloned()
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                ((address.clone(), *limit), transfer_volumes)
+            })
+            .collect())
+    }
+}
+
+/// Loads up to the given number of transfers between (sender, recipient) pairs.
+pub(crate) struct TransferLoader(pub ServiceState);
+
+impl Loader<(String, String, u16)> for TransferLoader {
+    type Value = Vec<Transfer>;
+    type Error = Arc<anyhow::Error>;
+
+    async fn load(
+        &self,
+        keys: &[(String, String, u16)],
+    ) -> LoaderResult<(String, String, u16), Self::Value> {
+        let limit = keys.iter().map(|(_, _, limit)| *limit).max().unwrap_or(0);
+        let mut pairs: Vec<(String, String)> = keys
+            .iter()
+            .map(|(from, to, _)| (from.clone(), to.clone()))
+            .collect();
+        pairs.sort();
+        pairs.dedup();
+        let transfers = self
+            .0
+            .relational_storage
+            .get_transfers_by_sender_and_recipient_pairs(&pairs, limit)
+            .await?;
+        let mut transfer_map: HashMap<(String, String), Vec<Transfer>> = HashMap::default();
+        for transfer in transfers {
+            transfer_map
+                .entry((transfer.from_address.clone(), transfer.to_address.clone()))
+                .or_default()
+                .push(transfer);
+        }
+        Ok(keys
+            .iter()
+            .map(|(from, to, limit)| {
+                let transfers = transfer_map
+                    .get(&(from.clone(), to.clone()))
+                    .map(|transfers| transfers.iter().take(*limit as usize).cloned().collect())
+                    .unwrap_or_default();
+                ((from.clone(), to.clone(), *limit), transfers)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,163 @@
+use crate::account::validate_search_page;
+use crate::auth::check_premium_scope;
+use crate::{ServiceState, CONFIG};
+use actix_web::{get, post, web, Either, HttpRequest, HttpResponse, ResponseError};
+use async_graphql::dataloader::DataLoader;
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, ErrorExtensions, Object, Result, Schema,
+};
+use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
+use ftd_service::err::APIError;
+use loader::{
+    BalanceLoader, IdentityLoader, LabelLoader, PrimaryUsernameLoader, SubIdentityLoader,
+    SubscanAccountLoader, TransferLoader, TransferVolumeLoader,
+};
+use object::{AccountObject, TransferObject, DEFAULT_TRANSFER_LIMIT};
+use std::borrow::Borrow;
+
+mod loader;
+mod object;
+
+pub(crate) type APISchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Converts the API error to a GraphQL error with the error code in the `code` extension.
+fn to_graphql_error(error: APIError) -> async_graphql::Error {
+    let code = error.code();
+    async_graphql::Error::new(error.to_string()).extend_with(|_, extensions| {
+        if let Ok(code) = async_graphql::to_value(code) {
+            extensions.set("code", code);
+        }
+    })
+}
+
+/// Logs the error and hides its details from the client, see `APIError`. Takes the shared
+/// errors of the data loaders as well.
+fn graphql_error<E: Borrow<anyhow::Error>>(error: E) -> async_graphql::Error {
+    to_graphql_error(APIError::from(error.borrow()))
+}
+
+/// Converts the SS58 address of any prefix or account id hex to the chain's SS58 format.
+fn normalize_address(address: &str) -> Result<String> {
+    crate::address::parse_address(address, "address").map_err(to_graphql_error)
+}
+
+pub(crate) struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Account with the given SS58 address or account id hex.
+    async fn account(&self, address: String) -> Result<AccountObject> {
+        Ok(AccountObject {
+            address: normalize_address(&address)?,
+        })
+    }
+
+    /// Accounts matching the query, most relevant first. Identity, sub-identity, Subscan and
+    /// Merkle Science names, labels and addresses are ranked by trigram similarity. The offset
+    /// plus the limit is at most 500.
+    async fn search_accounts(
+        &self,
+        ctx: &Context<'_>,
+        query: String,
+        offset: Option<u32>,
+        limit: Option<u16>,
+    ) -> Result<Vec<AccountObject>> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(to_graphql_error(APIError::InvalidParameter(
+                "Query should not be empty.".to_string(),
+            )));
+        }
+        let state = ctx.data_unchecked::<ServiceState>();
+        let limit = limit
+            .unwrap_or(CONFIG.api.account_search_limit)
+            .clamp(1, CONFIG.api.account_search_limit);
+        let offset = offset.unwrap_or(0);
+        validate_search_page(offset, limit).map_err(to_graphql_error)?;
+        let address_query =
+            crate::address::normalize_address(query).unwrap_or_else(|| query.to_string());
+        Ok(state
+            .relational_storage
+            .search_accounts_ranked(query, &address_query, limit, offset)
+            .await
+            .map_err(graphql_error)?
+            .into_iter()
+            .map(|(address, _)| AccountObject { address })
+            .collect())
+    }
+
+    /// Transfers from the sender to the recipient, latest first.
+    #[graphql(complexity = "limit.unwrap_or(DEFAULT_TRANSFER_LIMIT) as usize * child_complexity")]
+    async fn transfers(
+        &self,
+        ctx: &Context<'_>,
+        from: String,
+        to: String,
+        limit: Option<u16>,
+    ) -> Result<Vec<TransferObject>> {
+        let from = normalize_address(&from)?;
+        let to = normalize_address(&to)?;
+        let limit = limit
+            .unwrap_or(DEFAULT_TRANSFER_LIMIT)
+            .min(CONFIG.api.graph_search_limit);
+        Ok(ctx
+            .data_unchecked::<DataLoader<TransferLoader>>()
+            .load_one((from, to, limit))
+            .await
+            .map_err(graphql_error)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(TransferObject)
+            .collect())
+    }
+}
+
+/// Builds the schema with the configured depth and complexity limits. The data loaders are
+/// shared by all requests, which lets concurrent queries share batches.
+pub(crate) fn build_schema(state: ServiceState) -> APISchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(CONFIG.api.graphql_max_depth)
+        .limit_complexity(CONFIG.api.graphql_max_complexity)
+        .data(DataLoader::new(IdentityLoader(state.clone()), tokio::spawn))
+        .data(DataLoader::new(
+            SubIdentityLoader(state.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            SubscanAccountLoader(state.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            PrimaryUsernameLoader(state.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(LabelLoader(state.clone()), tokio::spawn))
+        .data(DataLoader::new(BalanceLoader(state.clone()), tokio::spawn))
+        .data(DataLoader::new(
+            TransferVolumeLoader(state.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(TransferLoader(state.clone()), tokio::spawn))
+        .data(state)
+        .finish()
+}
+
+#[post("/graphql")]
+pub(crate) async fn graphql_service(
+    http_request: HttpRequest,
+    schema: web::Data<APISchema>,
+    request: GraphQLRequest,
+) -> Either<HttpResponse, GraphQLResponse> {
+    if let Err(error) = check_premium_scope(&http_request) {
+        return Either::Left(error.error_response());
+    }
+    Either::Right(schema.execute(request.into_inner()).await.into())
+}
+
+#[get("/graphql")]
+pub(crate) async fn graphiql_service() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(GraphiQLSource::build().endpoint("/graphql").finish())
+}
@@ -0,0 +1,487 @@
+//! GraphQL output types. Amounts are exposed as decimal strings, since they don't fit into
+//! GraphQL integers.
+use super::graphql_error;
+use super::loader::{
+    BalanceLoader, IdentityLoader, LabelLoader, PrimaryUsernameLoader, SubIdentityLoader,
+    SubscanAccountLoader, TransferLoader, TransferVolumeLoader,
+};
+use crate::CONFIG;
+use async_graphql::dataloader::DataLoader;
+use async_graphql::{Context, Object, Result};
+use ftd_types::api::identity::{Identity, IdentityDataHashType, IdentityJudgement, SubIdentity};
+use ftd_types::api::label::Label;
+use ftd_types::api::transfer::Transfer;
+use ftd_types::graph::TransferVolume;
+use ftd_types::subscan::SubscanAccount;
+use ftd_types::substrate::balance::Balance;
+
+const DEFAULT_TRANSFER_VOLUME_LIMIT: u16 = 10;
+pub(super) const DEFAULT_TRANSFER_LIMIT: u16 = 10;
+
+pub(crate) struct AccountObject {
+    pub address: String,
+}
+
+#[Object(name = "Account")]
+impl AccountObject {
+    async fn address(&self) -> &str {
+        &self.address
+    }
+
+    async fn identity(&self, ctx: &Context<'_>) -> Result<Option<IdentityObject>> {
+        Ok(ctx
+            .data_unchecked::<DataLoader<IdentityLoader>>()
+            .load_one(self.address.clone())
+            .await
+            .map_err(graphql_error)?
+            .map(IdentityObject))
+    }
+
+    async fn sub_identity(&self, ctx: &Context<'_>) -> Result<Option<SubIdentityObject>> {
+        Ok(ctx
+            .data_unchecked::<DataLoader<SubIdentityLoader>>()
+            .load_one(self.address.clone())
+            .await
+            .map_err(graphql_error)?
+            .map(SubIdentityObject))
+    }
+
+    /// Identity of the parent account if this account is a sub-account.
+    async fn super_identity(&self, ctx: &Context<'_>) -> Result<Option<IdentityObject>> {
+        let Some(sub_identity) = ctx
+            .data_unchecked::<DataLoader<SubIdentityLoader>>()
+            .load_one(self.address.clone())
+            .await
+            .map_err(graphql_error)?
+        else {
+            return Ok(None);
+        };
+        Ok(ctx
+            .data_unchecked::<DataLoader<IdentityLoader>>()
+            .load_one(sub_identity.super_address)
+            .await
+            .map_err(graphql_error)?
+            .map(IdentityObject))
+    }
+
+    /// Username the account chose to be displayed, e.g. `alice.dot`.
+    async fn primary_username(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        ctx.data_unchecked::<DataLoader<PrimaryUsernameLoader>>()
+            .load_one(self.address.clone())
+            .await
+            .map_err(graphql_error)
+    }
+
+    /// Previously fetched Subscan account data, if any.
+    async fn subscan_account(&self, ctx: &Context<'_>) -> Result<Option<SubscanAccountObject>> {
+        Ok(ctx
+            .data_unchecked::<DataLoader<SubscanAccountLoader>>()
+            .load_one(self.address.clone())
+            .await
+            .map_err(graphql_error)?
+            .map(SubscanAccountObject))
+    }
+
+    /// Off-chain labels, the most confident first.
+    async fn labels(&self, ctx: &Context<'_>) -> Result<Vec<LabelObject>> {
+        Ok(ctx
+            .data_unchecked::<DataLoader<LabelLoader>>()
+            .load_one(self.address.clone())
+            .await
+            .map_err(graphql_error)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(LabelObject)
+            .collect())
+    }
+
+    /// Current balance of the account.
+    async fn balance(&self, ctx: &Context<'_>) -> Result<Option<BalanceObject>> {
+        Ok(ctx
+            .data_unchecked::<DataLoader<BalanceLoader>>()
+            .load_one(self.address.clone())
+            .await
+            .map_err(graphql_error)?
+            .map(BalanceObject))
+    }
+
+    /// Aggregated transfers from and to this account.
+    #[graphql(
+        complexity = "limit.unwrap_or(DEFAULT_TRANSFER_VOLUME_LIMIT) as usize * child_complexity"
+    )]
+    async fn transfer_volumes(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<u16>,
+    ) -> Result<Vec<TransferVolumeObject>> {
+        let limit = limit
+            .unwrap_or(DEFAULT_TRANSFER_VOLUME_LIMIT)
+            .min(CONFIG.api.graph_search_limit);
+        Ok(ctx
+            .data_unchecked::<DataLoader<TransferVolumeLoader>>()
+            .load_one((self.address.clone(), limit))
+            .await
+            .map_err(graphql_error)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(TransferVolumeObject)
+            .collect())
+    }
+
+    /// Accounts that this account has sent transfers to or received transfers from.
+    #[graphql(
+        complexity = "limit.unwrap_or(DEFAULT_TRANSFER_VOLUME_LIMIT) as usize * child_complexity"
+    )]
+    async fn counterparties(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<u16>,
+    ) -> Result<Vec<AccountObject>> {
+        let limit = limit
+            .unwrap_or(DEFAULT_TRANSFER_VOLUME_LIMIT)
+            .min(CONFIG.api.graph_search_limit);
+        let transfer_volumes = ctx
+            .data_unchecked::<DataLoader<TransferVolumeLoader>>()
+            .load_one((self.address.clone(), limit))
+            .await
+            .map_err(graphql_error)?
+            .unwrap_or_default();
+        let mut counterparties: Vec<AccountObject> = Vec::new();
+        for transfer_volume in transfer_volumes {
+            let address = if transfer_volume.from == self.address {
+                transfer_volume.to
+            } else {
+                transfer_volume.from
+            };
+            if !counterparties
+                .iter()
+                .any(|counterparty| counterparty.address == address)
+            {
+                counterparties.push(AccountObject { address });
+            }
+        }
+        Ok(counterparties)
+    }
+}
+
+pub(crate) struct IdentityObject(pub Identity);
+
+#[Object(name = "Identity")]
+impl IdentityObject {
+    async fn address(&self) -> &str {
+        &self.0.address
+    }
+
+    async fn display(&self) -> Option<&str> {
+        self.0.display.as_deref()
+    }
+
+    async fn legal(&self) -> Option<&str> {
+        self.0.legal.as_deref()
+    }
+
+    async fn web(&self) -> Option<&str> {
+        self.0.web.as_deref()
+    }
+
+    async fn riot(&self) -> Option<&str> {
+        self.0.riot.as_deref()
+    }
+
+    async fn email(&self) -> Option<&str> {
+        self.0.email.as_deref()
+    }
+
+    async fn twitter(&self) -> Option<&str> {
+        self.0.twitter.as_deref()
+    }
+
+    async fn github(&self) -> Option<&str> {
+        self.0.github.as_deref()
+    }
+
+    async fn discord(&self) -> Option<&str> {
+        self.0.discord.as_deref()
+    }
+
+    async fn image(&self) -> Option<&str> {
+        self.0.image.as_deref()
+    }
+
+    async fn pgp_fingerprint(&self) -> Option<&str> {
+        self.0.pgp_fingerprint.as_deref()
+    }
+
+    /// Fields that are set as hashes on chain. Their values are the hash hexes.
+    async fn hashed_fields(&self) -> Vec<HashedIdentityFieldObject> {
+        self.0
+            .hashed_fields
+            .iter()
+            .map(|(field, hash_type)| HashedIdentityFieldObject(field.clone(), *hash_type))
+            .collect()
+    }
+
+    /// Judgements of the identity by the registrars.
+    async fn judgements(&self) -> Vec<IdentityJudgementObject> {
+        self.0
+            .judgements
+            .iter()
+            .cloned()
+            .map(IdentityJudgementObject)
+            .collect()
+    }
+
+    async fn is_confirmed(&self) -> bool {
+        self.0.is_confirmed
+    }
+
+    async fn is_invalid(&self) -> bool {
+        self.0.is_invalid
+    }
+}
+
+pub(crate) struct IdentityJudgementObject(IdentityJudgement);
+
+#[Object(name = "IdentityJudgement")]
+impl IdentityJudgementObject {
+    async fn registrar_index(&self) -> u32 {
+        self.0.registrar_index
+    }
+
+    /// One of `unknown`, `fee_paid`, `reasonable`, `known_good`, `out_of_date`, `low_quality`
+    /// or `erroneous`.
+    async fn judgement(&self) -> String {
+        self.0.judgement.to_string()
+    }
+
+    /// Fee held for the registrar in Planck, for `fee_paid` judgements.
+    async fn fee_paid_amount(&self) -> Option<String> {
+        self.0.fee_paid_amount.map(|amount| amount.to_string())
+    }
+}
+
+pub(crate) struct HashedIdentityFieldObject(String, IdentityDataHashType);
+
+#[Object(name = "HashedIdentityField")]
+impl HashedIdentityFieldObject {
+    async fn field(&self) -> &str {
+        &self.0
+    }
+
+    /// One of `blake_two_256`, `sha_256`, `keccak_256` or `sha_three_256`.
+    async fn hash_type(&self) -> String {
+        self.1.to_string()
+    }
+}
+
+pub(crate) struct SubIdentityObject(pub SubIdentity);
+
+#[Object(name = "SubIdentity")]
+impl SubIdentityObject {
+    async fn address(&self) -> &str {
+        &self.0.address
+    }
+
+    async fn super_address(&self) -> &str {
+        &self.0.super_address
+    }
+
+    async fn sub_display(&self) -> Option<&str> {
+        self.0.sub_display.as_deref()
+    }
+}
+
+pub(crate) struct LabelObject(pub Label);
+
+#[Object(name = "Label")]
+impl LabelObject {
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn category(&self) -> String {
+        self.0.category.to_string()
+    }
+
+    async fn source(&self) -> &str {
+        &self.0.source
+    }
+
+    async fn confidence(&self) -> f32 {
+        self.0.confidence
+    }
+
+    /// Labeling time in milliseconds.
+    async fn labeled_at(&self) -> u64 {
+        self.0.labeled_at
+    }
+}
+
+pub(crate) struct SubscanAccountObject(pub SubscanAccount);
+
+#[Object(name = "SubscanAccount")]
+impl SubscanAccountObject {
+    async fn address(&self) -> &str {
+        &self.0.address
+    }
+
+    async fn display(&self) -> Option<&str> {
+        self.0.display.as_deref()
+    }
+
+    async fn account_index(&self) -> Option<&str> {
+        self.0.account_display.account_index.as_deref()
+    }
+
+    async fn parent_address(&self) -> Option<&str> {
+        self.0
+            .account_display
+            .parent
+            .as_ref()
+            .map(|parent| parent.address.as_str())
+    }
+
+    async fn parent_display(&self) -> Option<&str> {
+        self.0
+            .account_display
+            .parent
+            .as_ref()
+            .and_then(|parent| parent.display.as_deref())
+    }
+
+    async fn merkle_science_address_type(&self) -> Option<&str> {
+        self.0
+            .account_display
+            .merkle
+            .as_ref()
+            .map(|merkle| merkle.address_type.as_str())
+    }
+
+    async fn merkle_science_tag_type(&self) -> Option<&str> {
+        self.0
+            .account_display
+            .merkle
+            .as_ref()
+            .and_then(|merkle| merkle.tag_type.as_deref())
+    }
+
+    async fn merkle_science_tag_subtype(&self) -> Option<&str> {
+        self.0
+            .account_display
+            .merkle
+            .as_ref()
+            .and_then(|merkle| merkle.tag_subtype.as_deref())
+    }
+
+    async fn merkle_science_tag_name(&self) -> Option<&str> {
+        self.0
+            .account_display
+            .merkle
+            .as_ref()
+            .map(|merkle| merkle.tag_name.as_str())
+    }
+}
+
+pub(crate) struct BalanceObject(pub Balance);
+
+#[Object(name = "Balance")]
+impl BalanceObject {
+    async fn free(&self) -> String {
+        self.0.free.to_string()
+    }
+
+    async fn reserved(&self) -> String {
+        self.0.reserved.to_string()
+    }
+
+    async fn frozen(&self) -> String {
+        self.0.frozen.to_string()
+    }
+}
+
+pub(crate) struct TransferVolumeObject(pub TransferVolume);
+
+#[Object(name = "TransferVolume")]
+impl TransferVolumeObject {
+    async fn from(&self) -> AccountObject {
+        AccountObject {
+            address: self.0.from.clone(),
+        }
+    }
+
+    async fn to(&self) -> AccountObject {
+        AccountObject {
+            address: self.0.to.clone(),
+        }
+    }
+
+    async fn count(&self) -> u32 {
+        self.0.count
+    }
+
+    async fn volume(&self) -> String {
+        self.0.volume.to_string()
+    }
+
+    /// Individual transfers aggregated in this volume, latest first.
+    #[graphql(complexity = "limit.unwrap_or(DEFAULT_TRANSFER_LIMIT) as usize * child_complexity")]
+    async fn transfers(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<u16>,
+    ) -> Result<Vec<TransferObject>> {
+        let limit = limit
+            .unwrap_or(DEFAULT_TRANSFER_LIMIT)
+            .min(CONFIG.api.graph_search_limit);
+        Ok(ctx
+            .data_unchecked::<DataLoader<TransferLoader>>()
+            .load_one((self.0.from.clone(), self.0.to.clone(), limit))
+            .await
+            .map_err(graphql_error)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(TransferObject)
+            .collect())
+    }
+}
+
+pub(crate) struct TransferObject(pub Transfer);
+
+#[Object(name = "Transfer")]
+impl TransferObject {
+    async fn block_hash(&self) -> &str {
+        &self.0.block_hash
+    }
+
+    async fn block_number(&self) -> u64 {
+        self.0.block_number
+    }
+
+    async fn timestamp(&self) -> u64 {
+        self.0.timestamp
+    }
+
+    async fn extrinsic_index(&self) -> u16 {
+        self.0.extrinsic_index
+    }
+
+    async fn event_index(&self) -> u16 {
+        self.0.event_index
+    }
+
+    async fn from(&self) -> AccountObject {
+        AccountObject {
+            address: self.0.from_address.clone(),
+        }
+    }
+
+    async fn to(&self) -> AccountObject {
+        AccountObject {
+            address: self.0.to_address.clone(),
+        }
+    }
+
+    async fn amount(&self) -> String {
+        self.0.amount.to_string()
+    }
+}
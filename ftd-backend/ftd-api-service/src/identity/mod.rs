@@ -0,0 +1,119 @@
+use crate::address::{parse_address, validate_ss58_prefix, with_ss58_prefix};
+use crate::block::BlockReference;
+use crate::{ResultResponse, ServiceState};
+use actix_web::{get, web, HttpResponse};
+use ftd_service::err::APIError;
+use ftd_types::api::identity::{HistoricalIdentity, IdentityChange, Registrar};
+use ftd_types::err::ServiceError;
+use serde::Deserialize;
+use std::str::FromStr;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub(crate) struct IdentityParameters {
+    /// SS58 address or account id hex.
+    address: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct IdentityTimelineQueryParameters {
+    /// SS58 prefix of the addresses in the response, the prefix of the chain by default.
+    ss58_prefix: Option<u16>,
+}
+
+#[utoipa::path(
+    tag = "identity",
+    params(IdentityParameters, IdentityTimelineQueryParameters),
+    responses(
+        (status = 200, description = "Identity and sub identity changes of the account, oldest first.", body = Vec<IdentityChange>),
+        (status = 400, description = "Invalid address or SS58 prefix.", body = ServiceError),
+        (status = 500, description = "Internal server error.", body = ServiceError),
+    ),
+)]
+#[get("/account/{address}/identity/timeline")]
+pub(crate) async fn identity_timeline_service(
+    path: web::Path<IdentityParameters>,
+    query: web::Query<IdentityTimelineQueryParameters>,
+    state: web::Data<ServiceState>,
+) -> ResultResponse {
+    let address = parse_address(path.address.as_str(), "address")?;
+    validate_ss58_prefix(query.ss58_prefix)?;
+    let identity_changes = state
+        .relational_storage
+        .get_identity_timeline(address.as_str())
+        .await?;
+    Ok(HttpResponse::Ok().json(with_ss58_prefix(identity_changes, query.ss58_prefix)))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HistoricalIdentityQueryParameters {
+    /// Block number of the People chain, where the identities live, or RFC 3339 timestamp.
+    /// The backfilled relay chain changes precede all People chain blocks. The current identity
+    /// if not given.
+    at: Option<String>,
+    /// SS58 prefix of the addresses in the response, the prefix of the chain by default.
+    ss58_prefix: Option<u16>,
+}
+
+#[utoipa::path(
+    tag = "identity",
+    params(IdentityParameters, HistoricalIdentityQueryParameters),
+    responses(
+        (status = 200, description = "Identity and sub identity of the account at the given block or time.", body = HistoricalIdentity),
+        (status = 400, description = "Invalid address, block reference or SS58 prefix.", body = ServiceError),
+        (status = 500, description = "Internal server error.", body = ServiceError),
+    ),
+)]
+#[get("/account/{address}/identity")]
+pub(crate) async fn historical_identity_service(
+    path: web::Path<IdentityParameters>,
+    query: web::Query<HistoricalIdentityQueryParameters>,
+    state: web::Data<ServiceState>,
+) -> ResultResponse {
+    let address = parse_address(path.address.as_str(), "address")?;
+    validate_ss58_prefix(query.ss58_prefix)?;
+    let (maybe_block_number, maybe_timestamp) = match query.at.as_deref() {
+        Some(at) => match BlockReference::from_str(at).map_err(APIError::InvalidParameter)? {
+            BlockReference::Number(block_number) => (Some(block_number), None),
+            BlockReference::Timestamp(timestamp) => (None, Some(timestamp)),
+        },
+        None => (None, None),
+    };
+    let historical_identity = state
+        .relational_storage
+        .get_historical_identity(address.as_str(), maybe_block_number, maybe_timestamp)
+        .await?;
+    Ok(HttpResponse::Ok().json(with_ss58_prefix(historical_identity, query.ss58_prefix)))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RegistrarListQueryParameters {
+    /// SS58 prefix of the addresses in the response, the prefix of the chain by default.
+    ss58_prefix: Option<u16>,
+}
+
+#[utoipa::path(
+    tag = "identity",
+    params(RegistrarListQueryParameters),
+    responses(
+        (status = 200, description = "Identity registrars by index. The identity judgements refer to the registrars by index.", body = Vec<Registrar>),
+        (status = 400, description = "Invalid SS58 prefix.", body = ServiceError),
+        (status = 500, description = "Internal server error.", body = ServiceError),
+    ),
+)]
+#[get("/identity/registrar")]
+pub(crate) async fn registrar_list_service(
+    query: web::Query<RegistrarListQueryParameters>,
+    state: web::Data<ServiceState>,
+) -> ResultResponse {
+    validate_ss58_prefix(query.ss58_prefix)?;
+    let registrars = state.relational_storage.get_registrars().await?;
+    Ok(HttpResponse::Ok().json(with_ss58_prefix(registrars, query.ss58_prefix)))
+}
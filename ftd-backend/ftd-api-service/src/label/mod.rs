@@ -0,0 +1,108 @@
+use crate::address::{validate_ss58_prefix, with_ss58_prefix, CHAIN};
+use crate::auth::check_admin_key;
+use crate::{ResultResponse, ServiceState};
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse};
+use ftd_service::err::APIError;
+use ftd_types::api::label::{Label, LabelCategory, NewLabel};
+use ftd_types::err::ServiceError;
+use serde::Deserialize;
+use std::str::FromStr;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub(crate) struct LabelListQueryParameters {
+    /// One of `exchange`, `bridge`, `scam`, `treasury`, `validator` or `other`.
+    category: Option<String>,
+    /// Label source.
+    source: Option<String>,
+    /// SS58 prefix of the addresses in the response, the prefix of the chain by default.
+    ss58_prefix: Option<u16>,
+}
+
+#[utoipa::path(
+    tag = "label",
+    params(LabelListQueryParameters),
+    responses(
+        (status = 200, description = "Labels matching the filters.", body = Vec<Label>),
+        (status = 400, description = "Invalid category or SS58 prefix.", body = ServiceError),
+        (status = 500, description = "Internal server error.", body = ServiceError),
+    ),
+)]
+#[get("/label")]
+pub(crate) async fn label_list_service(
+    query: web::Query<LabelListQueryParameters>,
+    state: web::Data<ServiceState>,
+) -> ResultResponse {
+    let maybe_category = query
+        .category
+        .as_deref()
+        .map(LabelCategory::from_str)
+        .transpose()
+        .map_err(APIError::InvalidParameter)?;
+    validate_ss58_prefix(query.ss58_prefix)?;
+    let labels = state
+        .relational_storage
+        .get_labels(maybe_category, query.source.as_deref())
+        .await?;
+    Ok(HttpResponse::Ok().json(with_ss58_prefix(labels, query.ss58_prefix)))
+}
+
+#[utoipa::path(
+    tag = "label",
+    request_body = NewLabel,
+    params(("X-FTD-Admin-Key" = String, Header, description = "Admin key.")),
+    responses(
+        (status = 200, description = "Saved label.", body = Label),
+        (status = 400, description = "Invalid label.", body = ServiceError),
+        (status = 401, description = "Invalid admin key.", body = ServiceError),
+        (status = 500, description = "Internal server error.", body = ServiceError),
+    ),
+)]
+#[post("/label")]
+pub(crate) async fn label_save_service(
+    request: HttpRequest,
+    new_label: web::Json<NewLabel>,
+    state: web::Data<ServiceState>,
+) -> ResultResponse {
+    check_admin_key(&request)?;
+    let new_label = new_label
+        .normalize(&CHAIN)
+        .map_err(APIError::InvalidParameter)?;
+    Ok(HttpResponse::Ok().json(state.relational_storage.save_label(&new_label).await?))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub(crate) struct LabelParameters {
+    /// Label id.
+    id: i32,
+}
+
+#[utoipa::path(
+    tag = "label",
+    params(
+        LabelParameters,
+        ("X-FTD-Admin-Key" = String, Header, description = "Admin key."),
+    ),
+    responses(
+        (status = 204, description = "Label deleted."),
+        (status = 401, description = "Invalid admin key.", body = ServiceError),
+        (status = 404, description = "Label not found.", body = ServiceError),
+        (status = 500, description = "Internal server error.", body = ServiceError),
+    ),
+)]
+#[delete("/label/{id}")]
+pub(crate) async fn label_delete_service(
+    request: HttpRequest,
+    path: web::Path<LabelParameters>,
+    state: web::Data<ServiceState>,
+) -> ResultResponse {
+    check_admin_key(&request)?;
+    if state.relational_storage.delete_label(path.id).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(APIError::NotFound("Label not found.".to_string()))
+    }
+}
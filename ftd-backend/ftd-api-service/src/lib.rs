@@ -1,31 +1,63 @@
 use actix_cors::Cors;
-use actix_web::{dev::Service as _, web, App, HttpResponse, HttpServer};
+use actix_web::{dev::Service as _, middleware, web, App, HttpRequest, HttpResponse, HttpServer};
 use async_trait::async_trait;
+use auth::APIKeyStore;
+use cache::ResponseCache;
 use ftd_config::Config;
 use ftd_persistence::graph::GraphStorage;
 use ftd_persistence::relational::RelationalStorage;
-use ftd_service::err::InternalServerError;
+use ftd_service::err::APIError;
 use ftd_service::Service;
 use ftd_subscan_client::SubscanClient;
 use futures_util::future::FutureExt;
 use lazy_static::lazy_static;
 use std::sync::Arc;
+use stream::StreamedTransfer;
+use substrate::{BalanceCache, ReconnectingSubstrateClient};
+use tokio::sync::broadcast;
 
 mod account;
+mod address;
+mod alert;
+mod api_key;
+mod auth;
+mod block;
+mod cache;
+mod export;
+mod graphql;
+mod identity;
+mod label;
 mod metrics;
+mod openapi;
+mod stream;
+mod substrate;
 mod transfer;
 
 lazy_static! {
     static ref CONFIG: Config = Config::default();
 }
 
-pub(crate) type ResultResponse = Result<HttpResponse, InternalServerError>;
+pub(crate) type ResultResponse = Result<HttpResponse, APIError>;
 
 #[derive(Clone)]
 pub(crate) struct ServiceState {
     relational_storage: Arc<RelationalStorage>,
     graph_storage: Arc<GraphStorage>,
     subscan_client: Arc<SubscanClient>,
+    substrate_client: Arc<ReconnectingSubstrateClient>,
+    balance_cache: Arc<BalanceCache>,
+    transfer_sender: broadcast::Sender<Arc<StreamedTransfer>>,
+    api_key_store: Arc<APIKeyStore>,
+    response_cache: Arc<ResponseCache>,
+}
+
+/// Maps the query, JSON body and path extractor errors to invalid parameter, so that they
+/// carry the error code like the other client errors.
+fn extractor_error_handler<E: std::fmt::Display>(
+    error: E,
+    _request: &HttpRequest,
+) -> actix_web::Error {
+    APIError::InvalidParameter(error.to_string()).into()
 }
 
 async fn on_server_ready() {
@@ -48,60 +80,165 @@ impl Service for APIService {
         let graph_storage = Arc::new(GraphStorage::new().await?);
         let relational_storage = Arc::new(RelationalStorage::new().await?);
         let subscan_client = Arc::new(SubscanClient::new(&CONFIG)?);
+        let substrate_client = Arc::new(ReconnectingSubstrateClient::new());
+        let balance_cache = Arc::new(BalanceCache::new(std::time::Duration::from_secs(
+            CONFIG.api.balance_cache_ttl_seconds,
+        )));
+        let api_key_store = Arc::new(APIKeyStore::default());
+        api_key_store.reload(&relational_storage).await?;
+        let response_cache = Arc::new(ResponseCache::new(
+            CONFIG.api.response_cache_capacity,
+            std::time::Duration::from_secs(CONFIG.api.response_cache_ttl_seconds),
+        ));
+        let (transfer_sender, _) = broadcast::channel(CONFIG.api.transfer_stream_channel_capacity);
+        let state = ServiceState {
+            relational_storage,
+            graph_storage,
+            subscan_client,
+            substrate_client,
+            balance_cache,
+            transfer_sender,
+            api_key_store,
+            response_cache,
+        };
+        let graphql_schema = graphql::build_schema(state.clone());
 
         log::info!("Starting HTTP service.");
+        let task_state = state.clone();
         let server = HttpServer::new(move || {
-            let _cors = Cors::default()
+            let cors = Cors::default()
                 .allow_any_origin()
-                .allowed_methods(vec!["GET", "POST", "OPTIONS"])
+                .allowed_methods(vec!["GET", "POST", "DELETE", "OPTIONS"])
                 .allowed_headers(vec![
                     actix_web::http::header::AUTHORIZATION,
                     actix_web::http::header::CONTENT_TYPE,
+                    actix_web::http::header::IF_NONE_MATCH,
+                    actix_web::http::header::HeaderName::from_static("x-ftd-api-key"),
+                    actix_web::http::header::HeaderName::from_static("x-ftd-admin-key"),
+                ])
+                .expose_headers(vec![
+                    actix_web::http::header::ETAG,
+                    actix_web::http::header::RETRY_AFTER,
                 ])
                 .supports_credentials();
 
             App::new()
-                .app_data(web::Data::new(ServiceState {
-                    relational_storage: relational_storage.clone(),
-                    graph_storage: graph_storage.clone(),
-                    subscan_client: subscan_client.clone(),
-                }))
-                //.wrap(cors)
+                .app_data(web::Data::new(state.clone()))
+                .app_data(web::Data::new(graphql_schema.clone()))
+                .app_data(web::QueryConfig::default().error_handler(extractor_error_handler))
+                .app_data(web::JsonConfig::default().error_handler(extractor_error_handler))
+                .app_data(web::PathConfig::default().error_handler(extractor_error_handler))
+                .wrap(middleware::from_fn(auth::authenticate))
                 .wrap_fn(|request, service| {
-                    metrics::request_counter().inc();
                     metrics::connection_count().inc();
                     let start = std::time::Instant::now();
                     service.call(request).map(move |result| {
                         match &result {
                             Ok(response) => {
+                                let api_key = auth::get_metrics_label(response.request());
                                 let status_code = response.response().status();
-                                metrics::response_time_ms()
+                                metrics::request_counter(&api_key).inc();
+                                metrics::response_time_ms(&api_key)
                                     .observe(start.elapsed().as_millis() as f64);
-                                metrics::response_status_code_counter(status_code.as_str()).inc();
+                                metrics::response_status_code_counter(
+                                    &api_key,
+                                    status_code.as_str(),
+                                )
+                                .inc();
                             }
                             Err(error) => {
                                 let status_code = error.as_response_error().status_code();
-                                metrics::response_time_ms()
+                                metrics::request_counter(auth::ANONYMOUS_METRICS_LABEL).inc();
+                                metrics::response_time_ms(auth::ANONYMOUS_METRICS_LABEL)
                                     .observe(start.elapsed().as_millis() as f64);
-                                metrics::response_status_code_counter(status_code.as_str()).inc();
+                                metrics::response_status_code_counter(
+                                    auth::ANONYMOUS_METRICS_LABEL,
+                                    status_code.as_str(),
+                                )
+                                .inc();
                             }
                         }
                         metrics::connection_count().dec();
                         result
                     })
                 })
+                .wrap(cors)
                 .service(account::account_search_service)
+                .service(account::account_details_service)
                 .service(account::account_graph_service)
+                .service(identity::identity_timeline_service)
+                .service(identity::historical_identity_service)
+                .service(identity::registrar_list_service)
                 .service(transfer::transfer_list_service)
+                .service(stream::transfer_stream_service)
+                .service(alert::watch_entry_list_service)
+                .service(alert::watch_entry_create_service)
+                .service(alert::watch_entry_delete_service)
+                .service(alert::alert_list_service)
+                .service(label::label_list_service)
+                .service(label::label_save_service)
+                .service(label::label_delete_service)
+                .service(api_key::api_key_list_service)
+                .service(api_key::api_key_create_service)
+                .service(api_key::api_key_delete_service)
+                .service(openapi::openapi_service)
+                .service(graphql::graphql_service)
+                .service(graphql::graphiql_service)
         })
         .workers(10)
         .disable_signals()
         .bind(format!(
             "{}:{}",
             CONFIG.api.service_host, CONFIG.api.api_service_port,
-        ))?
-        .run();
+        ))?;
+
+        // the background tasks start only once the server is bound, so that a failed start
+        // does not leave them running
+        let health_check_task = {
+            let substrate_client = task_state.substrate_client.clone();
+            let balance_cache = task_state.balance_cache.clone();
+            tokio::spawn(async move {
+                loop {
+                    substrate_client.check_health().await;
+                    balance_cache.purge_expired();
+                    tokio::time::sleep(std::time::Duration::from_secs(
+                        CONFIG.api.substrate_health_check_seconds,
+                    ))
+                    .await;
+                }
+            })
+        };
+        let api_key_reload_task = {
+            let relational_storage = task_state.relational_storage.clone();
+            let api_key_store = task_state.api_key_store.clone();
+            tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(std::time::Duration::from_secs(
+                        CONFIG.api.api_key_refresh_seconds,
+                    ))
+                    .await;
+                    if let Err(error) = api_key_store.reload(&relational_storage).await {
+                        log::error!("Error while reloading API keys: {error:?}");
+                    }
+                }
+            })
+        };
+        let cache_invalidation_task = tokio::spawn(cache::invalidate_on_graph_updates(
+            task_state.relational_storage.clone(),
+            task_state.graph_storage.clone(),
+            task_state.response_cache.clone(),
+        ));
+        let transfer_broadcast_task = tokio::spawn(stream::broadcast_transfers(
+            task_state.relational_storage.clone(),
+            task_state.transfer_sender.clone(),
+        ));
+
+        let server = server.run();
         let (server_result, _) = tokio::join!(server, on_server_ready());
+        health_check_task.abort();
+        transfer_broadcast_task.abort();
+        api_key_reload_task.abort();
+        cache_invalidation_task.abort();
         Ok(server_result?)
     }
 }
@@ -1,18 +1,19 @@
-use ftd_metrics::registry::{Histogram, IntCounter, IntCounterVec, IntGauge};
+use ftd_metrics::registry::{Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge};
 use once_cell::sync::Lazy;
 
 const METRIC_PREFIX: &str = "ftd_api_service";
 
-pub(crate) fn request_counter() -> IntCounter {
-    static METER: Lazy<IntCounter> = Lazy::new(|| {
-        ftd_metrics::registry::register_int_counter(
+pub(crate) fn request_counter(api_key: &str) -> IntCounter {
+    static METER: Lazy<IntCounterVec> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_counter_vec(
             METRIC_PREFIX,
             "request_count",
             "The total number of requests made to the API",
+            &["api_key"],
         )
         .unwrap()
     });
-    METER.clone()
+    METER.with_label_values(&[api_key])
 }
 
 pub(crate) fn connection_count() -> IntGauge {
@@ -27,12 +28,13 @@ pub(crate) fn connection_count() -> IntGauge {
     METER.clone()
 }
 
-pub(crate) fn response_time_ms() -> Histogram {
-    static METER: Lazy<Histogram> = Lazy::new(|| {
-        ftd_metrics::registry::register_histogram(
+pub(crate) fn response_time_ms(api_key: &str) -> Histogram {
+    static METER: Lazy<HistogramVec> = Lazy::new(|| {
+        ftd_metrics::registry::register_histogram_vec(
             METRIC_PREFIX,
             "response_time_ms",
             "Response time in milliseconds",
+            &["api_key"],
             vec![
                 50.0, 100.0, 250.0, 500.0, 750.0, 1_000.0, 1_500.0, 2_500.0, 5_000.0, 10_000.0,
                 15_000.0, 30_000.0,
@@ -40,18 +42,114 @@ pub(crate) fn response_time_ms() -> Histogram {
         )
         .unwrap()
     });
-    METER.clone()
+    METER.with_label_values(&[api_key])
 }
 
-pub(crate) fn response_status_code_counter(status_code: &str) -> IntCounter {
+pub(crate) fn response_status_code_counter(api_key: &str, status_code: &str) -> IntCounter {
     static METER: Lazy<IntCounterVec> = Lazy::new(|| {
         ftd_metrics::registry::register_int_counter_vec(
             METRIC_PREFIX,
             "response_status_code_count",
             "The number of response status codes",
-            &["status_code"],
+            &["api_key", "status_code"],
+        )
+        .unwrap()
+    });
+    METER.with_label_values(&[api_key, status_code])
+}
+
+pub(crate) fn substrate_connection_status() -> IntGauge {
+    static METER: Lazy<IntGauge> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_gauge(
+            METRIC_PREFIX,
+            "substrate_connection_status",
+            "Boolean value for the result of the last Substrate client health check",
+        )
+        .unwrap()
+    });
+    METER.clone()
+}
+
+pub(crate) fn substrate_reconnection_count() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_counter(
+            METRIC_PREFIX,
+            "substrate_reconnection_count",
+            "The number of Substrate client (re)connections",
+        )
+        .unwrap()
+    });
+    METER.clone()
+}
+
+pub(crate) fn balance_cache_hit_count() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_counter(
+            METRIC_PREFIX,
+            "balance_cache_hit_count",
+            "The number of account balances served from the cache",
+        )
+        .unwrap()
+    });
+    METER.clone()
+}
+
+pub(crate) fn balance_cache_miss_count() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_counter(
+            METRIC_PREFIX,
+            "balance_cache_miss_count",
+            "The number of account balances fetched from the Substrate node",
+        )
+        .unwrap()
+    });
+    METER.clone()
+}
+
+pub(crate) fn transfer_stream_client_count() -> IntGauge {
+    static METER: Lazy<IntGauge> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_gauge(
+            METRIC_PREFIX,
+            "transfer_stream_client_count",
+            "Number of clients currently connected to the transfer stream",
+        )
+        .unwrap()
+    });
+    METER.clone()
+}
+
+pub(crate) fn response_cache_hit_count() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_counter(
+            METRIC_PREFIX,
+            "response_cache_hit_count",
+            "The number of account search and graph responses served from the cache",
         )
         .unwrap()
     });
-    METER.with_label_values(&[status_code])
+    METER.clone()
+}
+
+pub(crate) fn response_cache_miss_count() -> IntCounter {
+    static METER: Lazy<IntCounter> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_counter(
+            METRIC_PREFIX,
+            "response_cache_miss_count",
+            "The number of account search and graph responses missing or expired in the cache",
+        )
+        .unwrap()
+    });
+    METER.clone()
+}
+
+pub(crate) fn response_cache_entry_count() -> IntGauge {
+    static METER: Lazy<IntGauge> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_gauge(
+            METRIC_PREFIX,
+            "response_cache_entry_count",
+            "Number of responses currently in the cache",
+        )
+        .unwrap()
+    });
+    METER.clone()
 }
@@ -0,0 +1,50 @@
+use crate::{account, alert, api_key, identity, label, stream, transfer};
+use actix_web::{get, HttpResponse};
+use once_cell::sync::Lazy;
+use utoipa::OpenApi;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Follow the DOT API",
+        description = "Account search, account details, transfer graph, identity history, identity registrar, transfer list, transfer stream, watchlist, alert, label and API key endpoints of followthedot.live. Requests carry an API key in the `X-FTD-API-Key` header, admin endpoints the admin key in the `X-FTD-Admin-Key` header. Errors carry a stable `code`: `INVALID_PARAMETER` and `INVALID_ADDRESS` (400), `UNAUTHORIZED` (401), `FORBIDDEN` (403), `NOT_FOUND` (404), `RATE_LIMITED` (429, with `Retry-After`), `INTERNAL_ERROR` (500), `UPSTREAM_NODE_UNAVAILABLE` and `SUBSCAN_QUOTA_EXCEEDED` (503) and `DATABASE_TIMEOUT` (504). GraphQL errors carry the same code in the `code` extension."
+    ),
+    paths(
+        account::account_search_service,
+        account::account_details_service,
+        account::account_graph_service,
+        identity::identity_timeline_service,
+        identity::historical_identity_service,
+        identity::registrar_list_service,
+        transfer::transfer_list_service,
+        stream::transfer_stream_service,
+        alert::watch_entry_list_service,
+        alert::watch_entry_create_service,
+        alert::watch_entry_delete_service,
+        alert::alert_list_service,
+        label::label_list_service,
+        label::label_save_service,
+        label::label_delete_service,
+        api_key::api_key_list_service,
+        api_key::api_key_create_service,
+        api_key::api_key_delete_service,
+    ),
+    tags(
+        (name = "account", description = "Accounts, identities and transfer graphs."),
+        (name = "identity", description = "Identity history of accounts and identity registrars."),
+        (name = "transfer", description = "Transfers between accounts."),
+        (name = "alert", description = "Watchlist and alert history."),
+        (name = "label", description = "Off-chain account labels."),
+        (name = "api_key", description = "API keys and their rate limits."),
+    )
+)]
+pub(crate) struct APIDoc;
+
+static OPENAPI_JSON: Lazy<String> = Lazy::new(|| APIDoc::openapi().to_pretty_json().unwrap());
+
+#[get("/openapi.json")]
+pub(crate) async fn openapi_service() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("application/json")
+        .body(OPENAPI_JSON.as_str())
+}
@@ -0,0 +1,229 @@
+use crate::address::{parse_address, validate_ss58_prefix, with_ss58_prefix};
+use crate::auth::check_premium_scope;
+use crate::{metrics, ResultResponse, ServiceState, CONFIG};
+use actix_web::web::Bytes;
+use actix_web::{get, web, HttpRequest, HttpResponse};
+use ftd_persistence::relational::RelationalStorage;
+use ftd_service::err::APIError;
+use ftd_types::api::transfer::Transfer;
+use ftd_types::err::ServiceError;
+use rustc_hash::FxHashSet as HashSet;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use utoipa::IntoParams;
+
+/// Newly saved transfer, with the identity information needed by the stream filters.
+#[derive(Clone, Debug)]
+pub(crate) struct StreamedTransfer {
+    transfer: Transfer,
+    /// Whether the sender or the recipient has an identity or a sub-identity.
+    has_identity: bool,
+}
+
+async fn get_streamed_transfer(
+    relational_storage: &RelationalStorage,
+    transfer: Transfer,
+) -> anyhow::Result<StreamedTransfer> {
+    let addresses = [transfer.from_address.clone(), transfer.to_address.clone()];
+    let has_identity = !relational_storage
+        .get_identities_by_addresses(&addresses)
+        .await?
+        .is_empty()
+        || !relational_storage
+            .get_sub_identities_by_addresses(&addresses)
+            .await?
+            .is_empty();
+    Ok(StreamedTransfer {
+        transfer,
+        has_identity,
+    })
+}
+
+/// Listens to the transfers saved by the indexer and publishes them to the connected stream
+/// clients. Reconnects to the database on listener errors.
+pub(crate) async fn broadcast_transfers(
+    relational_storage: Arc<RelationalStorage>,
+    sender: broadcast::Sender<Arc<StreamedTransfer>>,
+) {
+    loop {
+        match relational_storage.listen_transfers().await {
+            Ok(mut listener) => {
+                log::info!("Listening to transfer notifications.");
+                loop {
+                    match relational_storage.receive_transfer(&mut listener).await {
+                        Ok(Some(transfer)) => {
+                            if sender.receiver_count() == 0 {
+                                continue;
+                            }
+                            match get_streamed_transfer(&relational_storage, transfer).await {
+                                Ok(streamed_transfer) => {
+                                    let _ = sender.send(Arc::new(streamed_transfer));
+                                }
+                                Err(error) => {
+                                    log::error!("Cannot get streamed transfer: {error:?}");
+                                }
+                            }
+                        }
+                        Ok(None) => (),
+                        Err(error) => {
+                            log::error!("Transfer listener error: {error:?}");
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(error) => log::error!("Cannot listen to transfer notifications: {error:?}"),
+        }
+        tokio::time::sleep(Duration::from_secs(CONFIG.common.recovery_retry_seconds)).await;
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub(crate) struct TransferStreamQueryParameters {
+    /// Comma-separated SS58 addresses or account id hexes. Only the transfers from or to one
+    /// of these accounts are streamed.
+    addresses: Option<String>,
+    /// Minimum transfer amount in Planck.
+    min_amount: Option<String>,
+    /// Stream only the transfers where the sender or the recipient has an identity if `true`,
+    /// or neither has one if `false`.
+    has_identity: Option<bool>,
+    /// SS58 prefix of the addresses in the stream, the prefix of the chain by default.
+    ss58_prefix: Option<u16>,
+}
+
+struct TransferStreamFilter {
+    addresses: Option<HashSet<String>>,
+    min_amount: Option<u128>,
+    has_identity: Option<bool>,
+}
+
+impl TransferStreamFilter {
+    fn from_query(query: &TransferStreamQueryParameters) -> Result<Self, APIError> {
+        let addresses = match query.addresses.as_deref() {
+            Some(addresses) => {
+                let mut address_set = HashSet::default();
+                for address in addresses.split(',').map(str::trim) {
+                    address_set.insert(parse_address(address, "address")?);
+                }
+                Some(address_set)
+            }
+            None => None,
+        };
+        let min_amount = match query.min_amount.as_deref() {
+            Some(min_amount) => Some(min_amount.trim().parse::<u128>().map_err(|_| {
+                APIError::InvalidParameter(format!("Invalid minimum amount {min_amount}."))
+            })?),
+            None => None,
+        };
+        Ok(Self {
+            addresses,
+            min_amount,
+            has_identity: query.has_identity,
+        })
+    }
+
+    fn matches(&self, streamed_transfer: &StreamedTransfer) -> bool {
+        let transfer = &streamed_transfer.transfer;
+        if let Some(addresses) = &self.addresses {
+            if !addresses.contains(&transfer.from_address)
+                && !addresses.contains(&transfer.to_address)
+            {
+                return false;
+            }
+        }
+        if let Some(min_amount) = self.min_amount {
+            if transfer.amount < min_amount {
+                return false;
+            }
+        }
+        if let Some(has_identity) = self.has_identity {
+            if streamed_transfer.has_identity != has_identity {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Decrements the connected client count when the client's stream is dropped.
+struct TransferStreamClient;
+
+impl TransferStreamClient {
+    fn new() -> Self {
+        metrics::transfer_stream_client_count().inc();
+        Self
+    }
+}
+
+impl Drop for TransferStreamClient {
+    fn drop(&mut self) {
+        metrics::transfer_stream_client_count().dec();
+    }
+}
+
+#[utoipa::path(
+    tag = "transfer",
+    params(TransferStreamQueryParameters),
+    responses(
+        (status = 200, description = "Server-sent events stream of the newly indexed transfers. Each `transfer` event carries a transfer as JSON.", content_type = "text/event-stream", body = Transfer),
+        (status = 400, description = "Invalid filter.", body = ServiceError),
+        (status = 403, description = "The transfer stream requires a premium API key.", body = ServiceError),
+    ),
+)]
+#[get("/transfer/stream")]
+pub(crate) async fn transfer_stream_service(
+    request: HttpRequest,
+    query: web::Query<TransferStreamQueryParameters>,
+    state: web::Data<ServiceState>,
+) -> ResultResponse {
+    check_premium_scope(&request)?;
+    let filter = TransferStreamFilter::from_query(&query)?;
+    validate_ss58_prefix(query.ss58_prefix)?;
+    let maybe_ss58_prefix = query.ss58_prefix;
+    let receiver = state.transfer_sender.subscribe();
+    let keep_alive = Duration::from_secs(CONFIG.api.transfer_stream_keep_alive_seconds);
+    let stream = futures_util::stream::unfold(
+        (receiver, filter, TransferStreamClient::new()),
+        move |(mut receiver, filter, client)| async move {
+            loop {
+                let event = match tokio::time::timeout(keep_alive, receiver.recv()).await {
+                    // comment line to keep the connection open
+                    Err(_) => ":\n\n".to_string(),
+                    Ok(Ok(streamed_transfer)) => {
+                        if !filter.matches(&streamed_transfer) {
+                            continue;
+                        }
+                        let transfer =
+                            with_ss58_prefix(streamed_transfer.transfer.clone(), maybe_ss58_prefix);
+                        match serde_json::to_string(&transfer) {
+                            Ok(json) => format!("event: transfer\ndata: {json}\n\n"),
+                            Err(error) => {
+                                log::error!("Cannot serialize streamed transfer: {error:?}");
+                                continue;
+                            }
+                        }
+                    }
+                    Ok(Err(RecvError::Lagged(count))) => {
+                        log::warn!("Transfer stream client lagged by {count} transfers.");
+                        format!("event: lagged\ndata: {count}\n\n")
+                    }
+                    Ok(Err(RecvError::Closed)) => return None,
+                };
+                return Some((
+                    Ok::<Bytes, actix_web::Error>(Bytes::from(event)),
+                    (receiver, filter, client),
+                ));
+            }
+        },
+    );
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(stream))
+}
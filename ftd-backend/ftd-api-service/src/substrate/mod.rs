@@ -0,0 +1,171 @@
+use crate::block::ResolvedBlock;
+use crate::{metrics, CONFIG};
+use ftd_persistence::REDENOMINATION_BLOCK_NUMBER;
+use ftd_substrate_client::SubstrateClient;
+use ftd_types::substrate::account_id::AccountId;
+use ftd_types::substrate::balance::Balance;
+use rustc_hash::FxHashMap as HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// Long-lived Substrate client shared by all API workers. The underlying websocket
+/// connection is established lazily and re-established when it's found to be disconnected,
+/// either on use or by the periodic health check.
+pub(crate) struct ReconnectingSubstrateClient {
+    maybe_client: RwLock<Option<Arc<SubstrateClient>>>,
+}
+
+impl ReconnectingSubstrateClient {
+    pub(crate) fn new() -> Self {
+        Self {
+            maybe_client: RwLock::new(None),
+        }
+    }
+
+    async fn connect() -> anyhow::Result<Arc<SubstrateClient>> {
+        let client = SubstrateClient::new(
+            &CONFIG.substrate.rpc_url,
+            CONFIG.substrate.connection_timeout_seconds,
+            CONFIG.substrate.request_timeout_seconds,
+        )
+        .await?;
+        metrics::substrate_reconnection_count().inc();
+        Ok(Arc::new(client))
+    }
+
+    pub(crate) async fn get_client(&self) -> anyhow::Result<Arc<SubstrateClient>> {
+        if let Some(client) = self.maybe_client.read().await.as_ref() {
+            if client.is_connected() {
+                return Ok(client.clone());
+            }
+        }
+        let mut maybe_client = self.maybe_client.write().await;
+        // another request might have reconnected while waiting for the lock
+        if let Some(client) = maybe_client.as_ref() {
+            if client.is_connected() {
+                return Ok(client.clone());
+            }
+            log::warn!("Substrate client disconnected. Reconnecting.");
+        }
+        let client = Self::connect().await?;
+        *maybe_client = Some(client.clone());
+        Ok(client)
+    }
+
+    /// Verifies that the connection is alive with a lightweight RPC call, and reconnects
+    /// if it's not.
+    pub(crate) async fn check_health(&self) {
+        let is_healthy = match self.get_client().await {
+            Ok(client) => match client.get_finalized_block_hash().await {
+                Ok(_) => true,
+                Err(error) => {
+                    log::error!("Substrate client health check failed: {error:?}");
+                    *self.maybe_client.write().await = None;
+                    false
+                }
+            },
+            Err(error) => {
+                log::error!("Cannot connect to the Substrate node: {error:?}");
+                false
+            }
+        };
+        metrics::substrate_connection_status().set(is_healthy as i64);
+    }
+}
+
+/// Short-lived cache of current account balances. Balances at a specific block are not
+/// cached here.
+pub(crate) struct BalanceCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<AccountId, (Instant, Option<Balance>)>>,
+}
+
+impl BalanceCache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::default()),
+        }
+    }
+
+    /// Returns the cached balances and the account ids that are missing or expired in the cache.
+    fn get(
+        &self,
+        account_ids: &[AccountId],
+    ) -> (HashMap<AccountId, Option<Balance>>, Vec<AccountId>) {
+        let entries = self.entries.lock().unwrap();
+        let mut balance_map = HashMap::default();
+        let mut missing_account_ids = Vec::new();
+        for account_id in account_ids {
+            match entries.get(account_id) {
+                Some((inserted_at, balance)) if inserted_at.elapsed() < self.ttl => {
+                    balance_map.insert(*account_id, balance.clone());
+                }
+                _ => missing_account_ids.push(*account_id),
+            }
+        }
+        (balance_map, missing_account_ids)
+    }
+
+    fn insert(&self, account_id: AccountId, balance: Option<Balance>) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(account_id, (Instant::now(), balance));
+    }
+
+    pub(crate) fn purge_expired(&self) {
+        self.entries
+            .lock()
+            .unwrap()
+            .retain(|_, (inserted_at, _)| inserted_at.elapsed() < self.ttl);
+    }
+}
+
+/// Gets the balances of the given accounts at the given block, or the current balances if no
+/// block is given. Only current balances are served from the cache.
+pub(crate) async fn get_balances(
+    substrate_client: &ReconnectingSubstrateClient,
+    balance_cache: &BalanceCache,
+    account_ids: &[AccountId],
+    maybe_block: Option<&ResolvedBlock>,
+) -> anyhow::Result<HashMap<AccountId, Option<Balance>>> {
+    if let Some(block) = maybe_block {
+        let balance_map = substrate_client
+            .get_client()
+            .await?
+            .get_balances(account_ids, Some(block.hash.as_str()))
+            .await?;
+        return Ok(account_ids
+            .iter()
+            .map(|account_id| {
+                let balance = balance_map.get(account_id).map(|balance| {
+                    if block.number < REDENOMINATION_BLOCK_NUMBER {
+                        balance.convert_to_old_dot()
+                    } else {
+                        balance.clone()
+                    }
+                });
+                (*account_id, balance)
+            })
+            .collect());
+    }
+    let (mut balance_map, missing_account_ids) = balance_cache.get(account_ids);
+    metrics::balance_cache_hit_count().inc_by(balance_map.len() as u64);
+    metrics::balance_cache_miss_count().inc_by(missing_account_ids.len() as u64);
+    if missing_account_ids.is_empty() {
+        return Ok(balance_map);
+    }
+    let fetched_balance_map = substrate_client
+        .get_client()
+        .await?
+        .get_balances(&missing_account_ids, None)
+        .await?;
+    for account_id in missing_account_ids {
+        let balance = fetched_balance_map.get(&account_id).cloned();
+        balance_cache.insert(account_id, balance.clone());
+        balance_map.insert(account_id, balance);
+    }
+    Ok(balance_map)
+}
@@ -1,33 +1,43 @@
+use crate::address::{parse_address, validate_ss58_prefix, with_ss58_prefix};
 use crate::{ResultResponse, ServiceState};
 use actix_web::{get, web, HttpResponse};
+use ftd_types::api::transfer::Transfer;
 use ftd_types::err::ServiceError;
-use ftd_types::substrate::account_id::AccountId;
 use serde::Deserialize;
-use std::str::FromStr;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
 pub(crate) struct TransferListQueryParameters {
+    /// Sender SS58 address or account id hex.
     from: String,
+    /// Recipient SS58 address or account id hex.
     to: String,
+    /// SS58 prefix of the addresses in the response, the prefix of the chain by default.
+    ss58_prefix: Option<u16>,
 }
 
+#[utoipa::path(
+    tag = "transfer",
+    params(TransferListQueryParameters),
+    responses(
+        (status = 200, description = "Transfers from the sender to the recipient, latest first.", body = Vec<Transfer>),
+        (status = 400, description = "Invalid address or SS58 prefix.", body = ServiceError),
+        (status = 500, description = "Internal server error.", body = ServiceError),
+    ),
+)]
 #[get("/transfer")]
 pub(crate) async fn transfer_list_service(
     query: web::Query<TransferListQueryParameters>,
     state: web::Data<ServiceState>,
 ) -> ResultResponse {
-    if AccountId::from_str(query.from.as_str()).is_err() {
-        return Ok(HttpResponse::BadRequest().json(ServiceError::from("Invalid sender address.")));
-    }
-    if AccountId::from_str(query.to.as_str()).is_err() {
-        return Ok(
-            HttpResponse::BadRequest().json(ServiceError::from("Invalid recipient address."))
-        );
-    }
-    Ok(HttpResponse::Ok().json(
-        state
-            .relational_storage
-            .get_transfers_by_sender_and_recipient(query.from.as_str(), query.to.as_str())
-            .await?,
-    ))
+    let from = parse_address(query.from.as_str(), "sender address")?;
+    let to = parse_address(query.to.as_str(), "recipient address")?;
+    validate_ss58_prefix(query.ss58_prefix)?;
+    let transfers = state
+        .relational_storage
+        .get_transfers_by_sender_and_recipient(from.as_str(), to.as_str())
+        .await?;
+    Ok(HttpResponse::Ok().json(with_ss58_prefix(transfers, query.ss58_prefix)))
 }
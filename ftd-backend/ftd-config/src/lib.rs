@@ -62,6 +62,22 @@ pub struct IndexerConfig {
 #[derive(Clone, Debug, Deserialize)]
 pub struct IdentityUpdaterConfig {
     pub sleep_seconds: u64,
+    pub backfill_relay_block_numbers: Vec<u64>,
+    pub backfill_people_start_block_number: Option<u64>,
+    pub backfill_people_block_step: u64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct RelationUpdaterConfig {
+    pub sleep_seconds: u64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct AlertEvaluatorConfig {
+    pub sleep_seconds: u64,
+    pub webhook_timeout_seconds: u64,
+    pub max_delivery_attempts: u32,
+    pub retry_base_delay_seconds: u64,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -97,6 +113,20 @@ pub struct APIConfig {
     pub api_service_port: u16,
     pub account_search_limit: u16,
     pub graph_search_limit: u16,
+    pub substrate_health_check_seconds: u64,
+    pub balance_cache_ttl_seconds: u64,
+    pub graphql_max_depth: usize,
+    pub graphql_max_complexity: usize,
+    pub transfer_stream_channel_capacity: usize,
+    pub transfer_stream_keep_alive_seconds: u64,
+    pub admin_key: String,
+    pub api_key_required: bool,
+    pub api_key_refresh_seconds: u64,
+    pub default_rate_limit_per_minute: u32,
+    pub default_rate_limit_burst: u32,
+    pub response_cache_capacity: usize,
+    pub response_cache_ttl_seconds: u64,
+    pub response_cache_invalidation_seconds: u64,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -116,6 +146,8 @@ pub struct MetricsConfig {
     pub graph_updater_port: u16,
     pub subscan_account_fetcher_port: u16,
     pub api_service_port: u16,
+    pub alert_evaluator_port: u16,
+    pub relation_updater_port: u16,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -129,6 +161,8 @@ pub struct Config {
     pub indexer: IndexerConfig,
     pub subscan: SubscanConfig,
     pub identity_updater: IdentityUpdaterConfig,
+    pub relation_updater: RelationUpdaterConfig,
+    pub alert_evaluator: AlertEvaluatorConfig,
     pub metrics: MetricsConfig,
 }
 
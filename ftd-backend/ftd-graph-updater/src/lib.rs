@@ -4,6 +4,7 @@ use ftd_persistence::graph::GraphStorage;
 use ftd_persistence::relational::RelationalStorage;
 use ftd_service::Service;
 use lazy_static::lazy_static;
+use rustc_hash::FxHashSet as HashSet;
 
 mod metrics;
 
@@ -11,6 +12,11 @@ lazy_static! {
     static ref CONFIG: Config = Config::default();
 }
 
+/// Number of identity changes projected onto the graph in one transaction.
+const IDENTITY_CHANGE_BATCH_SIZE: u16 = 1000;
+/// Number of account relation changes projected onto the graph in one transaction.
+const ACCOUNT_RELATION_CHANGE_BATCH_SIZE: u16 = 1000;
+
 #[derive(Default)]
 pub struct GraphUpdater;
 
@@ -43,6 +49,101 @@ impl GraphUpdater {
         log::info!("Max transfer id {max_transfer_id} is processed.");
         Ok(())
     }
+
+    /// Projects the identity changes logged by the identity updater onto the account nodes and
+    /// `SUB_OF` edges. The changed accounts get their current identity and sub identity, so
+    /// reprocessing a batch or processing backfilled historical changes is harmless.
+    async fn process_identity_changes(
+        &self,
+        relational_storage: &RelationalStorage,
+        graph_storage: &GraphStorage,
+    ) -> anyhow::Result<()> {
+        let state = graph_storage.get_state().await?;
+        let mut last_processed_id = state.last_processed_identity_change_id;
+        loop {
+            let changes = relational_storage
+                .get_identity_changes_after(last_processed_id, IDENTITY_CHANGE_BATCH_SIZE)
+                .await?;
+            let Some(last_change) = changes.last() else {
+                break;
+            };
+            let last_id = last_change.id;
+            log::info!(
+                "Process identity changes {}-{last_id}.",
+                last_processed_id + 1
+            );
+            let mut processed_addresses = HashSet::default();
+            let mut tx = graph_storage.begin_tx().await?;
+            for change in changes.iter() {
+                if !processed_addresses.insert(change.address.as_str()) {
+                    continue;
+                }
+                let maybe_identity = relational_storage
+                    .get_identity_by_address(&change.address)
+                    .await?;
+                let maybe_sub_identity = relational_storage
+                    .get_sub_identity_by_address(&change.address)
+                    .await?;
+                graph_storage
+                    .save_account_identity(
+                        &mut tx,
+                        &change.address,
+                        maybe_identity.as_ref(),
+                        maybe_sub_identity.as_ref(),
+                    )
+                    .await?;
+            }
+            graph_storage
+                .update_last_processed_identity_change_id(&mut tx, last_id)
+                .await?;
+            graph_storage.commit_tx(tx).await?;
+            metrics::processed_identity_change_id().set(last_id as i64);
+            last_processed_id = last_id;
+        }
+        log::info!("Max identity change id {last_processed_id} is processed.");
+        Ok(())
+    }
+
+    /// Projects the proxy, multisig and staking relation changes onto the typed relation
+    /// edges, in the order they were logged.
+    async fn process_account_relation_changes(
+        &self,
+        relational_storage: &RelationalStorage,
+        graph_storage: &GraphStorage,
+    ) -> anyhow::Result<()> {
+        let state = graph_storage.get_state().await?;
+        let mut last_processed_id = state.last_processed_account_relation_change_id;
+        loop {
+            let changes = relational_storage
+                .get_account_relation_changes_after(
+                    last_processed_id,
+                    ACCOUNT_RELATION_CHANGE_BATCH_SIZE,
+                )
+                .await?;
+            let Some(last_change) = changes.last() else {
+                break;
+            };
+            let last_id = last_change.id;
+            log::info!(
+                "Process account relation changes {}-{last_id}.",
+                last_processed_id + 1
+            );
+            let mut tx = graph_storage.begin_tx().await?;
+            for change in changes.iter() {
+                graph_storage
+                    .save_account_relation_change(&mut tx, change)
+                    .await?;
+            }
+            graph_storage
+                .update_last_processed_account_relation_change_id(&mut tx, last_id)
+                .await?;
+            graph_storage.commit_tx(tx).await?;
+            metrics::processed_account_relation_change_id().set(last_id as i64);
+            last_processed_id = last_id;
+        }
+        log::info!("Max account relation change id {last_processed_id} is processed.");
+        Ok(())
+    }
 }
 
 #[async_trait(? Send)]
@@ -62,6 +163,10 @@ impl Service for GraphUpdater {
         loop {
             self.process_transfers(&relational_storage, &graph_storage)
                 .await?;
+            self.process_identity_changes(&relational_storage, &graph_storage)
+                .await?;
+            self.process_account_relation_changes(&relational_storage, &graph_storage)
+                .await?;
             log::info!("Completed processing. Sleep for {sleep_seconds} seconds.");
             tokio::time::sleep(std::time::Duration::from_secs(sleep_seconds)).await;
         }
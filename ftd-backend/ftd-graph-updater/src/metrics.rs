@@ -14,3 +14,27 @@ pub fn processed_transfer_id() -> IntGauge {
     });
     METER.clone()
 }
+
+pub fn processed_identity_change_id() -> IntGauge {
+    static METER: Lazy<IntGauge> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_gauge(
+            _METRIC_PREFIX,
+            "processed_identity_change_id",
+            "Id of the last processed identity change",
+        )
+        .unwrap()
+    });
+    METER.clone()
+}
+
+pub fn processed_account_relation_change_id() -> IntGauge {
+    static METER: Lazy<IntGauge> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_gauge(
+            _METRIC_PREFIX,
+            "processed_account_relation_change_id",
+            "Id of the last processed account relation change",
+        )
+        .unwrap()
+    });
+    METER.clone()
+}
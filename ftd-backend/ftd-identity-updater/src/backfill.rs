@@ -0,0 +1,204 @@
+//! One-shot historical identity backfill. Samples the relay chain identities at the configured
+//! blocks and the People chain identities since the migration, and appends the changes
+//! between consecutive samples to the identity change log, before the changes observed by the
+//! identity updater.
+use crate::{metrics, record_diff, CONFIG};
+use async_trait::async_trait;
+use ftd_persistence::relational::RelationalStorage;
+use ftd_service::Service;
+use ftd_substrate_client::SubstrateClient;
+use ftd_types::api::identity::IdentityChain;
+use ftd_types::diff::Diff;
+use ftd_types::substrate::identity::{Identity, IdentityChangeBlock, SubIdentity};
+
+/// Identities and sub identities at a sampled block.
+#[derive(Default)]
+struct Sample {
+    identities: Vec<Identity>,
+    sub_identities: Vec<SubIdentity>,
+}
+
+#[derive(Default)]
+pub struct IdentityBackfill;
+
+impl IdentityBackfill {
+    async fn get_block(
+        &self,
+        substrate_client: &SubstrateClient,
+        chain: IdentityChain,
+        block_number: u64,
+    ) -> anyhow::Result<IdentityChangeBlock> {
+        let block_hash = substrate_client
+            .get_block_hash(block_number)
+            .await?
+            .trim_start_matches("0x")
+            .to_string();
+        let timestamp = substrate_client.get_block_timestamp(&block_hash).await?;
+        Ok(IdentityChangeBlock {
+            chain,
+            hash: block_hash,
+            number: block_number,
+            timestamp,
+        })
+    }
+
+    async fn get_sample(
+        &self,
+        substrate_client: &SubstrateClient,
+        block: &IdentityChangeBlock,
+    ) -> anyhow::Result<Sample> {
+        log::info!(
+            "Sample {} chain identities @ {}.",
+            block.chain,
+            block.number
+        );
+        let identities = match block.chain {
+            IdentityChain::Relay => substrate_client.get_legacy_identities(&block.hash).await?,
+            IdentityChain::People => substrate_client.get_identities(&block.hash).await?,
+        };
+        let sub_identities = substrate_client.get_sub_identities(&block.hash).await?;
+        log::info!(
+            "Got {} identities and {} sub identities.",
+            identities.len(),
+            sub_identities.len(),
+        );
+        Ok(Sample {
+            identities,
+            sub_identities,
+        })
+    }
+
+    /// Saves the changes from the previous sample to the current one. Only the removals are
+    /// saved if `is_removal_only`.
+    async fn save_changes(
+        &self,
+        relational_storage: &RelationalStorage,
+        previous: Sample,
+        current: &Sample,
+        block: &IdentityChangeBlock,
+        is_removal_only: bool,
+    ) -> anyhow::Result<()> {
+        let identity_diff = Diff::new(previous.identities, current.identities.clone());
+        let sub_identity_diff = Diff::new(previous.sub_identities, current.sub_identities.clone());
+        let (saved_identities, saved_sub_identities) = if is_removal_only {
+            (Vec::new(), Vec::new())
+        } else {
+            (identity_diff.saved(), sub_identity_diff.saved())
+        };
+        relational_storage
+            .save_backfill_identity_changes(
+                &saved_identities,
+                &identity_diff.removed,
+                &saved_sub_identities,
+                &sub_identity_diff.removed,
+                block,
+            )
+            .await?;
+        record_diff("backfill_identity", &identity_diff);
+        record_diff("backfill_sub_identity", &sub_identity_diff);
+        Ok(())
+    }
+}
+
+#[async_trait(? Send)]
+impl Service for IdentityBackfill {
+    fn get_metrics_server_addr() -> (&'static str, u16) {
+        (
+            CONFIG.metrics.host.as_str(),
+            CONFIG.metrics.identity_updater_port,
+        )
+    }
+
+    async fn run(&'static self) -> anyhow::Result<()> {
+        log::info!("Identity backfill started.");
+        // the People chain has no identities before the migration block, so that sampling from
+        // an earlier block would clear all the relay chain identities
+        let start_block_number = match CONFIG.identity_updater.backfill_people_start_block_number {
+            Some(start_block_number) if start_block_number > 1 => start_block_number,
+            _ => anyhow::bail!(
+                "Set identity_updater.backfill_people_start_block_number to the block at which \
+                the identities were migrated to the People chain."
+            ),
+        };
+        metrics::last_run_timestamp_ms().set(chrono::Utc::now().timestamp_millis());
+        let relational_storage = RelationalStorage::new().await?;
+        // the backfill replaces its previous results
+        let deleted_count = relational_storage
+            .delete_backfill_identity_changes()
+            .await?;
+        log::info!("Deleted {deleted_count} previously backfilled identity changes.");
+        let mut previous = Sample::default();
+
+        let mut relay_block_numbers = CONFIG.identity_updater.backfill_relay_block_numbers.clone();
+        relay_block_numbers.sort_unstable();
+        if !relay_block_numbers.is_empty() {
+            let relay_client = SubstrateClient::new(
+                &CONFIG.substrate.rpc_url,
+                CONFIG.substrate.connection_timeout_seconds,
+                CONFIG.substrate.request_timeout_seconds,
+            )
+            .await?;
+            for block_number in relay_block_numbers {
+                let block = self
+                    .get_block(&relay_client, IdentityChain::Relay, block_number)
+                    .await?;
+                let sample = self.get_sample(&relay_client, &block).await?;
+                self.save_changes(&relational_storage, previous, &sample, &block, false)
+                    .await?;
+                previous = sample;
+            }
+        }
+
+        let people_client = SubstrateClient::new(
+            &CONFIG.substrate.people_rpc_url,
+            CONFIG.substrate.connection_timeout_seconds,
+            CONFIG.substrate.request_timeout_seconds,
+        )
+        .await?;
+        // the identity updater's log starts with all identities at its first block
+        let maybe_first_block = relational_storage.get_first_identity_change_block().await?;
+        let end_block_number = match &maybe_first_block {
+            Some(first_block) => first_block.number,
+            None => {
+                let block_hash = people_client.get_finalized_block_hash().await?;
+                people_client
+                    .get_block_header(&block_hash)
+                    .await?
+                    .get_number()?
+            }
+        };
+        let step = CONFIG.identity_updater.backfill_people_block_step.max(1);
+        let mut block_number = start_block_number;
+        while block_number < end_block_number {
+            let block = self
+                .get_block(&people_client, IdentityChain::People, block_number)
+                .await?;
+            let sample = self.get_sample(&people_client, &block).await?;
+            self.save_changes(&relational_storage, previous, &sample, &block, false)
+                .await?;
+            previous = sample;
+            block_number += step;
+        }
+        // only the identities removed between the last sample and the end block are missing
+        // from the log if the identity updater's log starts there
+        let is_removal_only = maybe_first_block.is_some();
+        let end_block = match maybe_first_block {
+            Some(first_block) => first_block,
+            None => {
+                self.get_block(&people_client, IdentityChain::People, end_block_number)
+                    .await?
+            }
+        };
+        let sample = self.get_sample(&people_client, &end_block).await?;
+        self.save_changes(
+            &relational_storage,
+            previous,
+            &sample,
+            &end_block,
+            is_removal_only,
+        )
+        .await?;
+        log::info!("Identity backfill completed.");
+        Ok(())
+    }
+}
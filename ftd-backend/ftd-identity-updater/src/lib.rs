@@ -3,14 +3,30 @@ use ftd_config::Config;
 use ftd_persistence::relational::RelationalStorage;
 use ftd_service::Service;
 use ftd_substrate_client::SubstrateClient;
+use ftd_types::api::identity::IdentityChain;
+use ftd_types::diff::{Diff, Entry};
+use ftd_types::substrate::identity::IdentityChangeBlock;
 use lazy_static::lazy_static;
 
+pub mod backfill;
 mod metrics;
 
 lazy_static! {
     static ref CONFIG: Config = Config::default();
 }
 
+fn record_diff<T: Entry>(kind: &str, diff: &Diff<T>) {
+    log::info!(
+        "{kind}: {} inserted, {} changed, {} removed.",
+        diff.inserted.len(),
+        diff.changed.len(),
+        diff.removed.len(),
+    );
+    metrics::identity_change_count(kind, "inserted").inc_by(diff.inserted.len() as u64);
+    metrics::identity_change_count(kind, "changed").inc_by(diff.changed.len() as u64);
+    metrics::identity_change_count(kind, "removed").inc_by(diff.removed.len() as u64);
+}
+
 #[derive(Default)]
 pub struct IdentityUpdater;
 
@@ -23,22 +39,76 @@ impl IdentityUpdater {
         block_number: u64,
     ) -> anyhow::Result<()> {
         log::info!("Get identity @ finalized block {block_number}.");
+        let block = IdentityChangeBlock {
+            chain: IdentityChain::People,
+            hash: block_hash.to_string(),
+            number: block_number,
+            timestamp: substrate_client.get_block_timestamp(block_hash).await?,
+        };
+        let registrars = substrate_client.get_registrars(block_hash).await?;
+        log::info!("Got {} registrars.", registrars.len());
+        relational_storage.save_registrars(&registrars).await?;
         let identities = substrate_client.get_identities(block_hash).await?;
         metrics::last_identity_list_fetch_timestamp_ms().set(chrono::Utc::now().timestamp_millis());
         log::info!("Got {} identities.", identities.len());
-        relational_storage.save_identities(&identities).await?;
+        let identity_diff = Diff::new(relational_storage.get_all_identities().await?, identities);
+        if relational_storage.is_identity_baseline_pending().await? {
+            // the stored identities predate newly decoded fields, so their differences are not
+            // identity changes
+            log::info!(
+                "Save {} changed identities as the baseline.",
+                identity_diff.changed.len()
+            );
+            relational_storage
+                .update_identities(&identity_diff.inserted, &identity_diff.removed, &block)
+                .await?;
+            relational_storage
+                .save_identity_baseline(&identity_diff.changed)
+                .await?;
+        } else if !identity_diff.is_empty() {
+            relational_storage
+                .update_identities(&identity_diff.saved(), &identity_diff.removed, &block)
+                .await?;
+        }
+        record_diff("identity", &identity_diff);
         metrics::last_identity_list_persist_timestamp_ms()
             .set(chrono::Utc::now().timestamp_millis());
-        log::info!("Saved identities.");
         let sub_identities = substrate_client.get_sub_identities(block_hash).await?;
         metrics::last_sub_identity_list_fetch_timestamp_ms()
             .set(chrono::Utc::now().timestamp_millis());
         log::info!("Got {} sub identities.", sub_identities.len());
-        relational_storage
-            .save_sub_identities(&sub_identities)
-            .await?;
+        let sub_identity_diff = Diff::new(
+            relational_storage.get_all_sub_identities().await?,
+            sub_identities,
+        );
+        if !sub_identity_diff.is_empty() {
+            relational_storage
+                .update_sub_identities(
+                    &sub_identity_diff.saved(),
+                    &sub_identity_diff.removed,
+                    &block,
+                )
+                .await?;
+        }
+        record_diff("sub_identity", &sub_identity_diff);
         metrics::last_sub_identity_list_persist_timestamp_ms()
             .set(chrono::Utc::now().timestamp_millis());
+        let authorities = substrate_client
+            .get_username_authorities(block_hash)
+            .await?;
+        log::info!("Got {} username authorities.", authorities.len());
+        relational_storage
+            .save_username_authorities(&authorities)
+            .await?;
+        let usernames = substrate_client.get_usernames(block_hash).await?;
+        log::info!("Got {} usernames.", usernames.len());
+        let username_diff = Diff::new(relational_storage.get_all_usernames().await?, usernames);
+        if !username_diff.is_empty() {
+            relational_storage
+                .update_usernames(&username_diff.saved(), &username_diff.removed)
+                .await?;
+        }
+        record_diff("username", &username_diff);
         Ok(())
     }
 }
@@ -1,12 +1,21 @@
+use ftd_identity_updater::backfill::IdentityBackfill;
 use ftd_identity_updater::IdentityUpdater;
 use ftd_service::Service;
 use lazy_static::lazy_static;
 
 lazy_static! {
     static ref SERVICE: IdentityUpdater = IdentityUpdater;
+    static ref BACKFILL: IdentityBackfill = IdentityBackfill;
 }
 
 #[tokio::main]
 async fn main() {
-    SERVICE.start().await;
+    match std::env::args().nth(1).as_deref() {
+        None => SERVICE.start().await,
+        Some("backfill") => BACKFILL.start().await,
+        Some(_) => {
+            eprintln!("Usage: ftd-identity-updater [backfill]");
+            std::process::exit(1);
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use ftd_metrics::registry::IntGauge;
+use ftd_metrics::registry::{IntCounter, IntCounterVec, IntGauge};
 use once_cell::sync::Lazy;
 
 const METRIC_PREFIX: &str = "ftd_identity_updater";
@@ -63,6 +63,20 @@ pub fn last_sub_identity_list_persist_timestamp_ms() -> IntGauge {
     METER.clone()
 }
 
+/// `kind` is `identity` or `sub_identity`, `change` is `inserted`, `changed` or `removed`.
+pub fn identity_change_count(kind: &str, change: &str) -> IntCounter {
+    static METER: Lazy<IntCounterVec> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_counter_vec(
+            METRIC_PREFIX,
+            "identity_change_count",
+            "The total number of identity, sub identity and username changes applied to the database",
+            &["kind", "change"],
+        )
+        .unwrap()
+    });
+    METER.with_label_values(&[kind, change])
+}
+
 pub fn last_success_status() -> IntGauge {
     static METER: Lazy<IntGauge> = Lazy::new(|| {
         ftd_metrics::registry::register_int_gauge(
@@ -0,0 +1,57 @@
+//! Imports account labels from CSV or JSON files. CSV files have an `address`, `name`,
+//! `category`, `source`, `confidence` and `labeledAt` header, JSON files contain an array
+//! of objects with the same fields. Confidence and labeling time are optional.
+use ftd_persistence::relational::RelationalStorage;
+use ftd_types::api::label::NewLabel;
+use ftd_types::substrate::chain::Chain;
+use std::path::Path;
+
+fn read_labels(path: &Path) -> anyhow::Result<Vec<NewLabel>> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_lowercase());
+    match extension.as_deref() {
+        Some("csv") => {
+            let mut reader = csv::ReaderBuilder::new()
+                .trim(csv::Trim::All)
+                .from_path(path)?;
+            let mut labels = Vec::new();
+            for record in reader.deserialize() {
+                labels.push(record?);
+            }
+            Ok(labels)
+        }
+        Some("json") => Ok(serde_json::from_reader(std::io::BufReader::new(
+            std::fs::File::open(path)?,
+        ))?),
+        _ => Err(anyhow::anyhow!(
+            "Unsupported label file {}. Expected a .csv or .json file.",
+            path.display()
+        )),
+    }
+}
+
+/// Saves the valid labels in the file and skips the invalid ones. Existing labels with the
+/// same address, name and source are updated. Addresses are stored in the SS58 format of the
+/// chain.
+pub async fn import_labels(path: &str, chain: &Chain) -> anyhow::Result<()> {
+    let labels = read_labels(Path::new(path))?;
+    log::info!("Read {} labels from {path}.", labels.len());
+    let storage = RelationalStorage::new().await?;
+    let mut saved_count = 0;
+    for (index, label) in labels.iter().enumerate() {
+        match label.normalize(chain) {
+            Ok(label) => {
+                storage.save_label(&label).await?;
+                saved_count += 1;
+            }
+            Err(description) => log::warn!("Skip label #{}: {description}", index + 1),
+        }
+    }
+    log::info!(
+        "Saved {saved_count} labels, skipped {}.",
+        labels.len() - saved_count
+    );
+    Ok(())
+}
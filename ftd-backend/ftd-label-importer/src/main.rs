@@ -0,0 +1,19 @@
+use ftd_config::Config;
+use ftd_types::substrate::chain::Chain;
+use std::str::FromStr;
+
+#[tokio::main]
+async fn main() {
+    let config = Config::default();
+    ftd_logging::init(&config);
+    let chain = Chain::from_str(&config.substrate.chain).unwrap();
+    chain.sp_core_set_default_ss58_version();
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("Usage: ftd-label-importer <labels.csv|labels.json>");
+        std::process::exit(1);
+    };
+    if let Err(error) = ftd_label_importer::import_labels(&path, &chain).await {
+        log::error!("Label import failed: {error:?}");
+        std::process::exit(1);
+    }
+}
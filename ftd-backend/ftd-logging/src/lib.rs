@@ -11,13 +11,16 @@ pub fn init(config: &Config) {
     let mut builder = Builder::from_env(Env::default());
     builder.target(Target::Stdout);
     builder.filter(None, other_modules_log_level);
+    builder.filter(Some("ftd_alert_evaluator"), log_level);
     builder.filter(Some("ftd_api_service"), log_level);
     builder.filter(Some("ftd_graph_updater"), log_level);
     builder.filter(Some("ftd_identity_updater"), log_level);
     builder.filter(Some("ftd_indexer"), log_level);
+    builder.filter(Some("ftd_label_importer"), log_level);
     builder.filter(Some("ftd_metrics"), log_level);
     builder.filter(Some("ftd_metrics_server"), log_level);
     builder.filter(Some("ftd_persistence"), log_level);
+    builder.filter(Some("ftd_relation_updater"), log_level);
     builder.filter(Some("ftd_sidecar_client"), log_level);
     builder.filter(Some("ftd_subscan_account_fetcher"), log_level);
     builder.filter(Some("ftd_subscan_client"), log_level);
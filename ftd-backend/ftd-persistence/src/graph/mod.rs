@@ -1,8 +1,11 @@
 use crate::CONFIG;
+use ftd_types::api::identity::{Identity, SubIdentity};
+use ftd_types::api::relation::{AccountRelation, AccountRelationChange};
 use ftd_types::graph::{GraphUpdaterState, TransferVolume};
 use ftd_types::substrate::event::TransferEvent;
 use neo4j::Neo4JStorage;
 use neo4rs::Txn;
+use rustc_hash::FxHashMap as HashMap;
 
 pub mod neo4j;
 
@@ -47,6 +50,16 @@ impl GraphStorage {
             .await
     }
 
+    pub async fn update_last_processed_account_relation_change_id(
+        &self,
+        tx: &mut Txn,
+        id: i32,
+    ) -> anyhow::Result<()> {
+        self.neo4j
+            .update_last_processed_account_relation_change_id(tx, id)
+            .await
+    }
+
     pub async fn save_transfer(
         &self,
         tx: &mut Txn,
@@ -58,6 +71,42 @@ impl GraphStorage {
         Ok(())
     }
 
+    pub async fn save_account_identity(
+        &self,
+        tx: &mut Txn,
+        address: &str,
+        maybe_identity: Option<&Identity>,
+        maybe_sub_identity: Option<&SubIdentity>,
+    ) -> anyhow::Result<()> {
+        self.neo4j
+            .save_account_identity(tx, address, maybe_identity, maybe_sub_identity)
+            .await
+    }
+
+    pub async fn save_account_relation_change(
+        &self,
+        tx: &mut Txn,
+        change: &AccountRelationChange,
+    ) -> anyhow::Result<()> {
+        if change.is_removed {
+            self.neo4j
+                .delete_account_relation(tx, &change.relation)
+                .await
+        } else {
+            self.neo4j.save_account_relation(tx, &change.relation).await
+        }
+    }
+
+    pub async fn get_account_relations_for_account(
+        &self,
+        address: &str,
+        limit: u16,
+    ) -> anyhow::Result<Vec<AccountRelation>> {
+        self.neo4j
+            .get_account_relations_for_account(address, limit)
+            .await
+    }
+
     pub async fn get_transfer_volumes_for_account(
         &self,
         address: &str,
@@ -67,4 +116,14 @@ impl GraphStorage {
             .get_transfer_volumes_for_account(address, limit)
             .await
     }
+
+    pub async fn get_transfer_volumes_for_accounts(
+        &self,
+        addresses: &[String],
+        limit: u16,
+    ) -> anyhow::Result<HashMap<String, Vec<TransferVolume>>> {
+        self.neo4j
+            .get_transfer_volumes_for_accounts(addresses, limit)
+            .await
+    }
 }
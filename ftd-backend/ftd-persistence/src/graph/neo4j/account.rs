@@ -1,4 +1,5 @@
 use super::Neo4JStorage;
+use ftd_types::api::identity::{Identity, SubIdentity};
 use neo4rs::{query, Txn};
 
 impl Neo4JStorage {
@@ -8,60 +9,88 @@ impl Neo4JStorage {
         Ok(())
     }
 
-    /*
-    pub async fn _save_account_with_identity(
+    /// Sets the identity properties and the `SUB_OF` edge of the account to the given identity
+    /// and sub identity. Missing identity fields remove the properties, a missing sub identity
+    /// removes the edge.
+    pub async fn save_account_identity(
         &self,
         tx: &mut Txn,
         address: &str,
-        identity: &Identity,
-        sub_identity: &SubIdentity,
+        maybe_identity: Option<&Identity>,
+        maybe_sub_identity: Option<&SubIdentity>,
     ) -> anyhow::Result<()> {
         self.save_account(tx, address).await?;
         tx.run(
             query(
                 r#"
-                    MATCH (a:Account)
-                    WHERE a.address = $address
-                    SET a.display = $display, a.legal = $legal, a.web = $web, a.riot = $riot, a.email = $email, a.twitter = $twitter, a.judgement = $judgement, a.sub_display = $sub_display
-                    "#,
+                MATCH (a:Account {address: $address})
+                SET a.display = $display, a.legal = $legal, a.web = $web, a.riot = $riot, a.email = $email, a.twitter = $twitter, a.isConfirmed = $is_confirmed, a.isInvalid = $is_invalid, a.subDisplay = $sub_display
+                "#,
             )
             .param("address", address)
-            .param("display", identity.display.as_deref())
-            .param("legal", identity.legal.as_deref())
-            .param("web", identity.web.as_deref())
-            .param("riot", identity.riot.as_deref())
-            .param("email", identity.email.as_deref())
-            .param("twitter", identity.twitter.as_deref())
-            .param("is_confirmed", identity.is_confirmed.as_deref())
-            .param("sub_display", sub_identity.sub_display.as_deref()),
+            .param(
+                "display",
+                maybe_identity.and_then(|identity| identity.display.as_deref()),
+            )
+            .param(
+                "legal",
+                maybe_identity.and_then(|identity| identity.legal.as_deref()),
+            )
+            .param(
+                "web",
+                maybe_identity.and_then(|identity| identity.web.as_deref()),
+            )
+            .param(
+                "riot",
+                maybe_identity.and_then(|identity| identity.riot.as_deref()),
+            )
+            .param(
+                "email",
+                maybe_identity.and_then(|identity| identity.email.as_deref()),
+            )
+            .param(
+                "twitter",
+                maybe_identity.and_then(|identity| identity.twitter.as_deref()),
+            )
+            .param(
+                "is_confirmed",
+                maybe_identity.map(|identity| identity.is_confirmed),
+            )
+            .param(
+                "is_invalid",
+                maybe_identity.map(|identity| identity.is_invalid),
+            )
+            .param(
+                "sub_display",
+                maybe_sub_identity.and_then(|sub_identity| sub_identity.sub_display.as_deref()),
+            ),
         )
         .await?;
-        if let Some(super_address) = sub_identity.super_account_id.as_deref() {
-            self.save_account(tx, super_address).await?;
-            tx.run(
-                query(
-                    r#"
-                            MATCH (a:Account {address: $address})-[s:SUB_OF]->(:Account)
-                            DELETE s
-                            "#,
-                )
-                .param("address", address),
+        tx.run(
+            query(
+                r#"
+                MATCH (a:Account {address: $address})-[s:SUB_OF]->(:Account)
+                DELETE s
+                "#,
             )
-            .await?;
+            .param("address", address),
+        )
+        .await?;
+        if let Some(sub_identity) = maybe_sub_identity {
+            self.save_account(tx, &sub_identity.super_address).await?;
             tx.run(
                 query(
                     r#"
-                            MATCH (a:Account {address: $address})
-                            MATCH (b:Account {address: $super_address})
-                            MERGE (a)-[:SUB_OF]->(b)
-                            "#,
+                    MATCH (a:Account {address: $address})
+                    MATCH (b:Account {address: $super_address})
+                    MERGE (a)-[:SUB_OF]->(b)
+                    "#,
                 )
                 .param("address", address)
-                .param("super_address", super_address),
+                .param("super_address", sub_identity.super_address.as_str()),
             )
             .await?;
         }
         Ok(())
     }
-     */
 }
@@ -2,6 +2,7 @@ use ftd_config::Config;
 use neo4rs::{ConfigBuilder, Graph, Txn};
 
 pub mod account;
+pub mod relation;
 pub mod state;
 pub mod transfer;
 
@@ -0,0 +1,111 @@
+use super::Neo4JStorage;
+use ftd_types::api::relation::{AccountRelation, AccountRelationType};
+use neo4rs::{query, Txn};
+
+impl Neo4JStorage {
+    /// Merges the typed relation edge between the accounts. Proxies of different types between
+    /// the same accounts are separate edges.
+    pub async fn save_account_relation(
+        &self,
+        tx: &mut Txn,
+        relation: &AccountRelation,
+    ) -> anyhow::Result<()> {
+        self.save_account(tx, &relation.from).await?;
+        self.save_account(tx, &relation.to).await?;
+        let edge_properties = if relation.relation_type == AccountRelationType::Proxy {
+            " {proxyType: $proxy_type}"
+        } else {
+            ""
+        };
+        tx.run(
+            query(
+                format!(
+                    r#"
+                    MATCH (a:Account {{address: $from}})
+                    MATCH (b:Account {{address: $to}})
+                    MERGE (a)-[r:{}{edge_properties}]->(b)
+                    SET r.proxyDelay = $proxy_delay, r.multisigThreshold = $multisig_threshold
+                    "#,
+                    relation.relation_type.graph_edge_type(),
+                )
+                .as_str(),
+            )
+            .param("from", relation.from.as_str())
+            .param("to", relation.to.as_str())
+            .param("proxy_type", relation.proxy_type.as_deref())
+            .param(
+                "proxy_delay",
+                relation.proxy_delay.map(|delay| delay as i64),
+            )
+            .param(
+                "multisig_threshold",
+                relation
+                    .multisig_threshold
+                    .map(|threshold| threshold as i64),
+            ),
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete_account_relation(
+        &self,
+        tx: &mut Txn,
+        relation: &AccountRelation,
+    ) -> anyhow::Result<()> {
+        tx.run(
+            query(
+                format!(
+                    r#"
+                    MATCH (:Account {{address: $from}})-[r:{}]->(:Account {{address: $to}})
+                    WHERE $proxy_type IS NULL OR r.proxyType = $proxy_type
+                    DELETE r
+                    "#,
+                    relation.relation_type.graph_edge_type(),
+                )
+                .as_str(),
+            )
+            .param("from", relation.from.as_str())
+            .param("to", relation.to.as_str())
+            .param("proxy_type", relation.proxy_type.as_deref()),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Gets up to `limit` relations from or to the account.
+    pub async fn get_account_relations_for_account(
+        &self,
+        address: &str,
+        limit: u16,
+    ) -> anyhow::Result<Vec<AccountRelation>> {
+        let mut result = self
+            .graph
+            .execute(
+                query(
+                    r#"
+                MATCH (a:Account {address: $address})-[r:PROXY_OF|MULTISIG_MEMBER|CONTROLLER_OF|PAYEE_OF]-(:Account)
+                RETURN startNode(r).address AS from, endNode(r).address AS to, type(r) AS edge_type, r.proxyType AS proxy_type, r.proxyDelay AS proxy_delay, r.multisigThreshold AS multisig_threshold
+                LIMIT $limit
+                "#,
+                )
+                .param("address", address)
+                .param("limit", limit),
+            )
+            .await?;
+        let mut relations = Vec::new();
+        while let Some(row) = result.next().await? {
+            let edge_type = row.get::<String>("edge_type")?;
+            relations.push(AccountRelation {
+                from: row.get("from")?,
+                to: row.get("to")?,
+                relation_type: AccountRelationType::from_graph_edge_type(&edge_type)
+                    .map_err(anyhow::Error::msg)?,
+                proxy_type: row.get("proxy_type")?,
+                proxy_delay: row.get("proxy_delay")?,
+                multisig_threshold: row.get("multisig_threshold")?,
+            });
+        }
+        Ok(relations)
+    }
+}
@@ -8,13 +8,22 @@ impl Neo4JStorage {
     pub async fn get_state(&self) -> anyhow::Result<GraphUpdaterState> {
         let mut result = self
             .graph
-            .execute(query("MERGE (s:State {id: 1}) ON CREATE SET s.lastProcessedTransferId = 0, s.lastProcessedIdentityChangeId = 0 RETURN s"))
+            .execute(query(
+                r#"
+                MERGE (s:State {id: 1})
+                ON CREATE SET s.lastProcessedTransferId = 0, s.lastProcessedIdentityChangeId = 0
+                SET s.lastProcessedAccountRelationChangeId = coalesce(s.lastProcessedAccountRelationChangeId, 0)
+                RETURN s
+                "#,
+            ))
             .await?;
         let row = result.next().await?.unwrap();
         let node = row.get::<Node>("s")?;
         Ok(GraphUpdaterState {
             last_processed_transfer_id: node.get("lastProcessedTransferId")?,
             last_processed_identity_change_id: node.get("lastProcessedIdentityChangeId")?,
+            last_processed_account_relation_change_id: node
+                .get("lastProcessedAccountRelationChangeId")?,
         })
     }
 
@@ -42,4 +51,17 @@ impl Neo4JStorage {
         .await?;
         Ok(())
     }
+
+    pub async fn update_last_processed_account_relation_change_id(
+        &self,
+        tx: &mut Txn,
+        id: i32,
+    ) -> anyhow::Result<()> {
+        tx.run(
+            query("MATCH (s:State {id: 1}) SET s.lastProcessedAccountRelationChangeId = $id")
+                .param("id", id),
+        )
+        .await?;
+        Ok(())
+    }
 }
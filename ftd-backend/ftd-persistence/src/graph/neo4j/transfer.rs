@@ -2,6 +2,7 @@ use super::Neo4JStorage;
 use ftd_types::graph::TransferVolume;
 use ftd_types::substrate::event::TransferEvent;
 use neo4rs::{query, Node, Relation, Txn};
+use rustc_hash::FxHashMap as HashMap;
 
 impl Neo4JStorage {
     async fn get_transfer_volume(
@@ -109,4 +110,58 @@ impl Neo4JStorage {
         }
         Ok(transfer_volumes)
     }
+
+    /// Gets up to `limit` transfer volumes for each of the given accounts, keyed by address.
+    pub async fn get_transfer_volumes_for_accounts(
+        &self,
+        addresses: &[String],
+        limit: u16,
+    ) -> anyhow::Result<HashMap<String, Vec<TransferVolume>>> {
+        let mut result = self
+            .graph
+            .execute(
+                query(
+                    r#"
+                UNWIND $addresses AS address
+                CALL {
+                    WITH address
+                    MATCH (a:Account {address: address})-[t:TRANSFER]-(b:Account)
+                    RETURN b, t, id(t) as t_id, (startNode(t) = a) as is_from_a
+                    LIMIT $limit
+                }
+                RETURN address, b, t, t_id, is_from_a
+                "#,
+                )
+                .param("addresses", addresses.to_vec())
+                .param("limit", limit),
+            )
+            .await?;
+        let mut transfer_volume_map: HashMap<String, Vec<TransferVolume>> = HashMap::default();
+        while let Some(row) = result.next().await? {
+            let address = row.get::<String>("address")?;
+            let other = row.get::<Node>("b")?;
+            let other_address = other.get::<String>("address")?;
+            let transfer_volume = row.get::<Relation>("t")?;
+            let transfer_volume_id = row.get::<u64>("t_id")?;
+            let is_from_a = row.get::<bool>("is_from_a")?;
+            let (from, to) = if is_from_a {
+                (address.clone(), other_address)
+            } else {
+                (other_address, address.clone())
+            };
+            let count = transfer_volume.get::<String>("count")?.parse()?;
+            let volume = transfer_volume.get::<String>("volume")?.parse()?;
+            transfer_volume_map
+                .entry(address)
+                .or_default()
+                .push(TransferVolume {
+                    id: transfer_volume_id,
+                    from,
+                    to,
+                    count,
+                    volume,
+                });
+        }
+        Ok(transfer_volume_map)
+    }
 }
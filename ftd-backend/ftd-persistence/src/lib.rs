@@ -4,7 +4,10 @@ use lazy_static::lazy_static;
 pub mod graph;
 pub mod relational;
 
-const REDENOMINATION_BLOCK_NUMBER: u64 = 1_205_128;
+pub const REDENOMINATION_BLOCK_NUMBER: u64 = 1_205_128;
+/// Ranked account search pages end at or before this result, as each searched table only
+/// contributes this many best-scoring candidates.
+pub const ACCOUNT_SEARCH_MAX_RESULT_COUNT: u32 = 500;
 
 lazy_static! {
     static ref CONFIG: Config = Config::default();
@@ -1,15 +1,35 @@
 use crate::{CONFIG, REDENOMINATION_BLOCK_NUMBER};
-use ftd_types::api::identity::{Identity as APIIdentity, SubIdentity as APISubIdentity};
+use ftd_types::api::account::{Account, AccountStats};
+use ftd_types::api::alert::{Alert, AlertDeliveryStatus, WatchDirection, WatchEntry};
+use ftd_types::api::api_key::{APIKey, APIKeyScope};
+use ftd_types::api::identity::{
+    HistoricalIdentity, Identity as APIIdentity, IdentityChange, IdentityChangeType,
+    Registrar as APIRegistrar, SubIdentity as APISubIdentity,
+};
+use ftd_types::api::label::{Label, LabelCategory, NewLabel};
+use ftd_types::api::relation::{AccountRelationChange, AccountRelationType};
 use ftd_types::api::transfer::Transfer;
 use ftd_types::subscan::SubscanAccount;
+use ftd_types::substrate::account_id::AccountId;
 use ftd_types::substrate::block::Block;
 use ftd_types::substrate::event::TransferEvent;
-use ftd_types::substrate::identity::{Identity, SubIdentity};
+use ftd_types::substrate::identity::{
+    Identity, IdentityChangeBlock, Registrar, SubIdentity, Username, UsernameAuthority,
+};
+use ftd_types::substrate::relation::{AccountRelation, AccountRelationKey};
 use postgres::PostgreSQLStorage;
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use sqlx::postgres::PgListener;
 use sqlx::{Postgres, Transaction};
+use std::str::FromStr;
 
 pub mod postgres;
 
+/// Notification listener for newly saved transfers.
+pub struct TransferListener {
+    listener: PgListener,
+}
+
 pub struct RelationalStorage {
     postgres: PostgreSQLStorage,
 }
@@ -42,6 +62,16 @@ impl RelationalStorage {
         self.postgres.get_max_transfer_id().await
     }
 
+    pub async fn get_transfer_addresses_in_id_range(
+        &self,
+        first_id: i32,
+        last_id: i32,
+    ) -> anyhow::Result<Vec<String>> {
+        self.postgres
+            .get_transfer_addresses_in_id_range(first_id, last_id)
+            .await
+    }
+
     pub async fn get_transfer_by_id(&self, id: i32) -> anyhow::Result<Option<TransferEvent>> {
         self.postgres.get_transfer_by_id(id).await
     }
@@ -70,6 +100,19 @@ impl RelationalStorage {
         self.postgres.block_exists_by_number(block_number).await
     }
 
+    pub async fn get_block_hash(&self, block_number: u64) -> anyhow::Result<Option<String>> {
+        self.postgres.get_block_hash(block_number).await
+    }
+
+    pub async fn get_block_hash_and_number_at_timestamp(
+        &self,
+        timestamp: u64,
+    ) -> anyhow::Result<Option<(String, u64)>> {
+        self.postgres
+            .get_block_hash_and_number_at_timestamp(timestamp)
+            .await
+    }
+
     async fn save_transfer(
         &self,
         block: &Block,
@@ -95,47 +138,190 @@ impl RelationalStorage {
         for transfer in block.transfers.iter() {
             self.save_transfer(&block, transfer, &mut tx).await?;
         }
+        for multisig_call in block.multisig_calls.iter() {
+            let multisig = AccountId::from_str(&multisig_call.multisig)?;
+            for signatory in multisig_call.signatories.iter() {
+                let relation = AccountRelation {
+                    from: AccountId::from_str(signatory)?,
+                    to: multisig,
+                    relation_type: AccountRelationType::MultisigMember,
+                    proxy_type: None,
+                    proxy_delay: None,
+                    multisig_threshold: Some(multisig_call.threshold),
+                };
+                self.save_account_relation(&relation, block.number, &mut tx)
+                    .await?;
+            }
+        }
         self.postgres.commit_tx(tx).await?;
         Ok(())
     }
 
-    async fn delete_all_identities(
+    /// Saves the relation and appends it to the relation change log if it is new or changed.
+    async fn save_account_relation(
         &self,
+        relation: &AccountRelation,
+        block_number: u64,
         tx: &mut Transaction<'_, Postgres>,
     ) -> anyhow::Result<()> {
-        self.postgres.delete_all_identities(tx).await
+        if self
+            .postgres
+            .save_account_relation(relation, block_number, tx)
+            .await?
+        {
+            self.postgres
+                .save_account_relation_change(&relation.key(), Some(relation), block_number, tx)
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_all_identities(&self) -> anyhow::Result<Vec<Identity>> {
+        self.postgres.get_all_identities().await
     }
 
-    pub async fn save_identities(&self, identities: &[Identity]) -> anyhow::Result<()> {
+    /// Whether the stored identities predate newly decoded identity fields, so that the next
+    /// identity update has to save them as the baseline without logging changes.
+    pub async fn is_identity_baseline_pending(&self) -> anyhow::Result<bool> {
+        self.postgres.is_identity_baseline_pending().await
+    }
+
+    /// Saves the changed identities without appending them to the identity change log, and
+    /// clears the pending baseline, in one transaction.
+    pub async fn save_identity_baseline(&self, identities: &[Identity]) -> anyhow::Result<()> {
         let mut tx = self.postgres.begin_tx().await?;
-        self.delete_all_identities(&mut tx).await?;
         for identity in identities.iter() {
             self.postgres.save_identity(identity, &mut tx).await?;
         }
+        self.postgres
+            .clear_identity_baseline_pending(&mut tx)
+            .await?;
         self.postgres.commit_tx(tx).await?;
         Ok(())
     }
 
-    async fn delete_all_sub_identities(
+    /// Saves the new and changed identities and deletes the removed ones in one transaction,
+    /// and appends the changes to the identity change log.
+    pub async fn update_identities(
         &self,
-        tx: &mut Transaction<'_, Postgres>,
+        saved_identities: &[Identity],
+        removed_account_ids: &[AccountId],
+        block: &IdentityChangeBlock,
     ) -> anyhow::Result<()> {
-        self.postgres.delete_all_sub_identities(tx).await
+        let mut tx = self.postgres.begin_tx().await?;
+        let removed_addresses: Vec<String> = removed_account_ids
+            .iter()
+            .map(AccountId::to_ss58_check)
+            .collect();
+        self.postgres
+            .delete_identities(&removed_addresses, &mut tx)
+            .await?;
+        for account_id in removed_account_ids.iter() {
+            self.postgres
+                .save_identity_change(account_id, None, block, false, &mut tx)
+                .await?;
+        }
+        for identity in saved_identities.iter() {
+            self.postgres.save_identity(identity, &mut tx).await?;
+            self.postgres
+                .save_identity_change(&identity.account_id, Some(identity), block, false, &mut tx)
+                .await?;
+        }
+        self.postgres.commit_tx(tx).await?;
+        Ok(())
+    }
+
+    pub async fn get_all_sub_identities(&self) -> anyhow::Result<Vec<SubIdentity>> {
+        self.postgres.get_all_sub_identities().await
     }
 
-    pub async fn save_sub_identities(&self, sub_identities: &[SubIdentity]) -> anyhow::Result<()> {
+    /// Saves the new and changed sub identities and deletes the removed ones in one
+    /// transaction, and appends the changes to the identity change log.
+    pub async fn update_sub_identities(
+        &self,
+        saved_sub_identities: &[SubIdentity],
+        removed_account_ids: &[AccountId],
+        block: &IdentityChangeBlock,
+    ) -> anyhow::Result<()> {
         let mut tx = self.postgres.begin_tx().await?;
-        self.delete_all_sub_identities(&mut tx).await?;
-        for sub_identity in sub_identities.iter() {
+        let removed_addresses: Vec<String> = removed_account_ids
+            .iter()
+            .map(AccountId::to_ss58_check)
+            .collect();
+        self.postgres
+            .delete_sub_identities(&removed_addresses, &mut tx)
+            .await?;
+        for account_id in removed_account_ids.iter() {
+            self.postgres
+                .save_sub_identity_change(account_id, None, block, false, &mut tx)
+                .await?;
+        }
+        for sub_identity in saved_sub_identities.iter() {
             self.postgres
                 .save_sub_identity(sub_identity, &mut tx)
                 .await?;
+            self.postgres
+                .save_sub_identity_change(
+                    &sub_identity.account_id,
+                    Some(sub_identity),
+                    block,
+                    false,
+                    &mut tx,
+                )
+                .await?;
         }
         self.postgres.commit_tx(tx).await?;
         Ok(())
     }
 
-    pub async fn set_identity_updater_state(
+    pub async fn get_all_usernames(&self) -> anyhow::Result<Vec<Username>> {
+        self.postgres.get_all_usernames().await
+    }
+
+    pub async fn get_account_relations_by_types(
+        &self,
+        relation_types: &[AccountRelationType],
+    ) -> anyhow::Result<Vec<AccountRelation>> {
+        self.postgres
+            .get_account_relations_by_types(relation_types)
+            .await
+    }
+
+    /// Saves the new and changed relations and deletes the removed ones in one transaction,
+    /// and appends the changes to the relation change log.
+    pub async fn update_account_relations(
+        &self,
+        saved_relations: &[AccountRelation],
+        removed_keys: &[AccountRelationKey],
+        block_number: u64,
+    ) -> anyhow::Result<()> {
+        let mut tx = self.postgres.begin_tx().await?;
+        for key in removed_keys.iter() {
+            self.postgres.delete_account_relation(key, &mut tx).await?;
+            self.postgres
+                .save_account_relation_change(key, None, block_number, &mut tx)
+                .await?;
+        }
+        for relation in saved_relations.iter() {
+            self.save_account_relation(relation, block_number, &mut tx)
+                .await?;
+        }
+        self.postgres.commit_tx(tx).await?;
+        Ok(())
+    }
+
+    /// Next batch of relation changes to be processed by the graph updater.
+    pub async fn get_account_relation_changes_after(
+        &self,
+        after_id: i32,
+        limit: u16,
+    ) -> anyhow::Result<Vec<AccountRelationChange>> {
+        self.postgres
+            .get_account_relation_changes_after(after_id, limit)
+            .await
+    }
+
+    pub async fn set_relation_updater_state(
         &self,
         block_hash: &str,
         block_number: u64,
@@ -143,17 +329,200 @@ impl RelationalStorage {
         error_log: Option<&str>,
     ) -> anyhow::Result<()> {
         self.postgres
-            .set_identity_updater_state(block_hash, block_number, is_successful, error_log)
+            .set_relation_updater_state(block_hash, block_number, is_successful, error_log)
             .await
     }
 
-    pub async fn search_identities(
+    /// Saves the new and changed usernames and deletes the removed ones in one transaction.
+    pub async fn update_usernames(
         &self,
-        query: &str,
+        saved_usernames: &[Username],
+        removed_usernames: &[String],
+    ) -> anyhow::Result<()> {
+        let mut tx = self.postgres.begin_tx().await?;
+        self.postgres
+            .delete_usernames(removed_usernames, &mut tx)
+            .await?;
+        for username in saved_usernames.iter() {
+            self.postgres.save_username(username, &mut tx).await?;
+        }
+        self.postgres.commit_tx(tx).await?;
+        Ok(())
+    }
+
+    pub async fn save_username_authorities(
+        &self,
+        authorities: &[UsernameAuthority],
+    ) -> anyhow::Result<()> {
+        let mut tx = self.postgres.begin_tx().await?;
+        self.postgres
+            .save_username_authorities(authorities, &mut tx)
+            .await?;
+        self.postgres.commit_tx(tx).await?;
+        Ok(())
+    }
+
+    pub async fn get_primary_usernames_by_addresses(
+        &self,
+        addresses: &[String],
+    ) -> anyhow::Result<HashMap<String, String>> {
+        Ok(self
+            .postgres
+            .get_primary_usernames_by_addresses(addresses)
+            .await?
+            .into_iter()
+            .collect())
+    }
+
+    /// First block of the identity changes observed by the identity updater, where the
+    /// backfill stops.
+    pub async fn get_first_identity_change_block(
+        &self,
+    ) -> anyhow::Result<Option<IdentityChangeBlock>> {
+        self.postgres.get_first_identity_change_block().await
+    }
+
+    pub async fn delete_backfill_identity_changes(&self) -> anyhow::Result<u64> {
+        let mut tx = self.postgres.begin_tx().await?;
+        let count = self
+            .postgres
+            .delete_backfill_identity_changes(&mut tx)
+            .await?;
+        self.postgres.commit_tx(tx).await?;
+        Ok(count)
+    }
+
+    /// Appends the identity and sub identity changes between two backfill samples to the
+    /// identity change log, without touching the current identities.
+    pub async fn save_backfill_identity_changes(
+        &self,
+        saved_identities: &[Identity],
+        removed_identity_account_ids: &[AccountId],
+        saved_sub_identities: &[SubIdentity],
+        removed_sub_identity_account_ids: &[AccountId],
+        block: &IdentityChangeBlock,
+    ) -> anyhow::Result<()> {
+        let mut tx = self.postgres.begin_tx().await?;
+        for account_id in removed_identity_account_ids.iter() {
+            self.postgres
+                .save_identity_change(account_id, None, block, true, &mut tx)
+                .await?;
+        }
+        for identity in saved_identities.iter() {
+            self.postgres
+                .save_identity_change(&identity.account_id, Some(identity), block, true, &mut tx)
+                .await?;
+        }
+        for account_id in removed_sub_identity_account_ids.iter() {
+            self.postgres
+                .save_sub_identity_change(account_id, None, block, true, &mut tx)
+                .await?;
+        }
+        for sub_identity in saved_sub_identities.iter() {
+            self.postgres
+                .save_sub_identity_change(
+                    &sub_identity.account_id,
+                    Some(sub_identity),
+                    block,
+                    true,
+                    &mut tx,
+                )
+                .await?;
+        }
+        self.postgres.commit_tx(tx).await?;
+        Ok(())
+    }
+
+    /// Identity and sub identity changes of the account, oldest first.
+    pub async fn get_identity_timeline(
+        &self,
+        address: &str,
+    ) -> anyhow::Result<Vec<IdentityChange>> {
+        self.postgres
+            .get_identity_changes(address, None, None)
+            .await
+    }
+
+    /// Next batch of identity changes to be processed by the graph updater.
+    pub async fn get_identity_changes_after(
+        &self,
+        after_id: i32,
         limit: u16,
-    ) -> anyhow::Result<Vec<APIIdentity>> {
+    ) -> anyhow::Result<Vec<IdentityChange>> {
         self.postgres
-            .search_identities_by_display(query, limit)
+            .get_identity_changes_after(after_id, limit)
+            .await
+    }
+
+    /// Identity and sub identity of the account as of the last changes observed at or before
+    /// the given People chain block number or timestamp (ms). Not indexed before the start of
+    /// the identity change log.
+    pub async fn get_historical_identity(
+        &self,
+        address: &str,
+        maybe_block_number: Option<u64>,
+        maybe_timestamp: Option<u64>,
+    ) -> anyhow::Result<HistoricalIdentity> {
+        let mut historical_identity = HistoricalIdentity {
+            address: address.to_string(),
+            is_indexed: true,
+            identity: None,
+            sub_identity: None,
+        };
+        if maybe_block_number.is_some() || maybe_timestamp.is_some() {
+            let (has_relay_changes, maybe_first_block_number, maybe_first_timestamp) =
+                self.postgres.get_identity_change_log_start().await?;
+            historical_identity.is_indexed = match (maybe_block_number, maybe_timestamp) {
+                (Some(block_number), _) => {
+                    has_relay_changes
+                        || maybe_first_block_number
+                            .is_some_and(|first_block_number| block_number >= first_block_number)
+                }
+                (None, Some(timestamp)) => maybe_first_timestamp
+                    .is_some_and(|first_timestamp| timestamp >= first_timestamp),
+                (None, None) => true,
+            };
+            if !historical_identity.is_indexed {
+                return Ok(historical_identity);
+            }
+        }
+        for change in self
+            .postgres
+            .get_identity_changes(address, maybe_block_number, maybe_timestamp)
+            .await?
+        {
+            match change.change_type {
+                IdentityChangeType::IdentitySet | IdentityChangeType::IdentityCleared => {
+                    historical_identity.identity = change.identity;
+                }
+                IdentityChangeType::SubIdentitySet | IdentityChangeType::SubIdentityCleared => {
+                    historical_identity.sub_identity = change.sub_identity;
+                }
+            }
+        }
+        Ok(historical_identity)
+    }
+
+    pub async fn save_registrars(&self, registrars: &[Registrar]) -> anyhow::Result<()> {
+        let mut tx = self.postgres.begin_tx().await?;
+        self.postgres.save_registrars(registrars, &mut tx).await?;
+        self.postgres.commit_tx(tx).await?;
+        Ok(())
+    }
+
+    pub async fn get_registrars(&self) -> anyhow::Result<Vec<APIRegistrar>> {
+        self.postgres.get_registrars().await
+    }
+
+    pub async fn set_identity_updater_state(
+        &self,
+        block_hash: &str,
+        block_number: u64,
+        is_successful: bool,
+        error_log: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.postgres
+            .set_identity_updater_state(block_hash, block_number, is_successful, error_log)
             .await
     }
 
@@ -161,20 +530,20 @@ impl RelationalStorage {
         self.postgres.get_sub_identities(address).await
     }
 
-    pub async fn search_sub_identities(
+    /// Relevance-ranked account search, see `PostgreSQLStorage::search_accounts_ranked`.
+    /// `address_query` is matched against the addresses, `query` against the names.
+    pub async fn search_accounts_ranked(
         &self,
         query: &str,
+        address_query: &str,
         limit: u16,
-    ) -> anyhow::Result<Vec<APISubIdentity>> {
+        offset: u32,
+    ) -> anyhow::Result<Vec<(String, f32)>> {
         self.postgres
-            .search_sub_identities_by_sub_display(query, limit)
+            .search_accounts_ranked(query, address_query, limit, offset)
             .await
     }
 
-    pub async fn search_addresses(&self, query: &str, limit: u16) -> anyhow::Result<Vec<String>> {
-        self.postgres.search_addresses(query, limit).await
-    }
-
     pub async fn get_identity_by_address(
         &self,
         address: &str,
@@ -199,14 +568,325 @@ impl RelationalStorage {
             .await
     }
 
-    pub async fn get_subscan_account_by_address(
+    pub async fn save_subscan_account(&self, account: &SubscanAccount) -> anyhow::Result<String> {
+        self.postgres.save_subscan_account(account).await
+    }
+
+    pub async fn get_account_stats(&self, address: &str) -> anyhow::Result<AccountStats> {
+        let (first_seen_block_number, last_seen_block_number) = self
+            .postgres
+            .get_first_and_last_transfer_block_numbers(address)
+            .await?;
+        let (total_sent, sent_transfer_count) = self
+            .postgres
+            .get_total_outgoing_transfer_volume(address)
+            .await?;
+        let (total_received, received_transfer_count) = self
+            .postgres
+            .get_total_incoming_transfer_volume(address)
+            .await?;
+        Ok(AccountStats {
+            first_seen_block_number,
+            last_seen_block_number,
+            total_sent,
+            total_received,
+            sent_transfer_count,
+            received_transfer_count,
+            counterparty_count: self.postgres.get_counterparty_count(address).await?,
+            largest_incoming_transfer: self.postgres.get_largest_incoming_transfer(address).await?,
+            largest_outgoing_transfer: self.postgres.get_largest_outgoing_transfer(address).await?,
+        })
+    }
+
+    pub async fn get_identities_by_addresses(
+        &self,
+        addresses: &[String],
+    ) -> anyhow::Result<Vec<APIIdentity>> {
+        self.postgres.get_identities_by_addresses(addresses).await
+    }
+
+    pub async fn get_sub_identities_by_addresses(
+        &self,
+        addresses: &[String],
+    ) -> anyhow::Result<Vec<APISubIdentity>> {
+        self.postgres
+            .get_sub_identities_by_addresses(addresses)
+            .await
+    }
+
+    pub async fn get_subscan_accounts_by_addresses(
+        &self,
+        addresses: &[String],
+    ) -> anyhow::Result<Vec<SubscanAccount>> {
+        self.postgres.get_subscan_accounts(addresses).await
+    }
+
+    /// Resolves the identities, sub-identities, super identities and stored Subscan accounts of
+    /// the given addresses with a constant number of queries. The accounts are in the order of
+    /// the addresses, without balances and labels.
+    pub async fn get_accounts_by_addresses(
+        &self,
+        addresses: &[String],
+    ) -> anyhow::Result<Vec<Account>> {
+        let mut identity_map: HashMap<String, APIIdentity> = self
+            .get_identities_by_addresses(addresses)
+            .await?
+            .into_iter()
+            .map(|identity| (identity.address.clone(), identity))
+            .collect();
+        let sub_identity_map: HashMap<String, APISubIdentity> = self
+            .get_sub_identities_by_addresses(addresses)
+            .await?
+            .into_iter()
+            .map(|sub_identity| (sub_identity.address.clone(), sub_identity))
+            .collect();
+        let missing_super_addresses: Vec<String> = sub_identity_map
+            .values()
+            .map(|sub_identity| sub_identity.super_address.clone())
+            .filter(|super_address| !identity_map.contains_key(super_address))
+            .collect::<HashSet<String>>()
+            .into_iter()
+            .collect();
+        if !missing_super_addresses.is_empty() {
+            for identity in self
+                .get_identities_by_addresses(&missing_super_addresses)
+                .await?
+            {
+                identity_map.insert(identity.address.clone(), identity);
+            }
+        }
+        let subscan_account_map: HashMap<String, SubscanAccount> = self
+            .get_subscan_accounts_by_addresses(addresses)
+            .await?
+            .into_iter()
+            .map(|subscan_account| (subscan_account.address.clone(), subscan_account))
+            .collect();
+        let primary_username_map = self.get_primary_usernames_by_addresses(addresses).await?;
+        Ok(addresses
+            .iter()
+            .map(|address| {
+                let sub_identity = sub_identity_map.get(address).cloned();
+                let super_identity = sub_identity
+                    .as_ref()
+                    .and_then(|sub_identity| identity_map.get(&sub_identity.super_address))
+                    .cloned();
+                Account {
+                    address: address.clone(),
+                    identity: identity_map.get(address).cloned(),
+                    sub_identity,
+                    super_identity,
+                    primary_username: primary_username_map.get(address).cloned(),
+                    balance: None,
+                    subscan_account: subscan_account_map.get(address).cloned(),
+                    labels: Vec::new(),
+                }
+            })
+            .collect())
+    }
+
+    pub async fn get_transfers_by_sender_and_recipient_pairs(
+        &self,
+        pairs: &[(String, String)],
+        limit: u16,
+    ) -> anyhow::Result<Vec<Transfer>> {
+        self.postgres
+            .get_transfers_by_sender_and_recipient_pairs(pairs, limit)
+            .await
+    }
+
+    pub async fn listen_transfers(&self) -> anyhow::Result<TransferListener> {
+        Ok(TransferListener {
+            listener: self.postgres.listen_transfers().await?,
+        })
+    }
+
+    /// Waits for the next saved transfer. Transfers saved while the listener is reconnecting
+    /// are missed. Returns `None` if the transfer has been deleted since the notification.
+    pub async fn receive_transfer(
+        &self,
+        listener: &mut TransferListener,
+    ) -> anyhow::Result<Option<Transfer>> {
+        let notification = listener.listener.recv().await?;
+        let id: i32 = notification.payload().parse()?;
+        self.postgres.get_api_transfer_by_id(id).await
+    }
+
+    pub async fn get_alert_evaluator_last_processed_transfer_id(&self) -> anyhow::Result<i32> {
+        self.postgres
+            .get_alert_evaluator_last_processed_transfer_id()
+            .await
+    }
+
+    pub async fn set_alert_evaluator_last_processed_transfer_id(
+        &self,
+        id: i32,
+    ) -> anyhow::Result<()> {
+        self.postgres
+            .set_alert_evaluator_last_processed_transfer_id(id)
+            .await
+    }
+
+    pub async fn get_api_transfer_by_id(&self, id: i32) -> anyhow::Result<Option<Transfer>> {
+        self.postgres.get_api_transfer_by_id(id).await
+    }
+
+    pub async fn get_watch_entries(&self) -> anyhow::Result<Vec<WatchEntry>> {
+        self.postgres.get_watch_entries().await
+    }
+
+    pub async fn get_active_watch_entries_by_addresses(
+        &self,
+        addresses: &[String],
+        transfer_timestamp: u64,
+    ) -> anyhow::Result<Vec<WatchEntry>> {
+        self.postgres
+            .get_active_watch_entries_by_addresses(addresses, transfer_timestamp)
+            .await
+    }
+
+    pub async fn save_watch_entry(
         &self,
         address: &str,
-    ) -> anyhow::Result<Option<SubscanAccount>> {
-        self.postgres.get_subscan_account(address).await
+        direction: WatchDirection,
+        threshold: u128,
+        label: Option<&str>,
+        webhook_url: Option<&str>,
+        webhook_secret: Option<&str>,
+    ) -> anyhow::Result<WatchEntry> {
+        self.postgres
+            .save_watch_entry(
+                address,
+                direction,
+                threshold,
+                label,
+                webhook_url,
+                webhook_secret,
+            )
+            .await
     }
 
-    pub async fn save_subscan_account(&self, account: &SubscanAccount) -> anyhow::Result<String> {
-        self.postgres.save_subscan_account(account).await
+    pub async fn delete_watch_entry(&self, id: i32) -> anyhow::Result<bool> {
+        self.postgres.delete_watch_entry(id).await
+    }
+
+    pub async fn save_alert(
+        &self,
+        watch_entry_id: i32,
+        transfer_id: i32,
+        delivery_status: AlertDeliveryStatus,
+    ) -> anyhow::Result<Option<i32>> {
+        self.postgres
+            .save_alert(watch_entry_id, transfer_id, delivery_status)
+            .await
+    }
+
+    pub async fn get_alert_by_id(&self, id: i32) -> anyhow::Result<Option<Alert>> {
+        self.postgres.get_alert_by_id(id).await
+    }
+
+    pub async fn get_alerts(
+        &self,
+        maybe_watch_entry_id: Option<i32>,
+        maybe_address: Option<&str>,
+        maybe_before_id: Option<i32>,
+        limit: u16,
+    ) -> anyhow::Result<Vec<Alert>> {
+        self.postgres
+            .get_alerts(maybe_watch_entry_id, maybe_address, maybe_before_id, limit)
+            .await
+    }
+
+    pub async fn get_due_alert_deliveries(
+        &self,
+        limit: u16,
+    ) -> anyhow::Result<Vec<(i32, String, String)>> {
+        self.postgres.get_due_alert_deliveries(limit).await
+    }
+
+    pub async fn set_alert_delivered(&self, id: i32) -> anyhow::Result<()> {
+        self.postgres.set_alert_delivered(id).await
+    }
+
+    pub async fn set_alert_delivery_failed(
+        &self,
+        id: i32,
+        error: &str,
+        maybe_retry_delay_seconds: Option<u64>,
+    ) -> anyhow::Result<()> {
+        self.postgres
+            .set_alert_delivery_failed(id, error, maybe_retry_delay_seconds)
+            .await
+    }
+
+    /// Saves a normalized label, see `NewLabel::normalize`.
+    pub async fn save_label(&self, label: &NewLabel) -> anyhow::Result<Label> {
+        let labeled_at = match label.labeled_at {
+            Some(labeled_at) => labeled_at,
+            None => std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_millis() as u64,
+        };
+        self.postgres
+            .save_label(
+                &label.address,
+                &label.name,
+                label.category,
+                &label.source,
+                label.confidence.unwrap_or(1.0),
+                labeled_at,
+            )
+            .await
+    }
+
+    pub async fn delete_label(&self, id: i32) -> anyhow::Result<bool> {
+        self.postgres.delete_label(id).await
+    }
+
+    pub async fn get_labels(
+        &self,
+        maybe_category: Option<LabelCategory>,
+        maybe_source: Option<&str>,
+    ) -> anyhow::Result<Vec<Label>> {
+        self.postgres.get_labels(maybe_category, maybe_source).await
+    }
+
+    pub async fn get_labels_by_addresses(
+        &self,
+        addresses: &[String],
+    ) -> anyhow::Result<Vec<Label>> {
+        self.postgres.get_labels_by_addresses(addresses).await
+    }
+
+    pub async fn save_api_key(
+        &self,
+        name: &str,
+        key_hash: &str,
+        key_prefix: &str,
+        scope: APIKeyScope,
+        rate_limit_per_minute: u32,
+        burst: u32,
+    ) -> anyhow::Result<APIKey> {
+        self.postgres
+            .save_api_key(
+                name,
+                key_hash,
+                key_prefix,
+                scope,
+                rate_limit_per_minute,
+                burst,
+            )
+            .await
+    }
+
+    pub async fn delete_api_key(&self, id: i32) -> anyhow::Result<bool> {
+        self.postgres.delete_api_key(id).await
+    }
+
+    pub async fn get_api_keys(&self) -> anyhow::Result<Vec<APIKey>> {
+        self.postgres.get_api_keys().await
+    }
+
+    pub async fn get_active_api_keys_with_hashes(&self) -> anyhow::Result<Vec<(String, APIKey)>> {
+        self.postgres.get_active_api_keys_with_hashes().await
     }
 }
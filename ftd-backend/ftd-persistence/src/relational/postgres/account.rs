@@ -1,8 +1,11 @@
 use super::PostgreSQLStorage;
-use ftd_types::api::identity::{Identity, SubIdentity};
+use crate::ACCOUNT_SEARCH_MAX_RESULT_COUNT;
+use ftd_types::api::identity::{Identity, IdentityDataHashType, IdentityJudgement, SubIdentity};
+use sqlx::types::Json;
 use sqlx::{Postgres, Transaction};
+use std::collections::BTreeMap;
 
-type IdentityRow = (
+pub(super) type IdentityRow = (
     String,
     Option<String>,
     Option<String>,
@@ -10,6 +13,12 @@ type IdentityRow = (
     Option<String>,
     Option<String>,
     Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Json<BTreeMap<String, IdentityDataHashType>>,
+    Json<Vec<IdentityJudgement>>,
     bool,
     bool,
 );
@@ -23,12 +32,18 @@ fn row_into_identity(row: &IdentityRow) -> Identity {
         riot: row.4.clone(),
         email: row.5.clone(),
         twitter: row.6.clone(),
-        is_confirmed: row.7,
-        is_invalid: row.8,
+        github: row.7.clone(),
+        discord: row.8.clone(),
+        image: row.9.clone(),
+        pgp_fingerprint: row.10.clone(),
+        hashed_fields: (*row.11).clone(),
+        judgements: (*row.12).clone(),
+        is_confirmed: row.13,
+        is_invalid: row.14,
     }
 }
 
-type SubIdentityRow = (String, String, Option<String>);
+pub(super) type SubIdentityRow = (String, String, Option<String>);
 
 fn row_into_sub_identity(row: &SubIdentityRow) -> SubIdentity {
     SubIdentity {
@@ -38,7 +53,142 @@ fn row_into_sub_identity(row: &SubIdentityRow) -> SubIdentity {
     }
 }
 
+/// Added to the trigram similarity of the exact, case-insensitive matches.
+const SEARCH_EXACT_MATCH_BOOST: f32 = 1.0;
+/// Added to the trigram similarity of the matches that contain the query.
+const SEARCH_SUBSTRING_MATCH_BOOST: f32 = 0.2;
+/// Added to the score of the accounts with a confirmed identity or super identity.
+const SEARCH_CONFIRMED_IDENTITY_BOOST: f32 = 0.3;
+/// Sub-accounts of the matching identities are ranked below them by this factor.
+const SEARCH_SUB_ACCOUNT_FACTOR: f32 = 0.5;
+/// Address substring matching starts at this query length.
+const SEARCH_ADDRESS_MIN_QUERY_LENGTH: i32 = 5;
+
+/// Escapes the `LIKE` wildcards so that the text is matched literally. Backslash is the default
+/// `LIKE` escape character.
+fn escape_like_pattern(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
 impl PostgreSQLStorage {
+    /// Relevance-ranked search over the identity display, legal, web, email, twitter, github and
+    /// discord fields, sub-identity displays, usernames, Subscan and Merkle Science display
+    /// names, labels and addresses. Returns the addresses with their scores, most relevant first.
+    pub async fn search_accounts_ranked(
+        &self,
+        query: &str,
+        address_query: &str,
+        limit: u16,
+        offset: u32,
+    ) -> anyhow::Result<Vec<(String, f32)>> {
+        let rows: Vec<(String, f32)> = sqlx::query_as(
+            r#"
+            WITH candidate AS (
+                (
+                    SELECT address,
+                        COALESCE(GREATEST(similarity(display, $1), similarity(legal, $1), similarity(web, $1), similarity(email, $1), similarity(twitter, $1), similarity(github, $1), similarity(discord, $1)), 0)
+                        + CASE WHEN lower(display) = lower($1) OR lower(legal) = lower($1) OR lower(web) = lower($1) OR lower(email) = lower($1) OR lower(twitter) = lower($1) OR lower(github) = lower($1) OR lower(discord) = lower($1) THEN $5 ELSE 0 END
+                        + CASE WHEN display ILIKE $2 OR legal ILIKE $2 OR web ILIKE $2 OR email ILIKE $2 OR twitter ILIKE $2 OR github ILIKE $2 OR discord ILIKE $2 THEN $6 ELSE 0 END
+                        + CASE WHEN is_confirmed THEN $7 ELSE 0 END AS score
+                    FROM ftd_identity
+                    WHERE display % $1 OR legal % $1 OR web % $1 OR email % $1 OR twitter % $1 OR github % $1 OR discord % $1
+                        OR display ILIKE $2 OR legal ILIKE $2 OR web ILIKE $2 OR email ILIKE $2 OR twitter ILIKE $2 OR github ILIKE $2 OR discord ILIKE $2
+                    ORDER BY score DESC
+                    LIMIT $11
+                )
+                UNION ALL
+                (
+                    SELECT S.address,
+                        COALESCE(similarity(S.sub_display, $1), 0)
+                        + CASE WHEN lower(S.sub_display) = lower($1) THEN $5 ELSE 0 END
+                        + CASE WHEN S.sub_display ILIKE $2 THEN $6 ELSE 0 END
+                        + CASE WHEN I.is_confirmed THEN $7 ELSE 0 END AS score
+                    FROM ftd_sub_identity S
+                    LEFT JOIN ftd_identity I ON I.address = S.super_address
+                    WHERE S.sub_display % $1 OR S.sub_display ILIKE $2
+                    ORDER BY score DESC
+                    LIMIT $11
+                )
+                UNION ALL
+                (
+                    SELECT S.address,
+                        COALESCE(similarity(I.display, $1), 0) * $8
+                        + CASE WHEN I.is_confirmed THEN $7 ELSE 0 END AS score
+                    FROM ftd_sub_identity S
+                    INNER JOIN ftd_identity I ON I.address = S.super_address
+                    WHERE I.display % $1 OR I.display ILIKE $2
+                    ORDER BY score DESC
+                    LIMIT $11
+                )
+                UNION ALL
+                (
+                    SELECT address,
+                        COALESCE(GREATEST(similarity(display, $1), similarity(merkle_science_tag_name, $1)), 0)
+                        + CASE WHEN lower(display) = lower($1) OR lower(merkle_science_tag_name) = lower($1) THEN $5 ELSE 0 END
+                        + CASE WHEN display ILIKE $2 OR merkle_science_tag_name ILIKE $2 THEN $6 ELSE 0 END AS score
+                    FROM ftd_subscan_account
+                    WHERE display % $1 OR merkle_science_tag_name % $1 OR display ILIKE $2 OR merkle_science_tag_name ILIKE $2
+                    ORDER BY score DESC
+                    LIMIT $11
+                )
+                UNION ALL
+                (
+                    SELECT address,
+                        COALESCE(similarity(name, $1), 0)
+                        + CASE WHEN lower(name) = lower($1) THEN $5 ELSE 0 END
+                        + CASE WHEN name ILIKE $2 THEN $6 ELSE 0 END AS score
+                    FROM ftd_label
+                    WHERE name % $1 OR name ILIKE $2
+                    ORDER BY score DESC
+                    LIMIT $11
+                )
+                UNION ALL
+                (
+                    SELECT address,
+                        COALESCE(similarity(username, $1), 0)
+                        + CASE WHEN lower(username) = lower($1) THEN $5 ELSE 0 END
+                        + CASE WHEN username ILIKE $2 THEN $6 ELSE 0 END AS score
+                    FROM ftd_username
+                    WHERE username % $1 OR username ILIKE $2
+                    ORDER BY score DESC
+                    LIMIT $11
+                )
+                UNION ALL
+                (
+                    SELECT address,
+                        CASE WHEN address = $3 THEN $5 ELSE 0 END + $6 AS score
+                    FROM ftd_account
+                    WHERE length($3) >= $12 AND address ILIKE $4
+                    ORDER BY score DESC
+                    LIMIT $11
+                )
+            )
+            SELECT address, MAX(score)::REAL AS score
+            FROM candidate
+            GROUP BY address
+            ORDER BY score DESC, address ASC
+            LIMIT $9 OFFSET $10
+            "#,
+        )
+        .bind(query)
+        .bind(format!("%{}%", escape_like_pattern(query)))
+        .bind(address_query)
+        .bind(format!("%{}%", escape_like_pattern(address_query)))
+        .bind(SEARCH_EXACT_MATCH_BOOST)
+        .bind(SEARCH_SUBSTRING_MATCH_BOOST)
+        .bind(SEARCH_CONFIRMED_IDENTITY_BOOST)
+        .bind(SEARCH_SUB_ACCOUNT_FACTOR)
+        .bind(limit as i32)
+        .bind(offset as i64)
+        .bind(ACCOUNT_SEARCH_MAX_RESULT_COUNT as i64)
+        .bind(SEARCH_ADDRESS_MIN_QUERY_LENGTH)
+        .fetch_all(&self.connection_pool)
+        .await?;
+        Ok(rows)
+    }
+
     pub async fn save_account(
         &self,
         address: &str,
@@ -62,32 +212,10 @@ impl PostgreSQLStorage {
         }
     }
 
-    pub async fn search_identities_by_display(
-        &self,
-        query: &str,
-        limit: u16,
-    ) -> anyhow::Result<Vec<Identity>> {
-        let rows: Vec<IdentityRow> = sqlx::query_as(
-            r#"
-            SELECT address, display, legal, web, riot, email, twitter, is_confirmed, is_invalid
-            FROM ftd_identity
-            WHERE display ILIKE $1
-            LIMIT $2
-            "#,
-        )
-        .bind(format!("%{query}%"))
-        .bind(limit as i32)
-        .fetch_all(&self.connection_pool)
-        .await?;
-
-        let identities = rows.iter().map(row_into_identity).collect();
-        Ok(identities)
-    }
-
     pub async fn get_identity_by_address(&self, address: &str) -> anyhow::Result<Option<Identity>> {
         let maybe_row: Option<IdentityRow> = sqlx::query_as(
             r#"
-            SELECT address, display, legal, web, riot, email, twitter, is_confirmed, is_invalid
+            SELECT address, display, legal, web, riot, email, twitter, github, discord, image, pgp_fingerprint, hashed_fields, judgements, is_confirmed, is_invalid
             FROM ftd_identity
             WHERE address = $1
             "#,
@@ -132,39 +260,37 @@ impl PostgreSQLStorage {
         Ok(rows.iter().map(row_into_sub_identity).collect())
     }
 
-    pub async fn search_sub_identities_by_sub_display(
+    pub async fn get_identities_by_addresses(
         &self,
-        query: &str,
-        limit: u16,
-    ) -> anyhow::Result<Vec<SubIdentity>> {
-        let rows: Vec<SubIdentityRow> = sqlx::query_as(
+        addresses: &[String],
+    ) -> anyhow::Result<Vec<Identity>> {
+        let rows: Vec<IdentityRow> = sqlx::query_as(
             r#"
-            SELECT address, super_address, sub_display
-            FROM ftd_sub_identity
-            WHERE sub_display ILIKE $1
-            LIMIT $2
+            SELECT address, display, legal, web, riot, email, twitter, github, discord, image, pgp_fingerprint, hashed_fields, judgements, is_confirmed, is_invalid
+            FROM ftd_identity
+            WHERE address = ANY($1)
             "#,
         )
-        .bind(format!("%{query}%"))
-        .bind(limit as i32)
+        .bind(addresses)
         .fetch_all(&self.connection_pool)
         .await?;
-        Ok(rows.iter().map(row_into_sub_identity).collect())
+        Ok(rows.iter().map(row_into_identity).collect())
     }
 
-    pub async fn search_addresses(&self, query: &str, limit: u16) -> anyhow::Result<Vec<String>> {
-        let rows: Vec<(String,)> = sqlx::query_as(
+    pub async fn get_sub_identities_by_addresses(
+        &self,
+        addresses: &[String],
+    ) -> anyhow::Result<Vec<SubIdentity>> {
+        let rows: Vec<SubIdentityRow> = sqlx::query_as(
             r#"
-            SELECT address
-            FROM ftd_account
-            WHERE address ILIKE $1
-            LIMIT $2
+            SELECT address, super_address, sub_display
+            FROM ftd_sub_identity
+            WHERE address = ANY($1)
             "#,
         )
-        .bind(format!("%{query}%"))
-        .bind(limit as i32)
+        .bind(addresses)
         .fetch_all(&self.connection_pool)
         .await?;
-        Ok(rows.iter().map(|row| row.0.clone()).collect())
+        Ok(rows.iter().map(row_into_sub_identity).collect())
     }
 }
@@ -0,0 +1,313 @@
+use super::PostgreSQLStorage;
+use ftd_types::api::alert::{Alert, AlertDeliveryStatus, WatchDirection, WatchEntry};
+use ftd_types::api::transfer::Transfer;
+use std::str::FromStr;
+
+type WatchEntryRow = (
+    i32,
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    bool,
+);
+
+fn row_into_watch_entry(row: &WatchEntryRow) -> anyhow::Result<WatchEntry> {
+    Ok(WatchEntry {
+        id: row.0 as u32,
+        address: row.1.clone(),
+        direction: WatchDirection::from_str(&row.2).map_err(anyhow::Error::msg)?,
+        threshold: row.3.parse()?,
+        label: row.4.clone(),
+        webhook_url: row.5.clone(),
+        is_active: row.6,
+    })
+}
+
+type AlertRow = (
+    i32,
+    i32,
+    String,
+    Option<String>,
+    String,
+    i32,
+    i64,
+    String,
+    i64,
+    i64,
+    i32,
+    i32,
+    i32,
+    String,
+    String,
+    String,
+);
+
+fn row_into_alert(row: &AlertRow) -> anyhow::Result<Alert> {
+    let transfer = Transfer {
+        block_hash: row.7.clone(),
+        block_number: row.8 as u64,
+        timestamp: row.9 as u64,
+        extrinsic_index: row.10 as u16,
+        extrinsic_event_index: row.11 as u16,
+        event_index: row.12 as u16,
+        from_address: row.13.clone(),
+        to_address: row.14.clone(),
+        amount: row.15.parse()?,
+    };
+    let direction = if transfer.to_address == row.2 {
+        WatchDirection::Incoming
+    } else {
+        WatchDirection::Outgoing
+    };
+    Ok(Alert {
+        id: row.0 as u32,
+        watch_entry_id: row.1 as u32,
+        address: row.2.clone(),
+        label: row.3.clone(),
+        direction,
+        transfer,
+        delivery_status: AlertDeliveryStatus::from_str(&row.4).map_err(anyhow::Error::msg)?,
+        delivery_attempt_count: row.5 as u32,
+        created_at: row.6 as u64,
+    })
+}
+
+const ALERT_SELECT: &str = r#"
+    SELECT A.id, A.watch_entry_id, W.address, W.label, A.delivery_status, A.delivery_attempt_count, (EXTRACT(EPOCH FROM A.created_at) * 1000)::BIGINT, T.block_hash, T.block_number, T.timestamp, T.extrinsic_index, T.extrinsic_event_index, T.event_index, T.from_address, T.to_address, T.amount
+    FROM ftd_alert A
+    INNER JOIN ftd_watch_entry W ON W.id = A.watch_entry_id
+    INNER JOIN ftd_transfer T ON T.id = A.transfer_id
+"#;
+
+impl PostgreSQLStorage {
+    pub async fn get_alert_evaluator_last_processed_transfer_id(&self) -> anyhow::Result<i32> {
+        let last_processed_transfer_id: (i32,) = sqlx::query_as(
+            r#"
+            SELECT last_processed_transfer_id FROM ftd_alert_evaluator_state LIMIT 1
+            "#,
+        )
+        .fetch_one(&self.connection_pool)
+        .await?;
+        Ok(last_processed_transfer_id.0)
+    }
+
+    pub async fn set_alert_evaluator_last_processed_transfer_id(
+        &self,
+        id: i32,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE ftd_alert_evaluator_state
+            SET last_processed_transfer_id = $1, updated_at = now()
+            WHERE id = 1
+            "#,
+        )
+        .bind(id)
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_watch_entries(&self) -> anyhow::Result<Vec<WatchEntry>> {
+        let rows: Vec<WatchEntryRow> = sqlx::query_as(
+            r#"
+            SELECT id, address, direction, threshold, label, webhook_url, is_active
+            FROM ftd_watch_entry
+            ORDER BY id ASC
+            "#,
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+        rows.iter().map(row_into_watch_entry).collect()
+    }
+
+    /// Active watch entries of the addresses that were created at or before the given transfer
+    /// timestamp, so that new entries do not alert on past transfers.
+    pub async fn get_active_watch_entries_by_addresses(
+        &self,
+        addresses: &[String],
+        transfer_timestamp: u64,
+    ) -> anyhow::Result<Vec<WatchEntry>> {
+        let rows: Vec<WatchEntryRow> = sqlx::query_as(
+            r#"
+            SELECT id, address, direction, threshold, label, webhook_url, is_active
+            FROM ftd_watch_entry
+            WHERE address = ANY($1) AND is_active = true AND EXTRACT(EPOCH FROM created_at) * 1000 <= $2
+            "#,
+        )
+        .bind(addresses)
+        .bind(transfer_timestamp as i64)
+        .fetch_all(&self.connection_pool)
+        .await?;
+        rows.iter().map(row_into_watch_entry).collect()
+    }
+
+    pub async fn save_watch_entry(
+        &self,
+        address: &str,
+        direction: WatchDirection,
+        threshold: u128,
+        label: Option<&str>,
+        webhook_url: Option<&str>,
+        webhook_secret: Option<&str>,
+    ) -> anyhow::Result<WatchEntry> {
+        let row: WatchEntryRow = sqlx::query_as(
+            r#"
+            INSERT INTO ftd_watch_entry (address, direction, threshold, label, webhook_url, webhook_secret)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, address, direction, threshold, label, webhook_url, is_active
+            "#,
+        )
+        .bind(address)
+        .bind(direction.to_string())
+        .bind(threshold.to_string())
+        .bind(label)
+        .bind(webhook_url)
+        .bind(webhook_secret)
+        .fetch_one(&self.connection_pool)
+        .await?;
+        row_into_watch_entry(&row)
+    }
+
+    /// Returns `false` if there is no watch entry with the given id.
+    pub async fn delete_watch_entry(&self, id: i32) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM ftd_watch_entry WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns the id of the new alert, or `None` if the transfer has already triggered an
+    /// alert for the watch entry.
+    pub async fn save_alert(
+        &self,
+        watch_entry_id: i32,
+        transfer_id: i32,
+        delivery_status: AlertDeliveryStatus,
+    ) -> anyhow::Result<Option<i32>> {
+        let maybe_result: Option<(i32,)> = sqlx::query_as(
+            r#"
+            INSERT INTO ftd_alert (watch_entry_id, transfer_id, delivery_status, next_delivery_at)
+            VALUES ($1, $2, $3, now())
+            ON CONFLICT (watch_entry_id, transfer_id) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(watch_entry_id)
+        .bind(transfer_id)
+        .bind(delivery_status.to_string())
+        .fetch_optional(&self.connection_pool)
+        .await?;
+        Ok(maybe_result.map(|result| result.0))
+    }
+
+    pub async fn get_alert_by_id(&self, id: i32) -> anyhow::Result<Option<Alert>> {
+        let maybe_row: Option<AlertRow> =
+            sqlx::query_as(format!("{ALERT_SELECT} WHERE A.id = $1").as_str())
+                .bind(id)
+                .fetch_optional(&self.connection_pool)
+                .await?;
+        maybe_row.as_ref().map(row_into_alert).transpose()
+    }
+
+    /// Gets the alerts latest first, optionally filtered by watch entry and watched address.
+    /// `before_id` is the pagination cursor.
+    pub async fn get_alerts(
+        &self,
+        maybe_watch_entry_id: Option<i32>,
+        maybe_address: Option<&str>,
+        maybe_before_id: Option<i32>,
+        limit: u16,
+    ) -> anyhow::Result<Vec<Alert>> {
+        let rows: Vec<AlertRow> = sqlx::query_as(
+            format!(
+                r#"
+                {ALERT_SELECT}
+                WHERE ($1::INTEGER IS NULL OR A.watch_entry_id = $1)
+                AND ($2::VARCHAR IS NULL OR W.address = $2)
+                AND ($3::INTEGER IS NULL OR A.id < $3)
+                ORDER BY A.id DESC
+                LIMIT $4
+                "#
+            )
+            .as_str(),
+        )
+        .bind(maybe_watch_entry_id)
+        .bind(maybe_address)
+        .bind(maybe_before_id)
+        .bind(limit as i32)
+        .fetch_all(&self.connection_pool)
+        .await?;
+        rows.iter().map(row_into_alert).collect()
+    }
+
+    /// Gets the ids, webhook URLs and webhook secrets of the pending alerts that are due for
+    /// delivery.
+    pub async fn get_due_alert_deliveries(
+        &self,
+        limit: u16,
+    ) -> anyhow::Result<Vec<(i32, String, String)>> {
+        let rows: Vec<(i32, String, String)> = sqlx::query_as(
+            r#"
+            SELECT A.id, W.webhook_url, W.webhook_secret
+            FROM ftd_alert A
+            INNER JOIN ftd_watch_entry W ON W.id = A.watch_entry_id
+            WHERE A.delivery_status = 'pending' AND A.next_delivery_at <= now() AND W.webhook_url IS NOT NULL AND W.webhook_secret IS NOT NULL
+            ORDER BY A.id ASC
+            LIMIT $1
+            "#,
+        )
+        .bind(limit as i32)
+        .fetch_all(&self.connection_pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn set_alert_delivered(&self, id: i32) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE ftd_alert
+            SET delivery_status = 'delivered', delivery_attempt_count = delivery_attempt_count + 1, delivered_at = now(), next_delivery_at = NULL, updated_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Records a failed delivery attempt. The alert is retried after the given delay, or
+    /// marked as failed if no delay is given.
+    pub async fn set_alert_delivery_failed(
+        &self,
+        id: i32,
+        error: &str,
+        maybe_retry_delay_seconds: Option<u64>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE ftd_alert
+            SET delivery_status = CASE WHEN $3::BIGINT IS NULL THEN 'failed' ELSE 'pending' END,
+                delivery_attempt_count = delivery_attempt_count + 1,
+                last_delivery_error = $2,
+                next_delivery_at = now() + make_interval(secs => $3::BIGINT),
+                updated_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(error)
+        .bind(maybe_retry_delay_seconds.map(|seconds| seconds as i64))
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+}
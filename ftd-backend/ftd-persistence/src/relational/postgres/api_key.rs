@@ -0,0 +1,95 @@
+use super::PostgreSQLStorage;
+use ftd_types::api::api_key::{APIKey, APIKeyScope};
+use std::str::FromStr;
+
+type APIKeyRow = (i32, String, String, String, i32, i32, bool, i64);
+type APIKeyWithHashRow = (String, i32, String, String, String, i32, i32, bool, i64);
+
+fn row_into_api_key(row: &APIKeyRow) -> anyhow::Result<APIKey> {
+    Ok(APIKey {
+        id: row.0 as u32,
+        name: row.1.clone(),
+        key_prefix: row.2.clone(),
+        scope: APIKeyScope::from_str(&row.3).map_err(anyhow::Error::msg)?,
+        rate_limit_per_minute: row.4 as u32,
+        burst: row.5 as u32,
+        is_active: row.6,
+        created_at: row.7 as u64,
+    })
+}
+
+impl PostgreSQLStorage {
+    pub async fn save_api_key(
+        &self,
+        name: &str,
+        key_hash: &str,
+        key_prefix: &str,
+        scope: APIKeyScope,
+        rate_limit_per_minute: u32,
+        burst: u32,
+    ) -> anyhow::Result<APIKey> {
+        let row: APIKeyRow = sqlx::query_as(
+            r#"
+            INSERT INTO ftd_api_key (name, key_hash, key_prefix, scope, rate_limit_per_minute, burst)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, name, key_prefix, scope, rate_limit_per_minute, burst, is_active, (EXTRACT(EPOCH FROM created_at) * 1000)::BIGINT
+            "#,
+        )
+        .bind(name)
+        .bind(key_hash)
+        .bind(key_prefix)
+        .bind(scope.to_string())
+        .bind(rate_limit_per_minute as i32)
+        .bind(burst as i32)
+        .fetch_one(&self.connection_pool)
+        .await?;
+        row_into_api_key(&row)
+    }
+
+    /// Returns `false` if there is no API key with the given id.
+    pub async fn delete_api_key(&self, id: i32) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM ftd_api_key WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_api_keys(&self) -> anyhow::Result<Vec<APIKey>> {
+        let rows: Vec<APIKeyRow> = sqlx::query_as(
+            r#"
+            SELECT id, name, key_prefix, scope, rate_limit_per_minute, burst, is_active, (EXTRACT(EPOCH FROM created_at) * 1000)::BIGINT
+            FROM ftd_api_key
+            ORDER BY id ASC
+            "#,
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+        rows.iter().map(row_into_api_key).collect()
+    }
+
+    /// Gets the active API keys along with their hashes.
+    pub async fn get_active_api_keys_with_hashes(&self) -> anyhow::Result<Vec<(String, APIKey)>> {
+        let rows: Vec<APIKeyWithHashRow> =
+            sqlx::query_as(
+                r#"
+                SELECT key_hash, id, name, key_prefix, scope, rate_limit_per_minute, burst, is_active, (EXTRACT(EPOCH FROM created_at) * 1000)::BIGINT
+                FROM ftd_api_key
+                WHERE is_active = true
+                "#,
+            )
+            .fetch_all(&self.connection_pool)
+            .await?;
+        rows.into_iter()
+            .map(|row| {
+                let api_key =
+                    row_into_api_key(&(row.1, row.2, row.3, row.4, row.5, row.6, row.7, row.8))?;
+                Ok((row.0, api_key))
+            })
+            .collect()
+    }
+}
@@ -29,7 +29,7 @@ impl PostgreSQLStorage {
         Ok(record_count.0 > 0)
     }
 
-    pub async fn _get_block_hash(&self, block_number: u64) -> anyhow::Result<Option<String>> {
+    pub async fn get_block_hash(&self, block_number: u64) -> anyhow::Result<Option<String>> {
         Ok(sqlx::query_as(
             r#"
             SELECT hash FROM ftd_block
@@ -42,6 +42,25 @@ impl PostgreSQLStorage {
         .map(|hash: (String,)| hash.0))
     }
 
+    /// Returns the hash and number of the last block produced at or before the given timestamp.
+    pub async fn get_block_hash_and_number_at_timestamp(
+        &self,
+        timestamp: u64,
+    ) -> anyhow::Result<Option<(String, u64)>> {
+        Ok(sqlx::query_as(
+            r#"
+            SELECT hash, number FROM ftd_block
+            WHERE timestamp <= $1
+            ORDER BY timestamp DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(timestamp as i64)
+        .fetch_optional(&self.connection_pool)
+        .await?
+        .map(|row: (String, i64)| (row.0, row.1 as u64)))
+    }
+
     pub async fn get_max_block_number(&self) -> anyhow::Result<i64> {
         let max_block_number: (i64,) = sqlx::query_as(
             r#"
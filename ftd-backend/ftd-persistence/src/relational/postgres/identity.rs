@@ -1,13 +1,332 @@
+use super::account::{IdentityRow, SubIdentityRow};
 use super::PostgreSQLStorage;
-use ftd_types::substrate::identity::{Identity, SubIdentity};
+use ftd_types::api::identity::{
+    Identity as APIIdentity, IdentityChain, IdentityChange, IdentityChangeType,
+    IdentityDataHashType, IdentityJudgement, Registrar as APIRegistrar,
+    SubIdentity as APISubIdentity,
+};
+use ftd_types::substrate::account_id::AccountId;
+use ftd_types::substrate::identity::{Identity, IdentityChangeBlock, Registrar, SubIdentity};
+use sqlx::types::Json;
 use sqlx::{Postgres, Transaction};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+#[derive(sqlx::FromRow)]
+struct IdentityChangeRow {
+    id: i32,
+    address: String,
+    change_type: String,
+    display: Option<String>,
+    legal: Option<String>,
+    web: Option<String>,
+    riot: Option<String>,
+    email: Option<String>,
+    twitter: Option<String>,
+    github: Option<String>,
+    discord: Option<String>,
+    image: Option<String>,
+    pgp_fingerprint: Option<String>,
+    hashed_fields: Option<Json<BTreeMap<String, IdentityDataHashType>>>,
+    judgements: Option<Json<Vec<IdentityJudgement>>>,
+    is_confirmed: Option<bool>,
+    is_invalid: Option<bool>,
+    super_address: Option<String>,
+    sub_display: Option<String>,
+    chain: String,
+    is_backfill: bool,
+    block_hash: String,
+    block_number: i64,
+    block_timestamp: i64,
+}
+
+fn row_into_identity_change(row: IdentityChangeRow) -> anyhow::Result<IdentityChange> {
+    let change_type = IdentityChangeType::from_str(&row.change_type).map_err(anyhow::Error::msg)?;
+    let identity = match change_type {
+        IdentityChangeType::IdentitySet => Some(APIIdentity {
+            address: row.address.clone(),
+            display: row.display,
+            email: row.email,
+            legal: row.legal,
+            riot: row.riot,
+            twitter: row.twitter,
+            web: row.web,
+            github: row.github,
+            discord: row.discord,
+            image: row.image,
+            pgp_fingerprint: row.pgp_fingerprint,
+            hashed_fields: row
+                .hashed_fields
+                .map(|hashed_fields| hashed_fields.0)
+                .unwrap_or_default(),
+            judgements: row
+                .judgements
+                .map(|judgements| judgements.0)
+                .unwrap_or_default(),
+            is_confirmed: row.is_confirmed.unwrap_or(false),
+            is_invalid: row.is_invalid.unwrap_or(false),
+        }),
+        _ => None,
+    };
+    let sub_identity = match (change_type, row.super_address) {
+        (IdentityChangeType::SubIdentitySet, Some(super_address)) => Some(APISubIdentity {
+            address: row.address.clone(),
+            super_address,
+            sub_display: row.sub_display,
+        }),
+        _ => None,
+    };
+    Ok(IdentityChange {
+        id: row.id,
+        address: row.address,
+        change_type,
+        identity,
+        sub_identity,
+        chain: IdentityChain::from_str(&row.chain).map_err(anyhow::Error::msg)?,
+        is_backfill: row.is_backfill,
+        block_hash: row.block_hash,
+        block_number: row.block_number as u64,
+        block_timestamp: row.block_timestamp as u64,
+    })
+}
+
+fn row_into_identity(row: IdentityRow) -> anyhow::Result<Identity> {
+    Ok(Identity {
+        account_id: AccountId::from_str(&row.0)?,
+        display: row.1,
+        legal: row.2,
+        web: row.3,
+        riot: row.4,
+        email: row.5,
+        twitter: row.6,
+        github: row.7,
+        discord: row.8,
+        image: row.9,
+        pgp_fingerprint: row.10,
+        hashed_fields: (*row.11).clone(),
+        judgements: (*row.12).clone(),
+        is_confirmed: row.13,
+        is_invalid: row.14,
+    })
+}
+
+fn row_into_sub_identity(row: SubIdentityRow) -> anyhow::Result<SubIdentity> {
+    Ok(SubIdentity {
+        account_id: AccountId::from_str(&row.0)?,
+        super_account_id: AccountId::from_str(&row.1)?,
+        sub_display: row.2,
+    })
+}
 
 impl PostgreSQLStorage {
-    pub async fn delete_all_identities(
+    /// All stored identities, to be diffed against the chain state.
+    pub async fn get_all_identities(&self) -> anyhow::Result<Vec<Identity>> {
+        let rows: Vec<IdentityRow> = sqlx::query_as(
+            r#"
+            SELECT address, display, legal, web, riot, email, twitter, github, discord, image, pgp_fingerprint, hashed_fields, judgements, is_confirmed, is_invalid
+            FROM ftd_identity
+            "#,
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+        rows.into_iter().map(row_into_identity).collect()
+    }
+
+    /// Appends an `identity_set` change with the given identity, or an `identity_cleared`
+    /// change for the account if there's no identity.
+    pub async fn save_identity_change(
         &self,
+        account_id: &AccountId,
+        maybe_identity: Option<&Identity>,
+        block: &IdentityChangeBlock,
+        is_backfill: bool,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> anyhow::Result<i32> {
+        let change_type = if maybe_identity.is_some() {
+            IdentityChangeType::IdentitySet
+        } else {
+            IdentityChangeType::IdentityCleared
+        };
+        let result: (i32,) = sqlx::query_as(
+            r#"
+            INSERT INTO ftd_identity_change (address, change_type, display, legal, web, riot, email, twitter, github, discord, image, pgp_fingerprint, hashed_fields, judgements, is_confirmed, is_invalid, chain, is_backfill, block_hash, block_number, block_timestamp)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)
+            RETURNING id
+            "#,
+        )
+        .bind(account_id.to_ss58_check())
+        .bind(change_type.to_string())
+        .bind(maybe_identity.and_then(|identity| identity.display.as_deref()))
+        .bind(maybe_identity.and_then(|identity| identity.legal.as_deref()))
+        .bind(maybe_identity.and_then(|identity| identity.web.as_deref()))
+        .bind(maybe_identity.and_then(|identity| identity.riot.as_deref()))
+        .bind(maybe_identity.and_then(|identity| identity.email.as_deref()))
+        .bind(maybe_identity.and_then(|identity| identity.twitter.as_deref()))
+        .bind(maybe_identity.and_then(|identity| identity.github.as_deref()))
+        .bind(maybe_identity.and_then(|identity| identity.discord.as_deref()))
+        .bind(maybe_identity.and_then(|identity| identity.image.as_deref()))
+        .bind(maybe_identity.and_then(|identity| identity.pgp_fingerprint.as_deref()))
+        .bind(maybe_identity.map(|identity| Json(&identity.hashed_fields)))
+        .bind(maybe_identity.map(|identity| Json(&identity.judgements)))
+        .bind(maybe_identity.map(|identity| identity.is_confirmed))
+        .bind(maybe_identity.map(|identity| identity.is_invalid))
+        .bind(block.chain.to_string())
+        .bind(is_backfill)
+        .bind(&block.hash)
+        .bind(block.number as i64)
+        .bind(block.timestamp as i64)
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok(result.0)
+    }
+
+    /// Appends a `sub_identity_set` change with the given sub identity, or a
+    /// `sub_identity_cleared` change for the account if there's no sub identity.
+    pub async fn save_sub_identity_change(
+        &self,
+        account_id: &AccountId,
+        maybe_sub_identity: Option<&SubIdentity>,
+        block: &IdentityChangeBlock,
+        is_backfill: bool,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> anyhow::Result<i32> {
+        let change_type = if maybe_sub_identity.is_some() {
+            IdentityChangeType::SubIdentitySet
+        } else {
+            IdentityChangeType::SubIdentityCleared
+        };
+        let result: (i32,) = sqlx::query_as(
+            r#"
+            INSERT INTO ftd_identity_change (address, change_type, super_address, sub_display, chain, is_backfill, block_hash, block_number, block_timestamp)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id
+            "#,
+        )
+        .bind(account_id.to_ss58_check())
+        .bind(change_type.to_string())
+        .bind(maybe_sub_identity.map(|sub_identity| sub_identity.super_account_id.to_ss58_check()))
+        .bind(maybe_sub_identity.and_then(|sub_identity| sub_identity.sub_display.as_deref()))
+        .bind(block.chain.to_string())
+        .bind(is_backfill)
+        .bind(&block.hash)
+        .bind(block.number as i64)
+        .bind(block.timestamp as i64)
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok(result.0)
+    }
+
+    /// Identity changes of the account in the order they were observed, optionally only the
+    /// ones observed at or before the given People chain block number or timestamp (ms). The
+    /// relay chain changes precede all People chain blocks.
+    pub async fn get_identity_changes(
+        &self,
+        address: &str,
+        maybe_max_block_number: Option<u64>,
+        maybe_max_timestamp: Option<u64>,
+    ) -> anyhow::Result<Vec<IdentityChange>> {
+        let rows: Vec<IdentityChangeRow> = sqlx::query_as(
+            r#"
+            SELECT id, address, change_type, display, legal, web, riot, email, twitter, github, discord, image, pgp_fingerprint, hashed_fields, judgements, is_confirmed, is_invalid, super_address, sub_display, chain, is_backfill, block_hash, block_number, block_timestamp
+            FROM ftd_identity_change
+            WHERE address = $1
+            AND ($2::BIGINT IS NULL OR chain = 'relay' OR block_number <= $2)
+            AND ($3::BIGINT IS NULL OR block_timestamp <= $3)
+            ORDER BY block_timestamp ASC, id ASC
+            "#,
+        )
+        .bind(address)
+        .bind(maybe_max_block_number.map(|block_number| block_number as i64))
+        .bind(maybe_max_timestamp.map(|timestamp| timestamp as i64))
+        .fetch_all(&self.connection_pool)
+        .await?;
+        rows.into_iter().map(row_into_identity_change).collect()
+    }
+
+    /// Identity changes of all accounts with an id greater than `after_id`, in id order.
+    pub async fn get_identity_changes_after(
+        &self,
+        after_id: i32,
+        limit: u16,
+    ) -> anyhow::Result<Vec<IdentityChange>> {
+        let rows: Vec<IdentityChangeRow> = sqlx::query_as(
+            r#"
+            SELECT id, address, change_type, display, legal, web, riot, email, twitter, github, discord, image, pgp_fingerprint, hashed_fields, judgements, is_confirmed, is_invalid, super_address, sub_display, chain, is_backfill, block_hash, block_number, block_timestamp
+            FROM ftd_identity_change
+            WHERE id > $1
+            ORDER BY id ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(after_id)
+        .bind(limit as i32)
+        .fetch_all(&self.connection_pool)
+        .await?;
+        rows.into_iter().map(row_into_identity_change).collect()
+    }
+
+    /// First block at which the identity updater, not the backfill, observed People chain
+    /// identity changes.
+    pub async fn get_first_identity_change_block(
+        &self,
+    ) -> anyhow::Result<Option<IdentityChangeBlock>> {
+        let maybe_row: Option<(String, i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT block_hash, block_number, block_timestamp
+            FROM ftd_identity_change
+            WHERE chain = 'people' AND NOT is_backfill
+            ORDER BY block_number ASC
+            LIMIT 1
+            "#,
+        )
+        .fetch_optional(&self.connection_pool)
+        .await?;
+        Ok(maybe_row.map(|row| IdentityChangeBlock {
+            chain: IdentityChain::People,
+            hash: row.0,
+            number: row.1 as u64,
+            timestamp: row.2 as u64,
+        }))
+    }
+
+    /// Where the identity change log starts: whether it has relay chain changes, which precede
+    /// all People chain blocks, the first People chain block number, and the first timestamp.
+    pub async fn get_identity_change_log_start(
+        &self,
+    ) -> anyhow::Result<(bool, Option<u64>, Option<u64>)> {
+        let row: (bool, Option<i64>, Option<i64>) = sqlx::query_as(
+            r#"
+            SELECT EXISTS (SELECT 1 FROM ftd_identity_change WHERE chain = 'relay'),
+                (SELECT MIN(block_number) FROM ftd_identity_change WHERE chain = 'people'),
+                (SELECT MIN(block_timestamp) FROM ftd_identity_change)
+            "#,
+        )
+        .fetch_one(&self.connection_pool)
+        .await?;
+        Ok((
+            row.0,
+            row.1.map(|block_number| block_number as u64),
+            row.2.map(|timestamp| timestamp as u64),
+        ))
+    }
+
+    pub async fn delete_backfill_identity_changes(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> anyhow::Result<u64> {
+        let result = sqlx::query("DELETE FROM ftd_identity_change WHERE is_backfill")
+            .execute(&mut **tx)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_identities(
+        &self,
+        addresses: &[String],
         tx: &mut Transaction<'_, Postgres>,
     ) -> anyhow::Result<()> {
-        sqlx::query("DELETE FROM ftd_identity")
+        sqlx::query("DELETE FROM ftd_identity WHERE address = ANY($1)")
+            .bind(addresses)
             .execute(&mut **tx)
             .await?;
         Ok(())
@@ -22,8 +341,8 @@ impl PostgreSQLStorage {
         self.save_account(address.as_str(), tx).await?;
         let result: (String,) = sqlx::query_as(
             r#"
-            INSERT INTO ftd_identity (address, display, legal, web, riot, email, twitter, is_confirmed, is_invalid)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            INSERT INTO ftd_identity (address, display, legal, web, riot, email, twitter, github, discord, image, pgp_fingerprint, hashed_fields, judgements, is_confirmed, is_invalid)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
             ON CONFLICT (address) DO UPDATE
             SET
                 display = EXCLUDED.display,
@@ -32,6 +351,12 @@ impl PostgreSQLStorage {
                 riot = EXCLUDED.riot,
                 email = EXCLUDED.email,
                 twitter = EXCLUDED.twitter,
+                github = EXCLUDED.github,
+                discord = EXCLUDED.discord,
+                image = EXCLUDED.image,
+                pgp_fingerprint = EXCLUDED.pgp_fingerprint,
+                hashed_fields = EXCLUDED.hashed_fields,
+                judgements = EXCLUDED.judgements,
                 is_confirmed = EXCLUDED.is_confirmed,
                 is_invalid = EXCLUDED.is_invalid,
                 updated_at = now()
@@ -45,6 +370,12 @@ impl PostgreSQLStorage {
             .bind(&identity.riot)
             .bind(&identity.email)
             .bind(&identity.twitter)
+            .bind(&identity.github)
+            .bind(&identity.discord)
+            .bind(&identity.image)
+            .bind(&identity.pgp_fingerprint)
+            .bind(Json(&identity.hashed_fields))
+            .bind(Json(&identity.judgements))
             .bind(identity.is_confirmed)
             .bind(identity.is_invalid)
             .fetch_one(&mut **tx)
@@ -52,11 +383,26 @@ impl PostgreSQLStorage {
         Ok(result.0)
     }
 
-    pub async fn delete_all_sub_identities(
+    /// All stored sub identities, to be diffed against the chain state.
+    pub async fn get_all_sub_identities(&self) -> anyhow::Result<Vec<SubIdentity>> {
+        let rows: Vec<SubIdentityRow> = sqlx::query_as(
+            r#"
+            SELECT address, super_address, sub_display
+            FROM ftd_sub_identity
+            "#,
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+        rows.into_iter().map(row_into_sub_identity).collect()
+    }
+
+    pub async fn delete_sub_identities(
         &self,
+        addresses: &[String],
         tx: &mut Transaction<'_, Postgres>,
     ) -> anyhow::Result<()> {
-        sqlx::query("DELETE FROM ftd_sub_identity")
+        sqlx::query("DELETE FROM ftd_sub_identity WHERE address = ANY($1)")
+            .bind(addresses)
             .execute(&mut **tx)
             .await?;
         Ok(())
@@ -91,6 +437,81 @@ impl PostgreSQLStorage {
         Ok(result.0)
     }
 
+    /// Replaces the stored registrars.
+    pub async fn save_registrars(
+        &self,
+        registrars: &[Registrar],
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM ftd_registrar")
+            .execute(&mut **tx)
+            .await?;
+        for registrar in registrars {
+            sqlx::query(
+                r#"
+                INSERT INTO ftd_registrar (registrar_index, address, fee, fields)
+                VALUES ($1, $2, $3, $4)
+                "#,
+            )
+            .bind(registrar.index as i32)
+            .bind(registrar.account_id.to_ss58_check())
+            .bind(registrar.fee.to_string())
+            .bind(registrar.fields as i64)
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_registrars(&self) -> anyhow::Result<Vec<APIRegistrar>> {
+        let rows: Vec<(i32, String, String, i64)> = sqlx::query_as(
+            r#"
+            SELECT registrar_index, address, fee, fields
+            FROM ftd_registrar
+            ORDER BY registrar_index ASC
+            "#,
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+        let mut registrars = Vec::with_capacity(rows.len());
+        for row in rows {
+            registrars.push(APIRegistrar {
+                index: row.0 as u32,
+                address: row.1,
+                fee: row.2.parse()?,
+                fields: row.3 as u64,
+            });
+        }
+        Ok(registrars)
+    }
+
+    pub async fn is_identity_baseline_pending(&self) -> anyhow::Result<bool> {
+        let row: (bool,) = sqlx::query_as(
+            r#"
+            SELECT is_identity_baseline_pending FROM ftd_identity_transfer_updater_state WHERE id = 1
+            "#,
+        )
+        .fetch_one(&self.connection_pool)
+        .await?;
+        Ok(row.0)
+    }
+
+    pub async fn clear_identity_baseline_pending(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE ftd_identity_transfer_updater_state
+            SET is_identity_baseline_pending = FALSE
+            WHERE id = 1
+            "#,
+        )
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
     pub async fn set_identity_updater_state(
         &self,
         block_hash: &str,
@@ -0,0 +1,103 @@
+use super::PostgreSQLStorage;
+use ftd_types::api::label::{Label, LabelCategory};
+use std::str::FromStr;
+
+type LabelRow = (i32, String, String, String, String, f32, i64);
+
+fn row_into_label(row: &LabelRow) -> anyhow::Result<Label> {
+    Ok(Label {
+        id: row.0 as u32,
+        address: row.1.clone(),
+        name: row.2.clone(),
+        category: LabelCategory::from_str(&row.3).map_err(anyhow::Error::msg)?,
+        source: row.4.clone(),
+        confidence: row.5,
+        labeled_at: row.6 as u64,
+    })
+}
+
+impl PostgreSQLStorage {
+    /// Inserts the label, or updates the category, confidence and labeling time of the label
+    /// with the same address, name and source.
+    pub async fn save_label(
+        &self,
+        address: &str,
+        name: &str,
+        category: LabelCategory,
+        source: &str,
+        confidence: f32,
+        labeled_at: u64,
+    ) -> anyhow::Result<Label> {
+        let row: LabelRow = sqlx::query_as(
+            r#"
+            INSERT INTO ftd_label (address, name, category, source, confidence, labeled_at)
+            VALUES ($1, $2, $3, $4, $5, to_timestamp($6::BIGINT / 1000.0) AT TIME ZONE 'UTC')
+            ON CONFLICT (address, name, source) DO UPDATE
+            SET category = EXCLUDED.category, confidence = EXCLUDED.confidence, labeled_at = EXCLUDED.labeled_at, updated_at = now()
+            RETURNING id, address, name, category, source, confidence, (EXTRACT(EPOCH FROM labeled_at) * 1000)::BIGINT
+            "#,
+        )
+        .bind(address)
+        .bind(name)
+        .bind(category.to_string())
+        .bind(source)
+        .bind(confidence)
+        .bind(labeled_at as i64)
+        .fetch_one(&self.connection_pool)
+        .await?;
+        row_into_label(&row)
+    }
+
+    /// Returns `false` if there is no label with the given id.
+    pub async fn delete_label(&self, id: i32) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM ftd_label WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_labels(
+        &self,
+        maybe_category: Option<LabelCategory>,
+        maybe_source: Option<&str>,
+    ) -> anyhow::Result<Vec<Label>> {
+        let rows: Vec<LabelRow> = sqlx::query_as(
+            r#"
+            SELECT id, address, name, category, source, confidence, (EXTRACT(EPOCH FROM labeled_at) * 1000)::BIGINT
+            FROM ftd_label
+            WHERE ($1::VARCHAR IS NULL OR category = $1)
+            AND ($2::VARCHAR IS NULL OR source = $2)
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(maybe_category.map(|category| category.to_string()))
+        .bind(maybe_source)
+        .fetch_all(&self.connection_pool)
+        .await?;
+        rows.iter().map(row_into_label).collect()
+    }
+
+    /// Gets the labels of the given accounts, the most confident first.
+    pub async fn get_labels_by_addresses(
+        &self,
+        addresses: &[String],
+    ) -> anyhow::Result<Vec<Label>> {
+        let rows: Vec<LabelRow> = sqlx::query_as(
+            r#"
+            SELECT id, address, name, category, source, confidence, (EXTRACT(EPOCH FROM labeled_at) * 1000)::BIGINT
+            FROM ftd_label
+            WHERE address = ANY($1)
+            ORDER BY confidence DESC, id ASC
+            "#,
+        )
+        .bind(addresses)
+        .fetch_all(&self.connection_pool)
+        .await?;
+        rows.iter().map(row_into_label).collect()
+    }
+}
@@ -3,11 +3,16 @@ use sqlx::{Pool, Postgres, Transaction};
 use std::time::Duration;
 
 pub mod account;
+pub mod alert;
+pub mod api_key;
 pub mod block;
 pub mod identity;
+pub mod label;
+pub mod relation;
 pub mod subscan;
 pub mod transfer;
 pub mod transfer_volume;
+pub mod username;
 
 pub(crate) struct PostgreSQLStorage {
     connection_pool: Pool<Postgres>,
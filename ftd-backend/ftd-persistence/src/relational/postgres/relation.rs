@@ -0,0 +1,230 @@
+use super::PostgreSQLStorage;
+use ftd_types::api::relation::{
+    AccountRelation as APIAccountRelation, AccountRelationChange, AccountRelationType,
+};
+use ftd_types::substrate::account_id::AccountId;
+use ftd_types::substrate::relation::{AccountRelation, AccountRelationKey};
+use sqlx::{Postgres, Transaction};
+use std::str::FromStr;
+
+type AccountRelationRow = (
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<i32>,
+    Option<i32>,
+);
+
+fn row_into_account_relation(row: AccountRelationRow) -> anyhow::Result<AccountRelation> {
+    Ok(AccountRelation {
+        from: AccountId::from_str(&row.0)?,
+        to: AccountId::from_str(&row.1)?,
+        relation_type: AccountRelationType::from_str(&row.2).map_err(anyhow::Error::msg)?,
+        proxy_type: row.3,
+        proxy_delay: row.4.map(|proxy_delay| proxy_delay as u32),
+        multisig_threshold: row.5.map(|multisig_threshold| multisig_threshold as u16),
+    })
+}
+
+#[derive(sqlx::FromRow)]
+struct AccountRelationChangeRow {
+    id: i32,
+    from_address: String,
+    to_address: String,
+    relation_type: String,
+    proxy_type: Option<String>,
+    proxy_delay: Option<i32>,
+    multisig_threshold: Option<i32>,
+    is_removed: bool,
+    block_number: i64,
+}
+
+fn row_into_account_relation_change(
+    row: AccountRelationChangeRow,
+) -> anyhow::Result<AccountRelationChange> {
+    Ok(AccountRelationChange {
+        id: row.id,
+        relation: APIAccountRelation {
+            from: row.from_address,
+            to: row.to_address,
+            relation_type: AccountRelationType::from_str(&row.relation_type)
+                .map_err(anyhow::Error::msg)?,
+            proxy_type: row.proxy_type,
+            proxy_delay: row.proxy_delay.map(|proxy_delay| proxy_delay as u32),
+            multisig_threshold: row
+                .multisig_threshold
+                .map(|multisig_threshold| multisig_threshold as u16),
+        },
+        is_removed: row.is_removed,
+        block_number: row.block_number as u64,
+    })
+}
+
+impl PostgreSQLStorage {
+    /// Stored relations of the given types, to be diffed against the chain state.
+    pub async fn get_account_relations_by_types(
+        &self,
+        relation_types: &[AccountRelationType],
+    ) -> anyhow::Result<Vec<AccountRelation>> {
+        let relation_types: Vec<String> = relation_types
+            .iter()
+            .map(|relation_type| relation_type.to_string())
+            .collect();
+        let rows: Vec<AccountRelationRow> = sqlx::query_as(
+            r#"
+            SELECT from_address, to_address, relation_type, proxy_type, proxy_delay, multisig_threshold
+            FROM ftd_account_relation
+            WHERE relation_type = ANY($1)
+            "#,
+        )
+        .bind(relation_types)
+        .fetch_all(&self.connection_pool)
+        .await?;
+        rows.into_iter().map(row_into_account_relation).collect()
+    }
+
+    /// Inserts or updates the relation, returns whether it was new or changed.
+    pub async fn save_account_relation(
+        &self,
+        relation: &AccountRelation,
+        block_number: u64,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> anyhow::Result<bool> {
+        let from_address = relation.from.to_ss58_check();
+        let to_address = relation.to.to_ss58_check();
+        self.save_account(from_address.as_str(), tx).await?;
+        self.save_account(to_address.as_str(), tx).await?;
+        let maybe_result: Option<(i32,)> = sqlx::query_as(
+            r#"
+            INSERT INTO ftd_account_relation (from_address, to_address, relation_type, proxy_type, proxy_delay, multisig_threshold, block_number)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (from_address, to_address, relation_type, COALESCE(proxy_type, '')) DO UPDATE
+            SET
+                proxy_delay = EXCLUDED.proxy_delay,
+                multisig_threshold = EXCLUDED.multisig_threshold,
+                block_number = EXCLUDED.block_number,
+                updated_at = now()
+            WHERE (ftd_account_relation.proxy_delay, ftd_account_relation.multisig_threshold)
+                IS DISTINCT FROM (EXCLUDED.proxy_delay, EXCLUDED.multisig_threshold)
+            RETURNING id
+            "#,
+        )
+        .bind(&from_address)
+        .bind(&to_address)
+        .bind(relation.relation_type.to_string())
+        .bind(&relation.proxy_type)
+        .bind(relation.proxy_delay.map(|proxy_delay| proxy_delay as i32))
+        .bind(
+            relation
+                .multisig_threshold
+                .map(|multisig_threshold| multisig_threshold as i32),
+        )
+        .bind(block_number as i64)
+        .fetch_optional(&mut **tx)
+        .await?;
+        Ok(maybe_result.is_some())
+    }
+
+    pub async fn delete_account_relation(
+        &self,
+        key: &AccountRelationKey,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM ftd_account_relation
+            WHERE from_address = $1 AND to_address = $2 AND relation_type = $3 AND COALESCE(proxy_type, '') = COALESCE($4, '')
+            "#,
+        )
+        .bind(key.0.to_ss58_check())
+        .bind(key.1.to_ss58_check())
+        .bind(key.2.to_string())
+        .bind(&key.3)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// Appends the saved relation, or the removal of the relation with the given key, to the
+    /// relation change log.
+    pub async fn save_account_relation_change(
+        &self,
+        key: &AccountRelationKey,
+        maybe_relation: Option<&AccountRelation>,
+        block_number: u64,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO ftd_account_relation_change (from_address, to_address, relation_type, proxy_type, proxy_delay, multisig_threshold, is_removed, block_number)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(key.0.to_ss58_check())
+        .bind(key.1.to_ss58_check())
+        .bind(key.2.to_string())
+        .bind(&key.3)
+        .bind(
+            maybe_relation
+                .and_then(|relation| relation.proxy_delay)
+                .map(|proxy_delay| proxy_delay as i32),
+        )
+        .bind(
+            maybe_relation
+                .and_then(|relation| relation.multisig_threshold)
+                .map(|multisig_threshold| multisig_threshold as i32),
+        )
+        .bind(maybe_relation.is_none())
+        .bind(block_number as i64)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_account_relation_changes_after(
+        &self,
+        after_id: i32,
+        limit: u16,
+    ) -> anyhow::Result<Vec<AccountRelationChange>> {
+        let rows: Vec<AccountRelationChangeRow> = sqlx::query_as(
+            r#"
+            SELECT id, from_address, to_address, relation_type, proxy_type, proxy_delay, multisig_threshold, is_removed, block_number
+            FROM ftd_account_relation_change
+            WHERE id > $1
+            ORDER BY id ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(after_id)
+        .bind(limit as i32)
+        .fetch_all(&self.connection_pool)
+        .await?;
+        rows.into_iter()
+            .map(row_into_account_relation_change)
+            .collect()
+    }
+
+    pub async fn set_relation_updater_state(
+        &self,
+        block_hash: &str,
+        block_number: u64,
+        is_successful: bool,
+        error_log: Option<&str>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE ftd_relation_updater_state
+            SET block_hash = $1, block_number = $2, is_successful = $3, error_log = $4, updated_at = now()
+            WHERE id = 1
+            "#,
+        )
+        .bind(block_hash)
+        .bind(block_number as i64)
+        .bind(is_successful)
+        .bind(error_log)
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -107,20 +107,20 @@ impl PostgreSQLStorage {
         Ok(result.0)
     }
 
-    pub async fn get_subscan_account(
+    pub async fn get_subscan_accounts(
         &self,
-        address: &str,
-    ) -> anyhow::Result<Option<SubscanAccount>> {
-        let maybe_row: Option<SubscanAccountRow> = sqlx::query_as(
+        addresses: &[String],
+    ) -> anyhow::Result<Vec<SubscanAccount>> {
+        let rows: Vec<SubscanAccountRow> = sqlx::query_as(
             r#"
             SELECT address, display, account_index, account_display, account_identity, parent_address, parent_display, parent_sub_symbol, parent_identity, merkle_science_address_type, merkle_science_tag_type, merkle_science_tag_sub_type, merkle_science_tag_name
             FROM ftd_subscan_account
-            WHERE address = $1
+            WHERE address = ANY($1)
             "#,
         )
-            .bind(address)
-            .fetch_optional(&self.connection_pool)
+            .bind(addresses)
+            .fetch_all(&self.connection_pool)
             .await?;
-        Ok(maybe_row.as_ref().map(row_into_subscan_account))
+        Ok(rows.iter().map(row_into_subscan_account).collect())
     }
 }
@@ -2,8 +2,13 @@ use super::PostgreSQLStorage;
 use ftd_types::api::transfer::Transfer;
 use ftd_types::substrate::block::Block;
 use ftd_types::substrate::event::TransferEvent;
+use sqlx::postgres::PgListener;
 use sqlx::{Postgres, Transaction};
 
+/// Channel notified with the id of every inserted transfer, see the `ftd_transfer_notify`
+/// trigger.
+const TRANSFER_NOTIFICATION_CHANNEL: &str = "ftd_transfer";
+
 type TransferRow = (String, i64, i64, i32, i32, i32, String, String, String);
 
 fn row_into_transfer(row: &TransferRow) -> anyhow::Result<Transfer> {
@@ -32,6 +37,26 @@ impl PostgreSQLStorage {
         Ok(id.0)
     }
 
+    /// Gets the distinct senders and recipients of the transfers in the given id range.
+    pub async fn get_transfer_addresses_in_id_range(
+        &self,
+        first_id: i32,
+        last_id: i32,
+    ) -> anyhow::Result<Vec<String>> {
+        let addresses: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT from_address FROM ftd_transfer WHERE id BETWEEN $1 AND $2
+            UNION
+            SELECT to_address FROM ftd_transfer WHERE id BETWEEN $1 AND $2
+            "#,
+        )
+        .bind(first_id)
+        .bind(last_id)
+        .fetch_all(&self.connection_pool)
+        .await?;
+        Ok(addresses.into_iter().map(|address| address.0).collect())
+    }
+
     pub async fn save_transfer(
         &self,
         block: &Block,
@@ -108,4 +133,120 @@ impl PostgreSQLStorage {
         }
         Ok(transfers)
     }
+
+    pub async fn get_first_and_last_transfer_block_numbers(
+        &self,
+        address: &str,
+    ) -> anyhow::Result<(Option<u64>, Option<u64>)> {
+        let result: (Option<i64>, Option<i64>) = sqlx::query_as(
+            r#"
+            SELECT MIN(block_number), MAX(block_number)
+            FROM (
+                SELECT block_number FROM ftd_transfer WHERE from_address = $1
+                UNION ALL
+                SELECT block_number FROM ftd_transfer WHERE to_address = $1
+            ) AS account_transfer
+            "#,
+        )
+        .bind(address)
+        .fetch_one(&self.connection_pool)
+        .await?;
+        Ok((
+            result.0.map(|number| number as u64),
+            result.1.map(|number| number as u64),
+        ))
+    }
+
+    pub async fn get_largest_outgoing_transfer(
+        &self,
+        address: &str,
+    ) -> anyhow::Result<Option<Transfer>> {
+        let maybe_row: Option<TransferRow> = sqlx::query_as(
+            r#"
+            SELECT block_hash, block_number, timestamp, extrinsic_index, extrinsic_event_index, event_index, from_address, to_address, amount
+            FROM ftd_transfer
+            WHERE from_address = $1
+            ORDER BY amount::NUMERIC DESC, block_number DESC
+            LIMIT 1
+            "#,
+        )
+            .bind(address)
+            .fetch_optional(&self.connection_pool)
+            .await?;
+        maybe_row.as_ref().map(row_into_transfer).transpose()
+    }
+
+    pub async fn get_largest_incoming_transfer(
+        &self,
+        address: &str,
+    ) -> anyhow::Result<Option<Transfer>> {
+        let maybe_row: Option<TransferRow> = sqlx::query_as(
+            r#"
+            SELECT block_hash, block_number, timestamp, extrinsic_index, extrinsic_event_index, event_index, from_address, to_address, amount
+            FROM ftd_transfer
+            WHERE to_address = $1
+            ORDER BY amount::NUMERIC DESC, block_number DESC
+            LIMIT 1
+            "#,
+        )
+            .bind(address)
+            .fetch_optional(&self.connection_pool)
+            .await?;
+        maybe_row.as_ref().map(row_into_transfer).transpose()
+    }
+
+    /// Gets up to `limit` latest transfers for each given (sender, recipient) pair, latest first.
+    pub async fn get_transfers_by_sender_and_recipient_pairs(
+        &self,
+        pairs: &[(String, String)],
+        limit: u16,
+    ) -> anyhow::Result<Vec<Transfer>> {
+        let (from_addresses, to_addresses): (Vec<String>, Vec<String>) =
+            pairs.iter().cloned().unzip();
+        let rows: Vec<TransferRow> = sqlx::query_as(
+            r#"
+            SELECT t.block_hash, t.block_number, t.timestamp, t.extrinsic_index, t.extrinsic_event_index, t.event_index, t.from_address, t.to_address, t.amount
+            FROM UNNEST($1::VARCHAR[], $2::VARCHAR[]) AS pair(from_address, to_address)
+            CROSS JOIN LATERAL (
+                SELECT block_hash, block_number, timestamp, extrinsic_index, extrinsic_event_index, event_index, from_address, to_address, amount
+                FROM ftd_transfer
+                WHERE from_address = pair.from_address AND to_address = pair.to_address
+                ORDER BY block_number DESC, extrinsic_index DESC
+                LIMIT $3
+            ) t
+            ORDER BY t.block_number DESC, t.extrinsic_index DESC
+            "#,
+        )
+            .bind(from_addresses)
+            .bind(to_addresses)
+            .bind(limit as i64)
+            .fetch_all(&self.connection_pool)
+            .await?;
+        let mut transfers = Vec::new();
+        for row in rows.iter() {
+            transfers.push(row_into_transfer(row)?);
+        }
+        Ok(transfers)
+    }
+
+    /// Gets a transfer in the API format by its id.
+    pub async fn get_api_transfer_by_id(&self, id: i32) -> anyhow::Result<Option<Transfer>> {
+        let maybe_row: Option<TransferRow> = sqlx::query_as(
+            r#"
+            SELECT block_hash, block_number, timestamp, extrinsic_index, extrinsic_event_index, event_index, from_address, to_address, amount
+            FROM ftd_transfer
+            WHERE id = $1
+            "#,
+        )
+            .bind(id)
+            .fetch_optional(&self.connection_pool)
+            .await?;
+        maybe_row.as_ref().map(row_into_transfer).transpose()
+    }
+
+    pub async fn listen_transfers(&self) -> anyhow::Result<PgListener> {
+        let mut listener = PgListener::connect_with(&self.connection_pool).await?;
+        listener.listen(TRANSFER_NOTIFICATION_CHANNEL).await?;
+        Ok(listener)
+    }
 }
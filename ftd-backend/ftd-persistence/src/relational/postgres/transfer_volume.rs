@@ -71,4 +71,57 @@ impl PostgreSQLStorage {
         .await?;
         Ok((volume, result.0 as u32))
     }
+
+    /// Returns the total volume and transfer count sent by the given address.
+    pub async fn get_total_outgoing_transfer_volume(
+        &self,
+        address: &str,
+    ) -> anyhow::Result<(u128, u64)> {
+        let result: (String, i64) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(volume::NUMERIC), 0)::TEXT, COALESCE(SUM(count), 0)::BIGINT
+            FROM ftd_transfer_volume
+            WHERE from_address = $1
+            "#,
+        )
+        .bind(address)
+        .fetch_one(&self.connection_pool)
+        .await?;
+        Ok((result.0.parse()?, result.1 as u64))
+    }
+
+    /// Returns the total volume and transfer count received by the given address.
+    pub async fn get_total_incoming_transfer_volume(
+        &self,
+        address: &str,
+    ) -> anyhow::Result<(u128, u64)> {
+        let result: (String, i64) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(volume::NUMERIC), 0)::TEXT, COALESCE(SUM(count), 0)::BIGINT
+            FROM ftd_transfer_volume
+            WHERE to_address = $1
+            "#,
+        )
+        .bind(address)
+        .fetch_one(&self.connection_pool)
+        .await?;
+        Ok((result.0.parse()?, result.1 as u64))
+    }
+
+    pub async fn get_counterparty_count(&self, address: &str) -> anyhow::Result<u64> {
+        let result: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM (
+                SELECT to_address FROM ftd_transfer_volume WHERE from_address = $1
+                UNION
+                SELECT from_address FROM ftd_transfer_volume WHERE to_address = $1
+            ) AS counterparty
+            "#,
+        )
+        .bind(address)
+        .fetch_one(&self.connection_pool)
+        .await?;
+        Ok(result.0 as u64)
+    }
 }
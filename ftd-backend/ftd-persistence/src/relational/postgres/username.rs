@@ -0,0 +1,124 @@
+use super::PostgreSQLStorage;
+use ftd_types::substrate::account_id::AccountId;
+use ftd_types::substrate::identity::{Username, UsernameAuthority, UsernameProvider};
+use sqlx::{Postgres, Transaction};
+use std::str::FromStr;
+
+type UsernameRow = (String, String, String, Option<String>, bool);
+
+fn row_into_username(row: UsernameRow) -> anyhow::Result<Username> {
+    let deposit = row.3.map(|deposit| deposit.parse()).transpose()?;
+    Ok(Username {
+        username: row.0,
+        account_id: AccountId::from_str(&row.1)?,
+        provider: UsernameProvider::from_name(&row.2, deposit)?,
+        is_primary: row.4,
+    })
+}
+
+impl PostgreSQLStorage {
+    /// All stored usernames, to be diffed against the chain state.
+    pub async fn get_all_usernames(&self) -> anyhow::Result<Vec<Username>> {
+        let rows: Vec<UsernameRow> = sqlx::query_as(
+            r#"
+            SELECT username, address, provider, deposit, is_primary
+            FROM ftd_username
+            "#,
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+        rows.into_iter().map(row_into_username).collect()
+    }
+
+    pub async fn save_username(
+        &self,
+        username: &Username,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> anyhow::Result<()> {
+        let address = username.account_id.to_ss58_check();
+        self.save_account(address.as_str(), tx).await?;
+        sqlx::query(
+            r#"
+            INSERT INTO ftd_username (username, address, suffix, provider, deposit, is_primary)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (username) DO UPDATE
+            SET
+                address = EXCLUDED.address,
+                suffix = EXCLUDED.suffix,
+                provider = EXCLUDED.provider,
+                deposit = EXCLUDED.deposit,
+                is_primary = EXCLUDED.is_primary,
+                updated_at = now()
+            "#,
+        )
+        .bind(&username.username)
+        .bind(&address)
+        .bind(username.suffix())
+        .bind(username.provider.name())
+        .bind(
+            username
+                .provider
+                .deposit()
+                .map(|deposit| deposit.to_string()),
+        )
+        .bind(username.is_primary)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete_usernames(
+        &self,
+        usernames: &[String],
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM ftd_username WHERE username = ANY($1)")
+            .bind(usernames)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    /// Replaces the stored username authorities.
+    pub async fn save_username_authorities(
+        &self,
+        authorities: &[UsernameAuthority],
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM ftd_username_authority")
+            .execute(&mut **tx)
+            .await?;
+        for authority in authorities {
+            sqlx::query(
+                r#"
+                INSERT INTO ftd_username_authority (suffix, address, allocation)
+                VALUES ($1, $2, $3)
+                "#,
+            )
+            .bind(&authority.suffix)
+            .bind(authority.account_id.to_ss58_check())
+            .bind(authority.allocation as i32)
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Addresses and primary usernames of the accounts that have one.
+    pub async fn get_primary_usernames_by_addresses(
+        &self,
+        addresses: &[String],
+    ) -> anyhow::Result<Vec<(String, String)>> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT address, username
+            FROM ftd_username
+            WHERE address = ANY($1) AND is_primary
+            "#,
+        )
+        .bind(addresses)
+        .fetch_all(&self.connection_pool)
+        .await?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+use ftd_config::Config;
+use ftd_persistence::relational::RelationalStorage;
+use ftd_service::Service;
+use ftd_substrate_client::SubstrateClient;
+use ftd_types::api::relation::AccountRelationType;
+use ftd_types::diff::Diff;
+use ftd_types::substrate::relation::AccountRelation;
+use lazy_static::lazy_static;
+
+mod metrics;
+
+lazy_static! {
+    static ref CONFIG: Config = Config::default();
+}
+
+/// Relations read from the chain storage. Multisig members are derived from the multisig calls
+/// by the indexer.
+const STORAGE_RELATION_TYPES: [AccountRelationType; 3] = [
+    AccountRelationType::Proxy,
+    AccountRelationType::StakingController,
+    AccountRelationType::StakingPayee,
+];
+
+fn record_diff(diff: &Diff<AccountRelation>) {
+    log::info!(
+        "Relations: {} inserted, {} changed, {} removed.",
+        diff.inserted.len(),
+        diff.changed.len(),
+        diff.removed.len(),
+    );
+    for relation in diff.inserted.iter() {
+        metrics::relation_change_count(&relation.relation_type.to_string(), "inserted").inc();
+    }
+    for relation in diff.changed.iter() {
+        metrics::relation_change_count(&relation.relation_type.to_string(), "changed").inc();
+    }
+    for key in diff.removed.iter() {
+        metrics::relation_change_count(&key.2.to_string(), "removed").inc();
+    }
+}
+
+/// Syncs the proxy, staking controller and staking payee relations with the chain state.
+#[derive(Default)]
+pub struct RelationUpdater;
+
+impl RelationUpdater {
+    async fn update_relations(
+        &self,
+        substrate_client: &SubstrateClient,
+        relational_storage: &RelationalStorage,
+        block_hash: &str,
+        block_number: u64,
+    ) -> anyhow::Result<()> {
+        log::info!("Get relations @ finalized block {block_number}.");
+        let mut relations = substrate_client.get_proxies(block_hash).await?;
+        log::info!("Got {} proxy relations.", relations.len());
+        let mut staking_relations = substrate_client.get_staking_relations(block_hash).await?;
+        log::info!("Got {} staking relations.", staking_relations.len());
+        relations.append(&mut staking_relations);
+        metrics::last_relation_list_fetch_timestamp_ms().set(chrono::Utc::now().timestamp_millis());
+        let relation_diff = Diff::new(
+            relational_storage
+                .get_account_relations_by_types(&STORAGE_RELATION_TYPES)
+                .await?,
+            relations,
+        );
+        if !relation_diff.is_empty() {
+            relational_storage
+                .update_account_relations(
+                    &relation_diff.saved(),
+                    &relation_diff.removed,
+                    block_number,
+                )
+                .await?;
+        }
+        record_diff(&relation_diff);
+        Ok(())
+    }
+}
+
+#[async_trait(? Send)]
+impl Service for RelationUpdater {
+    fn get_metrics_server_addr() -> (&'static str, u16) {
+        (
+            CONFIG.metrics.host.as_str(),
+            CONFIG.metrics.relation_updater_port,
+        )
+    }
+
+    async fn run(&'static self) -> anyhow::Result<()> {
+        log::info!("Relation updater started.");
+        let relational_storage = RelationalStorage::new().await?;
+        let substrate_client = SubstrateClient::new(
+            &CONFIG.substrate.rpc_url,
+            CONFIG.substrate.connection_timeout_seconds,
+            CONFIG.substrate.request_timeout_seconds,
+        )
+        .await?;
+        let sleep_seconds = CONFIG.relation_updater.sleep_seconds;
+        loop {
+            log::info!("Update relations started.");
+            metrics::last_run_timestamp_ms().set(chrono::Utc::now().timestamp_millis());
+            let block_hash = substrate_client
+                .get_finalized_block_hash()
+                .await?
+                .trim_start_matches("0x")
+                .to_string();
+            let block_head = substrate_client.get_block_header(&block_hash).await?;
+            let block_number = block_head.get_number()?;
+            match self
+                .update_relations(
+                    &substrate_client,
+                    &relational_storage,
+                    block_hash.as_str(),
+                    block_number,
+                )
+                .await
+            {
+                Ok(()) => {
+                    metrics::last_success_status().set(1);
+                    relational_storage
+                        .set_relation_updater_state(block_hash.as_str(), block_number, true, None)
+                        .await?;
+                }
+                Err(error) => {
+                    metrics::last_success_status().set(0);
+                    let error_log = format!("{error:?}");
+                    relational_storage
+                        .set_relation_updater_state(
+                            block_hash.as_str(),
+                            block_number,
+                            false,
+                            Some(error_log.as_str()),
+                        )
+                        .await?;
+                }
+            }
+            log::info!("Sleep for {sleep_seconds} seconds.");
+            tokio::time::sleep(std::time::Duration::from_secs(sleep_seconds)).await;
+        }
+    }
+}
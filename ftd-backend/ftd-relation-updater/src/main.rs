@@ -0,0 +1,12 @@
+use ftd_relation_updater::RelationUpdater;
+use ftd_service::Service;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref SERVICE: RelationUpdater = RelationUpdater;
+}
+
+#[tokio::main]
+async fn main() {
+    SERVICE.start().await;
+}
@@ -0,0 +1,55 @@
+use ftd_metrics::registry::{IntCounter, IntCounterVec, IntGauge};
+use once_cell::sync::Lazy;
+
+const METRIC_PREFIX: &str = "ftd_relation_updater";
+
+pub fn last_run_timestamp_ms() -> IntGauge {
+    static METER: Lazy<IntGauge> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_gauge(
+            METRIC_PREFIX,
+            "last_run_timestamp_ms",
+            "Timestamp (ms) for the last run",
+        )
+        .unwrap()
+    });
+    METER.clone()
+}
+
+pub fn last_relation_list_fetch_timestamp_ms() -> IntGauge {
+    static METER: Lazy<IntGauge> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_gauge(
+            METRIC_PREFIX,
+            "last_relation_list_fetch_timestamp_ms",
+            "Timestamp (ms) for the last proxy and staking relation list fetch operation",
+        )
+        .unwrap()
+    });
+    METER.clone()
+}
+
+/// `relation_type` is `proxy`, `staking_controller` or `staking_payee`, `change` is `inserted`,
+/// `changed` or `removed`.
+pub fn relation_change_count(relation_type: &str, change: &str) -> IntCounter {
+    static METER: Lazy<IntCounterVec> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_counter_vec(
+            METRIC_PREFIX,
+            "relation_change_count",
+            "The total number of proxy and staking relation changes applied to the database",
+            &["relation_type", "change"],
+        )
+        .unwrap()
+    });
+    METER.with_label_values(&[relation_type, change])
+}
+
+pub fn last_success_status() -> IntGauge {
+    static METER: Lazy<IntGauge> = Lazy::new(|| {
+        ftd_metrics::registry::register_int_gauge(
+            METRIC_PREFIX,
+            "last_success_status",
+            "Boolean value for the success status of the last process",
+        )
+        .unwrap()
+    });
+    METER.clone()
+}
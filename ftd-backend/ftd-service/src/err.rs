@@ -1,23 +1,123 @@
-use ftd_types::err::ServiceError;
-use std::fmt::{Display, Formatter};
+use actix_web::http::{header, StatusCode};
+use actix_web::HttpResponse;
+use ftd_types::err::{ServiceError, ServiceErrorCode, SubscanError};
+use jsonrpsee_core::ClientError;
 
-#[derive(Debug)]
-pub struct InternalServerError {
-    err: anyhow::Error,
+/// API error with a stable code and the matching HTTP status. `anyhow` errors are logged and
+/// classified by their source, and their details are hidden from the client.
+#[derive(thiserror::Error, Debug)]
+pub enum APIError {
+    #[error("{0}")]
+    InvalidParameter(String),
+    #[error("{0}")]
+    InvalidAddress(String),
+    #[error("{0}")]
+    Unauthorized(String),
+    #[error("{0}")]
+    Forbidden(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("Rate limit exceeded.")]
+    RateLimited { retry_after_seconds: u64 },
+    #[error("Substrate node unavailable.")]
+    UpstreamNodeUnavailable,
+    #[error("Subscan API quota exceeded.")]
+    SubscanQuotaExceeded,
+    #[error("Database timeout.")]
+    DatabaseTimeout,
+    #[error("Internal server error.")]
+    Internal,
 }
 
-impl Display for InternalServerError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        log::error!("{:?}", self.err);
-        let err = ServiceError::from("Internal server error.");
-        write!(f, "{}", serde_json::to_string(&err).unwrap())
+impl APIError {
+    pub fn code(&self) -> ServiceErrorCode {
+        match self {
+            Self::InvalidParameter(_) => ServiceErrorCode::InvalidParameter,
+            Self::InvalidAddress(_) => ServiceErrorCode::InvalidAddress,
+            Self::Unauthorized(_) => ServiceErrorCode::Unauthorized,
+            Self::Forbidden(_) => ServiceErrorCode::Forbidden,
+            Self::NotFound(_) => ServiceErrorCode::NotFound,
+            Self::RateLimited { .. } => ServiceErrorCode::RateLimited,
+            Self::UpstreamNodeUnavailable => ServiceErrorCode::UpstreamNodeUnavailable,
+            Self::SubscanQuotaExceeded => ServiceErrorCode::SubscanQuotaExceeded,
+            Self::DatabaseTimeout => ServiceErrorCode::DatabaseTimeout,
+            Self::Internal => ServiceErrorCode::InternalError,
+        }
+    }
+
+    pub fn to_service_error(&self) -> ServiceError {
+        ServiceError::new(self.code(), self.to_string().as_str())
+    }
+}
+
+impl actix_web::error::ResponseError for APIError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::InvalidParameter(_) | Self::InvalidAddress(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Self::UpstreamNodeUnavailable | Self::SubscanQuotaExceeded => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            Self::DatabaseTimeout => StatusCode::GATEWAY_TIMEOUT,
+            Self::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut response = HttpResponse::build(self.status_code());
+        if let Self::RateLimited {
+            retry_after_seconds,
+        } = self
+        {
+            response.insert_header((header::RETRY_AFTER, retry_after_seconds.to_string()));
+        }
+        response.json(self.to_service_error())
     }
 }
 
-impl actix_web::error::ResponseError for InternalServerError {}
+fn is_node_unavailable(error: &ClientError) -> bool {
+    matches!(
+        error,
+        ClientError::Transport(_) | ClientError::RestartNeeded(_) | ClientError::RequestTimeout
+    )
+}
+
+fn is_database_timeout(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::PoolTimedOut => true,
+        sqlx::Error::Io(io_error) => io_error.kind() == std::io::ErrorKind::TimedOut,
+        // query_canceled, raised by statement_timeout
+        sqlx::Error::Database(database_error) => database_error.code().as_deref() == Some("57014"),
+        _ => false,
+    }
+}
+
+impl From<&anyhow::Error> for APIError {
+    fn from(error: &anyhow::Error) -> APIError {
+        log::error!("{error:?}");
+        for source in error.chain() {
+            if let Some(client_error) = source.downcast_ref::<ClientError>() {
+                if is_node_unavailable(client_error) {
+                    return APIError::UpstreamNodeUnavailable;
+                }
+            } else if let Some(SubscanError::QuotaExceeded) = source.downcast_ref::<SubscanError>()
+            {
+                return APIError::SubscanQuotaExceeded;
+            } else if let Some(sqlx_error) = source.downcast_ref::<sqlx::Error>() {
+                if is_database_timeout(sqlx_error) {
+                    return APIError::DatabaseTimeout;
+                }
+            }
+        }
+        APIError::Internal
+    }
+}
 
-impl From<anyhow::Error> for InternalServerError {
-    fn from(err: anyhow::Error) -> InternalServerError {
-        InternalServerError { err }
+impl From<anyhow::Error> for APIError {
+    fn from(error: anyhow::Error) -> APIError {
+        APIError::from(&error)
     }
 }
@@ -1,8 +1,11 @@
 use crate::SidecarClient;
-use ftd_types::err::{BlockDataError, TransferEventDataError};
+use ftd_types::err::{BlockDataError, MultisigCallDataError, TransferEventDataError};
+use ftd_types::substrate::account_id::AccountId;
 use ftd_types::substrate::block::Block;
 use ftd_types::substrate::event::TransferEvent;
+use ftd_types::substrate::relation::MultisigCall;
 use serde_json::Value;
+use std::str::FromStr;
 
 fn get_number(json: &Value) -> anyhow::Result<u64> {
     Ok(json["number"]
@@ -86,6 +89,109 @@ fn get_transfer_events(json: &Value) -> anyhow::Result<Vec<TransferEvent>> {
     Ok(transfers)
 }
 
+/// Account id of a `MultiAddress` or an SS58 address.
+fn get_account_id(json: &Value) -> Option<AccountId> {
+    json["id"]
+        .as_str()
+        .or(json.as_str())
+        .and_then(|address| AccountId::from_str(address).ok())
+}
+
+/// Multisig account of a `Multisig` call, from its other signatories and threshold.
+fn get_multisig_call(
+    extrinsic_index: u16,
+    call: &str,
+    args: &Value,
+    origin: AccountId,
+) -> Result<MultisigCall, MultisigCallDataError> {
+    let other_signatories = args["otherSignatories"]
+        .as_array()
+        .ok_or(MultisigCallDataError::SignatoriesNotFound)?
+        .iter()
+        .map(|signatory| get_account_id(signatory).ok_or(MultisigCallDataError::InvalidSignatory))
+        .collect::<Result<Vec<AccountId>, MultisigCallDataError>>()?;
+    let threshold = if call == "asmultithreshold1" {
+        1
+    } else {
+        args["threshold"]
+            .as_str()
+            .and_then(|threshold| threshold.parse::<u16>().ok())
+            .or(args["threshold"].as_u64().map(|threshold| threshold as u16))
+            .ok_or(MultisigCallDataError::ThresholdNotFound)?
+    };
+    Ok(MultisigCall::new(
+        extrinsic_index,
+        origin,
+        &other_signatories,
+        threshold,
+    ))
+}
+
+/// Collects the `Multisig` calls in the call, or in the batch and proxy calls wrapping them,
+/// with the account that dispatched them. Calls with unexpected arguments are logged and
+/// skipped.
+fn collect_multisig_calls(
+    extrinsic_index: u16,
+    call_json: &Value,
+    origin: AccountId,
+    multisig_calls: &mut Vec<MultisigCall>,
+) {
+    let module = call_json["method"]["pallet"]
+        .as_str()
+        .unwrap_or_default()
+        .to_lowercase();
+    let call = call_json["method"]["method"]
+        .as_str()
+        .unwrap_or_default()
+        .to_lowercase();
+    let args = &call_json["args"];
+    match (module.as_str(), call.as_str()) {
+        ("multisig", "asmulti" | "approveasmulti" | "cancelasmulti" | "asmultithreshold1") => {
+            log::debug!("Found {module}.{call}.");
+            match get_multisig_call(extrinsic_index, call.as_str(), args, origin) {
+                Ok(multisig_call) => multisig_calls.push(multisig_call),
+                Err(error) => {
+                    log::warn!("Skip {module}.{call} in extrinsic {extrinsic_index}: {error:?}")
+                }
+            }
+        }
+        ("utility", "batch" | "batchall" | "forcebatch") => {
+            for batch_call_json in args["calls"].as_array().into_iter().flatten() {
+                collect_multisig_calls(extrinsic_index, batch_call_json, origin, multisig_calls);
+            }
+        }
+        ("proxy", "proxy") => {
+            if let Some(real) = get_account_id(&args["real"]) {
+                collect_multisig_calls(extrinsic_index, &args["call"], real, multisig_calls);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Multisig calls of the successful signed extrinsics, which reveal the signatories and the
+/// thresholds of the multisig accounts.
+fn get_multisig_calls(json: &Value) -> anyhow::Result<Vec<MultisigCall>> {
+    let mut multisig_calls = Vec::new();
+    let extrinsics = json["extrinsics"]
+        .as_array()
+        .ok_or(BlockDataError::ExtrinsicsNotFound)?;
+    for (extrinsic_index, extrinsic) in extrinsics.iter().enumerate() {
+        if !extrinsic["success"].as_bool().unwrap_or(false) {
+            continue;
+        }
+        if let Some(signer) = get_account_id(&extrinsic["signature"]["signer"]) {
+            collect_multisig_calls(
+                extrinsic_index as u16,
+                extrinsic,
+                signer,
+                &mut multisig_calls,
+            );
+        }
+    }
+    Ok(multisig_calls)
+}
+
 impl SidecarClient {
     async fn get_block_timestamp(&self, hash: &str) -> anyhow::Result<u64> {
         let url = format!(
@@ -118,6 +224,7 @@ impl SidecarClient {
         let author_address = get_author_address(json);
         let timestamp = self.get_block_timestamp(&hash).await?;
         let transfers = get_transfer_events(json)?;
+        let multisig_calls = get_multisig_calls(json)?;
         Ok(Block {
             timestamp,
             number,
@@ -125,6 +232,7 @@ impl SidecarClient {
             parent_hash,
             author_address,
             transfers,
+            multisig_calls,
         })
     }
 
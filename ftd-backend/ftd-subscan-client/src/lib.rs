@@ -1,8 +1,9 @@
 use ftd_config::Config;
+use ftd_types::err::SubscanError;
 use ftd_types::subscan::{
     SubscanAccountListBody, SubscanAccountListResult, SubscanAccountSearchResult,
 };
-use reqwest::Client;
+use reqwest::{Client, Response, StatusCode};
 use rustc_hash::FxHashMap as HashMap;
 
 fn get_http_client(config: &Config) -> anyhow::Result<Client> {
@@ -15,6 +16,16 @@ fn get_http_client(config: &Config) -> anyhow::Result<Client> {
         .build()?)
 }
 
+/// Maps the error statuses to `SubscanError`, so that callers can tell an exhausted quota
+/// apart from other failures.
+fn check_status(response: Response) -> anyhow::Result<Response> {
+    match response.status() {
+        StatusCode::TOO_MANY_REQUESTS => Err(SubscanError::QuotaExceeded.into()),
+        status if !status.is_success() => Err(SubscanError::Status(status.as_u16()).into()),
+        _ => Ok(response),
+    }
+}
+
 pub struct SubscanClient {
     config: Config,
     http_client: Client,
@@ -34,13 +45,14 @@ impl SubscanClient {
     ) -> anyhow::Result<SubscanAccountSearchResult> {
         let mut map = HashMap::default();
         map.insert("key", account_address);
-        Ok(self
+        let response = self
             .http_client
             .post(self.config.subscan.account_data_url.as_str())
             .header("x-api-key", self.config.subscan.api_key.as_str())
             .json(&map)
             .send()
-            .await?
+            .await?;
+        Ok(check_status(response)?
             .json::<SubscanAccountSearchResult>()
             .await?)
     }
@@ -56,13 +68,14 @@ impl SubscanClient {
             page: page_index,
             row: page_size,
         };
-        Ok(self
+        let response = self
             .http_client
             .post(self.config.subscan.account_list_url.as_str())
             .header("x-api-key", self.config.subscan.api_key.as_str())
             .json(&body)
             .send()
-            .await?
+            .await?;
+        Ok(check_status(response)?
             .json::<SubscanAccountListResult>()
             .await?)
     }
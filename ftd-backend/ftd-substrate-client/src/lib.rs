@@ -1,29 +1,45 @@
-use crate::storage_utility::{
-    account_id_from_storage_key, decode_hex_string, get_rpc_paged_keys_params,
-    get_rpc_storage_plain_params, get_storage_plain_key, hash,
-};
-use frame_metadata::v14::StorageHasher;
+use crate::metadata::{RuntimeVersion, StorageEntryLayout, StorageMetadata};
+use crate::storage_utility::get_rpc_storage_params;
 use ftd_types::substrate::account_id::AccountId;
 use ftd_types::substrate::balance::Balance;
 use ftd_types::substrate::block::BlockHeader;
 use ftd_types::substrate::chain::Chain;
-use ftd_types::substrate::identity::{Identity, SubIdentity};
+use ftd_types::substrate::identity::{
+    username_from_bytes, Identity, Registrar, SubIdentity, Username, UsernameAuthority,
+};
+use ftd_types::substrate::relation::AccountRelation;
 use ftd_types::substrate::{AccountData, AccountInfo};
 use jsonrpsee::ws_client::WsClientBuilder;
-use jsonrpsee_core::client::{Client, ClientT};
+use jsonrpsee_core::client::{Client, ClientT, Subscription, SubscriptionClientT};
+use jsonrpsee_core::params::ArrayParams;
 use jsonrpsee_core::rpc_params;
-use parity_scale_codec::{Decode, Encode};
-use rustc_hash::FxHashMap as HashMap;
-use sp_core::storage::StorageChangeSet;
+use parity_scale_codec::Decode;
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use serde::de::DeserializeOwned;
+use sp_core::storage::{StorageChangeSet, StorageKey};
 use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use storage_iterator::StorageIterator;
 
+pub mod metadata;
+pub mod storage_iterator;
 mod storage_utility;
 
+/// Number of keys per `state_getKeysPaged` page and per `state_queryStorageAt` request.
 const KEY_QUERY_PAGE_SIZE: usize = 1000;
+/// Attempts of a storage page request before the error is returned.
+const STORAGE_REQUEST_MAX_ATTEMPTS: u32 = 3;
+/// Delay before the first retry of a storage page request, doubled for each further retry.
+const STORAGE_REQUEST_RETRY_DELAY_MS: u64 = 500;
 
 pub struct SubstrateClient {
     pub chain: Chain,
     ws_client: Client,
+    /// Storage layouts by runtime spec version.
+    storage_metadata: RwLock<HashMap<u32, Arc<StorageMetadata>>>,
+    /// Runtime spec version of the best block, kept up to date by the runtime version
+    /// subscription. `None` when the subscription is not active.
+    best_spec_version: Arc<RwLock<Option<u32>>>,
 }
 
 impl SubstrateClient {
@@ -41,45 +57,263 @@ impl SubstrateClient {
         let chain: String = ws_client.request("system_chain", rpc_params!()).await?;
         let chain = Chain::from_str(chain.as_str())?;
         log::info!("{chain} Substrate connection successful.");
-        Ok(SubstrateClient { chain, ws_client })
+        let best_spec_version = Arc::new(RwLock::new(None));
+        match ws_client
+            .subscribe(
+                "state_subscribeRuntimeVersion",
+                rpc_params!(),
+                "state_unsubscribeRuntimeVersion",
+            )
+            .await
+        {
+            Ok(subscription) => {
+                tokio::spawn(Self::follow_runtime_version(
+                    subscription,
+                    best_spec_version.clone(),
+                ));
+            }
+            Err(error) => {
+                log::warn!("Cannot subscribe to the runtime version: {error:?}");
+            }
+        }
+        Ok(SubstrateClient {
+            chain,
+            ws_client,
+            storage_metadata: RwLock::new(HashMap::default()),
+            best_spec_version,
+        })
+    }
+
+    /// Updates the best block spec version on every runtime upgrade, until the subscription
+    /// ends.
+    async fn follow_runtime_version(
+        mut subscription: Subscription<RuntimeVersion>,
+        best_spec_version: Arc<RwLock<Option<u32>>>,
+    ) {
+        while let Some(result) = subscription.next().await {
+            match result {
+                Ok(runtime_version) => {
+                    log::info!(
+                        "Best block runtime spec version is {}.",
+                        runtime_version.spec_version
+                    );
+                    *best_spec_version.write().unwrap() = Some(runtime_version.spec_version);
+                }
+                Err(error) => {
+                    log::warn!("Invalid runtime version notification: {error:?}");
+                    break;
+                }
+            }
+        }
+        *best_spec_version.write().unwrap() = None;
     }
 
-    async fn get_all_keys_for_storage(
+    pub fn is_connected(&self) -> bool {
+        self.ws_client.is_connected()
+    }
+
+    /// Sends the request, retrying it with exponential backoff if it fails.
+    async fn request_with_retry<R: DeserializeOwned>(
         &self,
-        module_name: &str,
-        storage_name: &str,
-        block_hash: &str,
-    ) -> anyhow::Result<Vec<String>> {
-        let mut all_keys: Vec<String> = Vec::new();
-        let mut total = 0;
+        method: &str,
+        params: ArrayParams,
+    ) -> anyhow::Result<R> {
+        let mut attempt = 1;
         loop {
-            let last = all_keys.last();
-            let mut keys: Vec<String> = self
-                .ws_client
-                .request(
-                    "state_getKeysPaged",
-                    get_rpc_paged_keys_params(
-                        module_name,
-                        storage_name,
-                        KEY_QUERY_PAGE_SIZE,
-                        if let Some(last) = last {
-                            Some(last.as_str())
-                        } else {
-                            None
-                        },
-                        Some(block_hash),
-                    ),
-                )
+            match self.ws_client.request(method, params.clone()).await {
+                Ok(response) => return Ok(response),
+                Err(error) if attempt < STORAGE_REQUEST_MAX_ATTEMPTS => {
+                    let delay_ms = STORAGE_REQUEST_RETRY_DELAY_MS << (attempt - 1);
+                    log::warn!(
+                        "{method} attempt {attempt} failed: {error:?}. Retry in {delay_ms} ms."
+                    );
+                    tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+                    attempt += 1;
+                }
+                Err(error) => return Err(error.into()),
+            }
+        }
+    }
+
+    /// Values of the given storage keys at the block, or at the best block, queried in chunks
+    /// of `KEY_QUERY_PAGE_SIZE`. Keys without values are skipped.
+    async fn query_storage_at(
+        &self,
+        keys: &[String],
+        maybe_block_hash: Option<&str>,
+    ) -> anyhow::Result<Vec<(StorageKey, Vec<u8>)>> {
+        let mut entries = Vec::new();
+        for chunk in keys.chunks(KEY_QUERY_PAGE_SIZE) {
+            let rpc_params = if let Some(block_hash) = maybe_block_hash {
+                rpc_params!(chunk, block_hash)
+            } else {
+                rpc_params!(chunk)
+            };
+            let change_sets: Vec<StorageChangeSet<String>> = self
+                .request_with_retry("state_queryStorageAt", rpc_params)
                 .await?;
-            let keys_length = keys.len();
-            all_keys.append(&mut keys);
-            total += keys_length;
-            println!("{total}");
-            if keys_length < KEY_QUERY_PAGE_SIZE {
-                break;
+            for change_set in change_sets {
+                for (storage_key, maybe_data) in change_set.changes {
+                    if let Some(data) = maybe_data {
+                        entries.push((storage_key, data.0));
+                    }
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Storage layouts of the runtime at the given block, or at the best block. The runtime
+    /// metadata is fetched once per runtime spec version. The spec version of the best block
+    /// comes from the runtime version subscription when it is active, other blocks cost a
+    /// runtime version request.
+    async fn get_storage_metadata(
+        &self,
+        maybe_block_hash: Option<&str>,
+    ) -> anyhow::Result<Arc<StorageMetadata>> {
+        let rpc_params = if let Some(block_hash) = maybe_block_hash {
+            rpc_params!(block_hash)
+        } else {
+            rpc_params!()
+        };
+        let maybe_best_spec_version = *self.best_spec_version.read().unwrap();
+        let spec_version = match (maybe_block_hash, maybe_best_spec_version) {
+            (None, Some(best_spec_version)) => best_spec_version,
+            _ => {
+                let runtime_version: RuntimeVersion = self
+                    .ws_client
+                    .request("state_getRuntimeVersion", rpc_params.clone())
+                    .await?;
+                runtime_version.spec_version
             }
+        };
+        if let Some(metadata) = self.storage_metadata.read().unwrap().get(&spec_version) {
+            return Ok(metadata.clone());
+        }
+        log::info!("Get runtime metadata of spec version {spec_version}.");
+        let hex_string: String = self
+            .ws_client
+            .request("state_getMetadata", rpc_params)
+            .await?;
+        let metadata = Arc::new(StorageMetadata::from_bytes(&hex::decode(
+            hex_string.trim_start_matches("0x"),
+        )?)?);
+        self.storage_metadata
+            .write()
+            .unwrap()
+            .insert(spec_version, metadata.clone());
+        Ok(metadata)
+    }
+
+    /// Layout of the storage in the runtime at the given block, or at the best block. Fails if
+    /// the runtime has no such storage.
+    pub async fn get_storage_layout(
+        &self,
+        pallet_name: &str,
+        storage_name: &str,
+        maybe_block_hash: Option<&str>,
+    ) -> anyhow::Result<StorageEntryLayout> {
+        self.get_storage_metadata(maybe_block_hash)
+            .await?
+            .get_entry(pallet_name, storage_name)
+    }
+
+    /// Iterator over the entries of the map storage at the given block.
+    pub async fn iter_storage(
+        &self,
+        pallet_name: &str,
+        storage_name: &str,
+        block_hash: &str,
+    ) -> anyhow::Result<StorageIterator<'_>> {
+        let layout = self
+            .get_storage_layout(pallet_name, storage_name, Some(block_hash))
+            .await?;
+        Ok(StorageIterator::new(self, layout, block_hash))
+    }
+
+    /// All entries of the map storage at the given block, decoded by `decode`.
+    async fn get_storage_values<T>(
+        &self,
+        pallet_name: &str,
+        storage_name: &str,
+        block_hash: &str,
+        decode: impl Fn(&StorageEntryLayout, &StorageKey, &[u8]) -> anyhow::Result<T>,
+    ) -> anyhow::Result<Vec<T>> {
+        let mut iterator = self
+            .iter_storage(pallet_name, storage_name, block_hash)
+            .await?;
+        let mut values = Vec::new();
+        while let Some(mut page) = iterator.next_decoded_page(&decode).await? {
+            values.append(&mut page);
+        }
+        log::info!(
+            "Got {} {pallet_name}.{storage_name} entries.",
+            iterator.entry_count()
+        );
+        Ok(values)
+    }
+
+    /// All entries of the single-key map storage at the given block, with their keys decoded
+    /// as `K`. Fails if the storage is not a single-key map or a key is not a `K`.
+    pub async fn get_map_values<K: Decode, T>(
+        &self,
+        pallet_name: &str,
+        storage_name: &str,
+        block_hash: &str,
+        decode: impl Fn(K, &[u8]) -> anyhow::Result<T>,
+    ) -> anyhow::Result<Vec<T>> {
+        self.get_storage_values(
+            pallet_name,
+            storage_name,
+            block_hash,
+            |layout, storage_key, bytes| decode(layout.decode_map_key(storage_key)?, bytes),
+        )
+        .await
+    }
+
+    /// All entries of the double-map storage at the given block, with their keys decoded as
+    /// `K1` and `K2`. Fails if the storage is not a double map or a key is not a `K1` and `K2`.
+    pub async fn get_double_map_values<K1: Decode, K2: Decode, T>(
+        &self,
+        pallet_name: &str,
+        storage_name: &str,
+        block_hash: &str,
+        decode: impl Fn(K1, K2, &[u8]) -> anyhow::Result<T>,
+    ) -> anyhow::Result<Vec<T>> {
+        self.get_storage_values(
+            pallet_name,
+            storage_name,
+            block_hash,
+            |layout, storage_key, bytes| {
+                let (key_1, key_2) = layout.decode_double_map_key(storage_key)?;
+                decode(key_1, key_2, bytes)
+            },
+        )
+        .await
+    }
+
+    /// Value of the plain storage at the given block, `None` if the storage is empty.
+    async fn get_plain_value_bytes(
+        &self,
+        pallet_name: &str,
+        storage_name: &str,
+        block_hash: &str,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let key = self
+            .get_storage_layout(pallet_name, storage_name, Some(block_hash))
+            .await?
+            .plain_key_hex()?;
+        let maybe_hex_string: Option<String> = self
+            .ws_client
+            .request(
+                "state_getStorage",
+                get_rpc_storage_params(key.as_str(), Some(block_hash)),
+            )
+            .await?;
+        match maybe_hex_string {
+            Some(hex_string) => Ok(Some(hex::decode(hex_string.trim_start_matches("0x"))?)),
+            None => Ok(None),
         }
-        Ok(all_keys)
     }
 
     pub async fn get_current_block_hash(&self) -> anyhow::Result<String> {
@@ -113,14 +347,13 @@ impl SubstrateClient {
     }
 
     pub async fn get_block_timestamp(&self, block_hash: &str) -> anyhow::Result<u64> {
-        let hex_string: String = self
-            .ws_client
-            .request(
-                "state_getStorage",
-                get_rpc_storage_plain_params("Timestamp", "Now", Some(block_hash)),
-            )
-            .await?;
-        decode_hex_string(hex_string.as_str())
+        match self
+            .get_plain_value_bytes("Timestamp", "Now", block_hash)
+            .await?
+        {
+            Some(bytes) => Ok(Decode::decode(&mut bytes.as_slice())?),
+            None => anyhow::bail!("No timestamp @ block {block_hash}."),
+        }
     }
 
     pub async fn get_block_header(&self, block_hash: &str) -> anyhow::Result<BlockHeader> {
@@ -146,86 +379,144 @@ impl SubstrateClient {
         Ok(header)
     }
 
+    async fn get_identities_with(
+        &self,
+        at: &str,
+        decode: fn(AccountId, &[u8]) -> anyhow::Result<Identity>,
+    ) -> anyhow::Result<Vec<Identity>> {
+        self.get_map_values("Identity", "IdentityOf", at, decode)
+            .await
+    }
+
     pub async fn get_identities(&self, at: &str) -> anyhow::Result<Vec<Identity>> {
-        let keys = self
-            .get_all_keys_for_storage("Identity", "IdentityOf", at)
-            .await?;
-        log::info!("Got {} identity keys.", keys.len());
-        let values: Vec<StorageChangeSet<String>> = self
-            .ws_client
-            .request("state_queryStorageAt", rpc_params!(keys, at))
-            .await?;
-        let mut identities = Vec::new();
-        for (storage_key, storage_data) in values[0].changes.iter() {
-            let account_id = account_id_from_storage_key(storage_key);
-            if let Some(data) = storage_data {
-                let bytes: &[u8] = &data.0;
-                let identity = Identity::from_bytes(account_id, bytes).unwrap();
-                identities.push(identity);
-            }
-        }
-        Ok(identities)
+        self.get_identities_with(at, Identity::from_bytes).await
+    }
+
+    /// Identities on the relay chain, before they moved to the People chain.
+    pub async fn get_legacy_identities(&self, at: &str) -> anyhow::Result<Vec<Identity>> {
+        self.get_identities_with(at, Identity::from_legacy_bytes)
+            .await
     }
 
     pub async fn get_sub_identities(&self, at: &str) -> anyhow::Result<Vec<SubIdentity>> {
-        let keys = self
-            .get_all_keys_for_storage("Identity", "SuperOf", at)
-            .await?;
-        log::info!("Got {} sub identity keys.", keys.len());
-        let mut sub_identities = Vec::new();
-        let values: Vec<StorageChangeSet<String>> = self
-            .ws_client
-            .request("state_queryStorageAt", rpc_params!(keys, at))
-            .await?;
-        for (storage_key, storage_data) in values[0].changes.iter() {
-            let account_id = account_id_from_storage_key(storage_key);
-            if let Some(data) = storage_data {
-                let bytes: &[u8] = &data.0;
-                sub_identities.push(SubIdentity::from_bytes(account_id, bytes)?)
-            }
+        self.get_map_values("Identity", "SuperOf", at, SubIdentity::from_bytes)
+            .await
+    }
+
+    pub async fn get_registrars(&self, at: &str) -> anyhow::Result<Vec<Registrar>> {
+        match self
+            .get_plain_value_bytes("Identity", "Registrars", at)
+            .await?
+        {
+            Some(bytes) => Registrar::list_from_bytes(&bytes),
+            None => Ok(Vec::new()),
         }
-        Ok(sub_identities)
     }
 
+    /// All usernames, with the primary usernames of the accounts marked.
+    pub async fn get_usernames(&self, at: &str) -> anyhow::Result<Vec<Username>> {
+        let primary_usernames: HashSet<String> = self
+            .get_map_values("Identity", "UsernameOf", at, |_: AccountId, bytes| {
+                username_from_bytes(bytes)
+            })
+            .await?
+            .into_iter()
+            .collect();
+        self.get_map_values(
+            "Identity",
+            "UsernameInfoOf",
+            at,
+            |username: Vec<u8>, bytes| {
+                let username = String::from_utf8(username)?;
+                let is_primary = primary_usernames.contains(&username);
+                Username::from_bytes(username, bytes, is_primary)
+            },
+        )
+        .await
+    }
+
+    pub async fn get_username_authorities(
+        &self,
+        at: &str,
+    ) -> anyhow::Result<Vec<UsernameAuthority>> {
+        self.get_map_values("Identity", "AuthorityOf", at, |suffix: Vec<u8>, bytes| {
+            UsernameAuthority::from_bytes(String::from_utf8(suffix)?, bytes)
+        })
+        .await
+    }
+
+    /// Proxy relations of all delegating accounts.
+    pub async fn get_proxies(&self, at: &str) -> anyhow::Result<Vec<AccountRelation>> {
+        let chain = &self.chain;
+        Ok(self
+            .get_map_values("Proxy", "Proxies", at, |account_id, bytes| {
+                AccountRelation::proxies_from_bytes(chain, account_id, bytes)
+            })
+            .await?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    /// Controller and reward payee relations of the stash accounts, other than the stash
+    /// accounts themselves.
+    pub async fn get_staking_relations(&self, at: &str) -> anyhow::Result<Vec<AccountRelation>> {
+        let mut relations = self
+            .get_map_values(
+                "Staking",
+                "Bonded",
+                at,
+                AccountRelation::staking_controller_from_bytes,
+            )
+            .await?;
+        relations.append(
+            &mut self
+                .get_map_values(
+                    "Staking",
+                    "Payee",
+                    at,
+                    AccountRelation::staking_payee_from_bytes,
+                )
+                .await?,
+        );
+        Ok(relations.into_iter().flatten().collect())
+    }
+
+    /// Balances of the accounts at the given block, or at the best block. The best block is
+    /// pinned when the query takes more than one chunk. The keys are built with the best block's
+    /// `System.Account` layout, which does not change between runtime versions, so no runtime
+    /// version is requested per call. The returned keys are still decoded with that layout.
     pub async fn get_balances(
         &self,
         account_ids: &[AccountId],
         maybe_block_hash: Option<&str>,
     ) -> anyhow::Result<HashMap<AccountId, Balance>> {
-        let storage_key_hex = get_storage_plain_key("System", "Account");
-        let hasher = StorageHasher::Blake2_128Concat;
-        let mut keys = Vec::new();
-        for account_id in account_ids {
-            let key_hash = hash(&hasher, &account_id.encode());
-            let key_hex: String = hex::encode(key_hash);
-            keys.push(format!("{storage_key_hex}{key_hex}"));
-        }
-        let mut map = HashMap::default();
-        for chunk in keys.chunks(KEY_QUERY_PAGE_SIZE) {
-            let rpc_params = if let Some(block_hash) = maybe_block_hash {
-                rpc_params!(chunk, block_hash)
-            } else {
-                rpc_params!(chunk)
-            };
-            let chunk_values: Vec<StorageChangeSet<String>> = self
-                .ws_client
-                .request("state_queryStorageAt", rpc_params)
-                .await?;
-            for (storage_key, data) in &chunk_values[0].changes {
-                if let Some(data) = data {
-                    let mut bytes: &[u8] = &data.0;
-                    let account_info: AccountInfo<u32, AccountData<u128>> =
-                        Decode::decode(&mut bytes)?;
-                    map.insert(
-                        account_id_from_storage_key(storage_key),
-                        Balance {
-                            free: account_info.data.free,
-                            reserved: account_info.data.reserved,
-                            frozen: account_info.data.frozen,
-                        },
-                    );
-                }
+        let maybe_block_hash = match maybe_block_hash {
+            None if account_ids.len() > KEY_QUERY_PAGE_SIZE => {
+                Some(self.get_current_block_hash().await?)
             }
+            maybe_block_hash => maybe_block_hash.map(str::to_string),
+        };
+        let layout = self.get_storage_layout("System", "Account", None).await?;
+        let keys = account_ids
+            .iter()
+            .map(|account_id| layout.map_key_hex(account_id))
+            .collect::<anyhow::Result<Vec<String>>>()?;
+        let mut map = HashMap::default();
+        for (storage_key, bytes) in self
+            .query_storage_at(&keys, maybe_block_hash.as_deref())
+            .await?
+        {
+            let account_info: AccountInfo<u32, AccountData<u128>> =
+                Decode::decode(&mut bytes.as_slice())?;
+            map.insert(
+                layout.decode_map_key(&storage_key)?,
+                Balance {
+                    free: account_info.data.free,
+                    reserved: account_info.data.reserved,
+                    frozen: account_info.data.frozen,
+                },
+            );
         }
         Ok(map)
     }
@@ -235,35 +526,9 @@ impl SubstrateClient {
         account_id: &AccountId,
         maybe_block_hash: Option<&str>,
     ) -> anyhow::Result<Option<Balance>> {
-        let storage_key_hex = get_storage_plain_key("System", "Account");
-        let hasher = StorageHasher::Blake2_128Concat;
-        let key_hash = hash(&hasher, &account_id.encode());
-        let key_hex: String = hex::encode(key_hash);
-        let keys = vec![format!("{storage_key_hex}{key_hex}")];
-        let rpc_params = if let Some(block_hash) = maybe_block_hash {
-            rpc_params!(keys, block_hash)
-        } else {
-            rpc_params!(keys)
-        };
-        let values: Vec<StorageChangeSet<String>> = self
-            .ws_client
-            .request("state_queryStorageAt", rpc_params)
-            .await?;
-        if let Some(change_set) = values.first() {
-            if let Some((_, Some(data))) = change_set.changes.first() {
-                let mut bytes: &[u8] = &data.0;
-                let account_info: AccountInfo<u32, AccountData<u128>> = Decode::decode(&mut bytes)?;
-                let account_data = account_info.data;
-                Ok(Some(Balance {
-                    free: account_data.free,
-                    reserved: account_data.reserved,
-                    frozen: account_data.frozen,
-                }))
-            } else {
-                Ok(None)
-            }
-        } else {
-            Ok(None)
-        }
+        Ok(self
+            .get_balances(std::slice::from_ref(account_id), maybe_block_hash)
+            .await?
+            .remove(account_id))
     }
 }
@@ -0,0 +1,257 @@
+use crate::storage_utility::hash;
+use frame_metadata::v14::{StorageEntryType, StorageHasher};
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed, META_RESERVED};
+use parity_scale_codec::{Decode, Encode};
+use rustc_hash::FxHashMap as HashMap;
+use serde::Deserialize;
+use sp_core::storage::StorageKey;
+
+/// The part of the runtime version the metadata cache is keyed by.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RuntimeVersion {
+    pub spec_version: u32,
+}
+
+/// Storage layout of a storage entry as declared in the runtime metadata: the pallet storage
+/// prefix, and one hasher per key of a map storage.
+#[derive(Clone, Debug)]
+pub struct StorageEntryLayout {
+    pallet_name: String,
+    storage_prefix: String,
+    storage_name: String,
+    hashers: Vec<StorageHasher>,
+}
+
+impl StorageEntryLayout {
+    pub fn pallet_name(&self) -> &str {
+        &self.pallet_name
+    }
+
+    pub fn storage_name(&self) -> &str {
+        &self.storage_name
+    }
+
+    /// Number of keys, zero for a plain storage.
+    pub fn key_count(&self) -> usize {
+        self.hashers.len()
+    }
+
+    fn prefix(&self) -> Vec<u8> {
+        let mut prefix = hash(&StorageHasher::Twox128, self.storage_prefix.as_bytes());
+        prefix.append(&mut hash(
+            &StorageHasher::Twox128,
+            self.storage_name.as_bytes(),
+        ));
+        prefix
+    }
+
+    /// Hex key prefix shared by all the entries of the storage.
+    pub fn prefix_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.prefix()))
+    }
+
+    fn check_key_count(&self, key_count: usize) -> anyhow::Result<()> {
+        if self.key_count() != key_count {
+            anyhow::bail!(
+                "{}.{} has {} keys in the runtime metadata, expected {key_count}.",
+                self.pallet_name,
+                self.storage_name,
+                self.key_count(),
+            );
+        }
+        Ok(())
+    }
+
+    fn key_hex(&self, encoded_keys: &[Vec<u8>]) -> anyhow::Result<String> {
+        self.check_key_count(encoded_keys.len())?;
+        let mut key = self.prefix();
+        for (hasher, encoded_key) in self.hashers.iter().zip(encoded_keys) {
+            key.append(&mut hash(hasher, encoded_key));
+        }
+        Ok(format!("0x{}", hex::encode(key)))
+    }
+
+    pub fn plain_key_hex(&self) -> anyhow::Result<String> {
+        self.key_hex(&[])
+    }
+
+    pub fn map_key_hex<K: Encode>(&self, key: &K) -> anyhow::Result<String> {
+        self.key_hex(&[key.encode()])
+    }
+
+    pub fn double_map_key_hex<K1: Encode, K2: Encode>(
+        &self,
+        key_1: &K1,
+        key_2: &K2,
+    ) -> anyhow::Result<String> {
+        self.key_hex(&[key_1.encode(), key_2.encode()])
+    }
+
+    /// Strips the storage prefix off the key, returns the hashed keys.
+    fn hashed_keys<'a>(&self, storage_key: &'a StorageKey) -> anyhow::Result<&'a [u8]> {
+        match storage_key.0.strip_prefix(self.prefix().as_slice()) {
+            Some(hashed_keys) => Ok(hashed_keys),
+            None => anyhow::bail!(
+                "Storage key 0x{} is not a {}.{} key.",
+                hex::encode(&storage_key.0),
+                self.pallet_name,
+                self.storage_name,
+            ),
+        }
+    }
+
+    /// Decodes the key at the start of the hashed keys. Only the concat hashers keep the key.
+    fn decode_key<K: Decode>(
+        &self,
+        hasher: &StorageHasher,
+        bytes: &mut &[u8],
+    ) -> anyhow::Result<K> {
+        let hash_length = match hasher {
+            StorageHasher::Identity => 0,
+            StorageHasher::Twox64Concat => 8,
+            StorageHasher::Blake2_128Concat => 16,
+            _ => anyhow::bail!(
+                "{}.{} keys are hashed with {hasher:?}, which does not keep the key.",
+                self.pallet_name,
+                self.storage_name,
+            ),
+        };
+        if bytes.len() < hash_length {
+            anyhow::bail!(
+                "{}.{} key is shorter than its {hasher:?} hash.",
+                self.pallet_name,
+                self.storage_name,
+            );
+        }
+        *bytes = &bytes[hash_length..];
+        K::decode(bytes).map_err(|error| {
+            anyhow::anyhow!(
+                "Cannot decode {}.{} key: {error}",
+                self.pallet_name,
+                self.storage_name,
+            )
+        })
+    }
+
+    fn check_key_end(&self, bytes: &[u8]) -> anyhow::Result<()> {
+        if !bytes.is_empty() {
+            anyhow::bail!(
+                "{}.{} key has {} bytes left after decoding.",
+                self.pallet_name,
+                self.storage_name,
+                bytes.len(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Decodes the key of a single-key map storage. Fails if the storage is not a single-key
+    /// map or the key does not decode to exactly `K`.
+    pub fn decode_map_key<K: Decode>(&self, storage_key: &StorageKey) -> anyhow::Result<K> {
+        self.check_key_count(1)?;
+        let mut bytes = self.hashed_keys(storage_key)?;
+        let key = self.decode_key(&self.hashers[0], &mut bytes)?;
+        self.check_key_end(bytes)?;
+        Ok(key)
+    }
+
+    /// Decodes the keys of a double-map storage. Fails if the storage is not a double map or
+    /// the keys do not decode to exactly `K1` and `K2`.
+    pub fn decode_double_map_key<K1: Decode, K2: Decode>(
+        &self,
+        storage_key: &StorageKey,
+    ) -> anyhow::Result<(K1, K2)> {
+        self.check_key_count(2)?;
+        let mut bytes = self.hashed_keys(storage_key)?;
+        let key_1 = self.decode_key(&self.hashers[0], &mut bytes)?;
+        let key_2 = self.decode_key(&self.hashers[1], &mut bytes)?;
+        self.check_key_end(bytes)?;
+        Ok((key_1, key_2))
+    }
+}
+
+/// Pallet name, storage prefix, and the storage names with their hashers.
+type PalletStorages = (String, String, Vec<(String, Vec<StorageHasher>)>);
+
+/// `PalletStorages` of the pallets with storage. The pallet metadata types differ between the
+/// metadata versions but have the same storage fields.
+macro_rules! pallet_storages {
+    ($metadata:expr) => {
+        $metadata
+            .pallets
+            .into_iter()
+            .filter_map(|pallet| {
+                let storage = pallet.storage?;
+                Some((
+                    pallet.name,
+                    storage.prefix,
+                    storage
+                        .entries
+                        .into_iter()
+                        .map(|entry| {
+                            let hashers = match entry.ty {
+                                StorageEntryType::Plain(_) => Vec::new(),
+                                StorageEntryType::Map { hashers, .. } => hashers,
+                            };
+                            (entry.name, hashers)
+                        })
+                        .collect(),
+                ))
+            })
+            .collect()
+    };
+}
+
+/// Storage layouts of a runtime version, by pallet and storage name.
+pub(crate) struct StorageMetadata {
+    entries: HashMap<(String, String), StorageEntryLayout>,
+}
+
+impl StorageMetadata {
+    pub fn from_bytes(mut bytes: &[u8]) -> anyhow::Result<Self> {
+        let metadata = RuntimeMetadataPrefixed::decode(&mut bytes)?;
+        if metadata.0 != META_RESERVED {
+            anyhow::bail!("Invalid runtime metadata prefix.");
+        }
+        let pallets: Vec<PalletStorages> = match metadata.1 {
+            RuntimeMetadata::V14(metadata) => pallet_storages!(metadata),
+            RuntimeMetadata::V15(metadata) => pallet_storages!(metadata),
+            RuntimeMetadata::V16(metadata) => pallet_storages!(metadata),
+            metadata => anyhow::bail!(
+                "Unsupported runtime metadata version {}.",
+                metadata.version()
+            ),
+        };
+        let mut entries = HashMap::default();
+        for (pallet_name, storage_prefix, storage_entries) in pallets {
+            for (storage_name, hashers) in storage_entries {
+                entries.insert(
+                    (pallet_name.clone(), storage_name.clone()),
+                    StorageEntryLayout {
+                        pallet_name: pallet_name.clone(),
+                        storage_prefix: storage_prefix.clone(),
+                        storage_name,
+                        hashers,
+                    },
+                );
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    /// Layout of the storage entry. Fails if the runtime has no such storage.
+    pub fn get_entry(
+        &self,
+        pallet_name: &str,
+        storage_name: &str,
+    ) -> anyhow::Result<StorageEntryLayout> {
+        match self
+            .entries
+            .get(&(pallet_name.to_string(), storage_name.to_string()))
+        {
+            Some(entry) => Ok(entry.clone()),
+            None => anyhow::bail!("{pallet_name}.{storage_name} is not in the runtime metadata."),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::metadata::StorageEntryLayout;
+use crate::storage_utility::get_rpc_paged_keys_params;
+use crate::{SubstrateClient, KEY_QUERY_PAGE_SIZE};
+use sp_core::storage::StorageKey;
+
+/// Pages through the entries of a map storage at a pinned block. Each page fetches the next
+/// `KEY_QUERY_PAGE_SIZE` keys and then their values, so the keys of the whole map are never held
+/// at once and a failed request only repeats its page.
+pub struct StorageIterator<'a> {
+    client: &'a SubstrateClient,
+    layout: StorageEntryLayout,
+    prefix_key: String,
+    block_hash: String,
+    maybe_last_key: Option<String>,
+    is_exhausted: bool,
+    entry_count: usize,
+}
+
+impl<'a> StorageIterator<'a> {
+    pub(crate) fn new(
+        client: &'a SubstrateClient,
+        layout: StorageEntryLayout,
+        block_hash: &str,
+    ) -> Self {
+        Self {
+            client,
+            prefix_key: layout.prefix_hex(),
+            layout,
+            block_hash: block_hash.to_string(),
+            maybe_last_key: None,
+            is_exhausted: false,
+            entry_count: 0,
+        }
+    }
+
+    /// Next page of keys and values, `None` after the last page. Keys without values are
+    /// skipped.
+    pub async fn next_page(&mut self) -> anyhow::Result<Option<Vec<(StorageKey, Vec<u8>)>>> {
+        if self.is_exhausted {
+            return Ok(None);
+        }
+        let keys: Vec<String> = self
+            .client
+            .request_with_retry(
+                "state_getKeysPaged",
+                get_rpc_paged_keys_params(
+                    self.prefix_key.as_str(),
+                    KEY_QUERY_PAGE_SIZE,
+                    self.maybe_last_key.as_deref(),
+                    Some(self.block_hash.as_str()),
+                ),
+            )
+            .await?;
+        if keys.len() < KEY_QUERY_PAGE_SIZE {
+            self.is_exhausted = true;
+        }
+        let Some(last_key) = keys.last() else {
+            return Ok(None);
+        };
+        self.maybe_last_key = Some(last_key.clone());
+        let entries = self
+            .client
+            .query_storage_at(&keys, Some(self.block_hash.as_str()))
+            .await?;
+        self.entry_count += entries.len();
+        log::debug!(
+            "Got {} {}.{} entries.",
+            self.entry_count,
+            self.layout.pallet_name(),
+            self.layout.storage_name(),
+        );
+        Ok(Some(entries))
+    }
+
+    /// Next page of entries decoded by `decode`, which gets the storage layout to decode the
+    /// keys with, `None` after the last page.
+    pub async fn next_decoded_page<T>(
+        &mut self,
+        decode: impl Fn(&StorageEntryLayout, &StorageKey, &[u8]) -> anyhow::Result<T>,
+    ) -> anyhow::Result<Option<Vec<T>>> {
+        match self.next_page().await? {
+            Some(entries) => Ok(Some(
+                entries
+                    .iter()
+                    .map(|(key, bytes)| decode(&self.layout, key, bytes))
+                    .collect::<anyhow::Result<Vec<T>>>()?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Number of entries fetched so far.
+    pub fn entry_count(&self) -> usize {
+        self.entry_count
+    }
+}
@@ -1,9 +1,6 @@
 use frame_metadata::v14::StorageHasher;
-use ftd_types::substrate::account_id::AccountId;
 use jsonrpsee_core::params::ArrayParams;
 use jsonrpsee_core::JsonValue;
-use sp_core::storage::StorageKey;
-use sp_core::Decode;
 
 pub(crate) fn hash(hasher: &StorageHasher, bytes: &[u8]) -> Vec<u8> {
     match hasher {
@@ -25,26 +22,14 @@ pub(crate) fn hash(hasher: &StorageHasher, bytes: &[u8]) -> Vec<u8> {
     }
 }
 
-pub(crate) fn get_storage_plain_key(module_name: &str, storage_name: &str) -> String {
-    let hasher = StorageHasher::Twox128;
-    let mut storage_hash: Vec<u8> = Vec::new();
-    let mut module_name_hash = hash(&hasher, module_name.as_bytes());
-    storage_hash.append(&mut module_name_hash);
-    let mut storage_name_hash = hash(&hasher, storage_name.as_bytes());
-    storage_hash.append(&mut storage_name_hash);
-    let storage_key_hex: String = hex::encode(storage_hash);
-    format!("0x{storage_key_hex}")
-}
-
 pub fn get_rpc_paged_keys_params<'a>(
-    module: &'a str,
-    name: &'a str,
+    prefix_key: &'a str,
     count: usize,
     start_key: Option<&'a str>,
     block_hash: Option<&'a str>,
 ) -> ArrayParams {
     let mut params = ArrayParams::new();
-    params.insert(get_storage_plain_key(module, name)).unwrap();
+    params.insert(prefix_key).unwrap();
     params.insert(count).unwrap();
     if let Some(start_key) = start_key {
         params.insert(start_key).unwrap();
@@ -59,33 +44,13 @@ pub fn get_rpc_paged_keys_params<'a>(
     params
 }
 
-pub fn get_rpc_storage_plain_params<'a>(
-    module: &'a str,
-    name: &'a str,
-    block_hash: Option<&'a str>,
-) -> ArrayParams {
+pub fn get_rpc_storage_params<'a>(key: &'a str, block_hash: Option<&'a str>) -> ArrayParams {
     //let mut params: Vec<JsonValue> = vec![.into()];
     let mut params = ArrayParams::new();
-    params.insert(get_storage_plain_key(module, name)).unwrap();
+    params.insert(key).unwrap();
     if let Some(block_hash) = block_hash {
         //params.push(block_hash.into());
         params.insert(block_hash).unwrap();
     }
     params
 }
-
-pub fn decode_hex_string<T>(hex_string: &str) -> anyhow::Result<T>
-where
-    T: Decode,
-{
-    let trimmed_hex_string = hex_string.trim_start_matches("0x");
-    let mut bytes: &[u8] = &hex::decode(trimmed_hex_string).unwrap();
-    let decoded = Decode::decode(&mut bytes)?;
-    Ok(decoded)
-}
-
-pub fn account_id_from_storage_key(storage_key: &StorageKey) -> AccountId {
-    storage_key.0[storage_key.0.len() - 32..]
-        .try_into()
-        .unwrap()
-}
@@ -1,24 +1,56 @@
 use crate::api::identity::{Identity, SubIdentity};
+use crate::api::label::Label;
+use crate::api::relation::AccountRelation;
+use crate::api::transfer::Transfer;
 use crate::graph::TransferVolume;
 use crate::subscan::SubscanAccount;
 use crate::substrate::balance::Balance;
 use frame_support::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Account {
     pub address: String,
     pub identity: Option<Identity>,
     pub sub_identity: Option<SubIdentity>,
     pub super_identity: Option<Identity>,
+    /// Username the account chose to be displayed, e.g. `alice.dot`.
+    pub primary_username: Option<String>,
     pub balance: Option<Balance>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subscan_account: Option<SubscanAccount>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<Label>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountGraph {
     pub accounts: Vec<Account>,
     pub transfer_volumes: Vec<TransferVolume>,
+    /// Proxy, multisig and staking relations of the account, when requested.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relations: Vec<AccountRelation>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStats {
+    pub first_seen_block_number: Option<u64>,
+    pub last_seen_block_number: Option<u64>,
+    pub total_sent: u128,
+    pub total_received: u128,
+    pub sent_transfer_count: u64,
+    pub received_transfer_count: u64,
+    pub counterparty_count: u64,
+    pub largest_incoming_transfer: Option<Transfer>,
+    pub largest_outgoing_transfer: Option<Transfer>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountDetails {
+    pub account: Account,
+    pub stats: AccountStats,
 }
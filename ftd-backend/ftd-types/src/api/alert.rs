@@ -0,0 +1,144 @@
+use crate::api::transfer::Transfer;
+use frame_support::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+/// Direction of the transfers that trigger an alert, relative to the watched address.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchDirection {
+    Incoming,
+    Outgoing,
+    #[default]
+    Both,
+}
+
+impl WatchDirection {
+    pub fn matches(&self, address: &str, transfer: &Transfer) -> bool {
+        match self {
+            Self::Incoming => transfer.to_address == address,
+            Self::Outgoing => transfer.from_address == address,
+            Self::Both => transfer.to_address == address || transfer.from_address == address,
+        }
+    }
+}
+
+impl Display for WatchDirection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let display = match self {
+            Self::Incoming => "incoming",
+            Self::Outgoing => "outgoing",
+            Self::Both => "both",
+        };
+        write!(f, "{display}")
+    }
+}
+
+impl FromStr for WatchDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "incoming" => Ok(Self::Incoming),
+            "outgoing" => Ok(Self::Outgoing),
+            "both" => Ok(Self::Both),
+            _ => Err(format!("Unknown watch direction {s}.")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchEntry {
+    pub id: u32,
+    pub address: String,
+    pub direction: WatchDirection,
+    /// Minimum transfer amount in Planck.
+    pub threshold: u128,
+    pub label: Option<String>,
+    pub webhook_url: Option<String>,
+    pub is_active: bool,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertDeliveryStatus {
+    /// Waiting for the first or a retried webhook delivery.
+    Pending,
+    Delivered,
+    /// All delivery attempts have failed.
+    Failed,
+    /// The watch entry has no webhook.
+    Skipped,
+}
+
+impl Display for AlertDeliveryStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let display = match self {
+            Self::Pending => "pending",
+            Self::Delivered => "delivered",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+        };
+        write!(f, "{display}")
+    }
+}
+
+impl FromStr for AlertDeliveryStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "delivered" => Ok(Self::Delivered),
+            "failed" => Ok(Self::Failed),
+            "skipped" => Ok(Self::Skipped),
+            _ => Err(format!("Unknown alert delivery status {s}.")),
+        }
+    }
+}
+
+/// A transfer that matched a watch entry. This is also the webhook payload.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Alert {
+    pub id: u32,
+    pub watch_entry_id: u32,
+    pub address: String,
+    pub label: Option<String>,
+    /// Whether the transfer was received or sent by the watched address.
+    pub direction: WatchDirection,
+    pub transfer: Transfer,
+    pub delivery_status: AlertDeliveryStatus,
+    pub delivery_attempt_count: u32,
+    /// Creation time in milliseconds.
+    pub created_at: u64,
+}
+
+/// Response to the watch entry creation, the only time the webhook secret is returned.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedWatchEntry {
+    #[serde(flatten)]
+    pub watch_entry: WatchEntry,
+    /// Key of the HMAC-SHA256 signature in the `X-FTD-Signature` header of the webhook
+    /// requests. Only set if the watch entry has a webhook.
+    pub webhook_secret: Option<String>,
+}
+
+/// Request body for creating a watch entry.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NewWatchEntry {
+    /// SS58 address or account id hex.
+    pub address: String,
+    #[serde(default)]
+    pub direction: WatchDirection,
+    /// Minimum transfer amount in Planck, zero by default.
+    #[serde(default)]
+    pub threshold: u128,
+    pub label: Option<String>,
+    /// HTTP(S) URL that the alerts are posted to. Alerts are only recorded if not set.
+    pub webhook_url: Option<String>,
+}
@@ -0,0 +1,74 @@
+use frame_support::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+/// Premium keys can also use the graph export formats, GraphQL and the transfer stream.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum APIKeyScope {
+    #[default]
+    Standard,
+    Premium,
+}
+
+impl Display for APIKeyScope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let display = match self {
+            Self::Standard => "standard",
+            Self::Premium => "premium",
+        };
+        write!(f, "{display}")
+    }
+}
+
+impl FromStr for APIKeyScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "standard" => Ok(Self::Standard),
+            "premium" => Ok(Self::Premium),
+            _ => Err(format!("Unknown API key scope {s}.")),
+        }
+    }
+}
+
+/// API key without the key itself, only its hash is stored.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct APIKey {
+    pub id: u32,
+    pub name: String,
+    /// First characters of the key to tell the keys apart.
+    pub key_prefix: String,
+    pub scope: APIKeyScope,
+    /// Token bucket refill rate.
+    pub rate_limit_per_minute: u32,
+    /// Token bucket capacity.
+    pub burst: u32,
+    pub is_active: bool,
+    /// Creation time in milliseconds.
+    pub created_at: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NewAPIKey {
+    pub name: String,
+    #[serde(default)]
+    pub scope: APIKeyScope,
+    /// The configured default if not given.
+    pub rate_limit_per_minute: Option<u32>,
+    /// The configured default if not given.
+    pub burst: Option<u32>,
+}
+
+/// Response to the key creation, the only time the key is returned.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedAPIKey {
+    #[serde(flatten)]
+    pub api_key: APIKey,
+    pub key: String,
+}
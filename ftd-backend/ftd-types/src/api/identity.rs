@@ -1,6 +1,85 @@
 use frame_support::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+/// Hash function of an identity field that's stored on chain as a hash instead of raw data.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize, ToSchema)]
+pub enum IdentityDataHashType {
+    #[serde(rename = "blake_two_256")]
+    BlakeTwo256,
+    #[serde(rename = "sha_256")]
+    Sha256,
+    #[serde(rename = "keccak_256")]
+    Keccak256,
+    #[serde(rename = "sha_three_256")]
+    ShaThree256,
+}
+
+impl Display for IdentityDataHashType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let display = match self {
+            Self::BlakeTwo256 => "blake_two_256",
+            Self::Sha256 => "sha_256",
+            Self::Keccak256 => "keccak_256",
+            Self::ShaThree256 => "sha_three_256",
+        };
+        write!(f, "{display}")
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JudgementType {
+    Unknown,
+    FeePaid,
+    Reasonable,
+    KnownGood,
+    OutOfDate,
+    LowQuality,
+    Erroneous,
+}
+
+impl Display for JudgementType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let display = match self {
+            Self::Unknown => "unknown",
+            Self::FeePaid => "fee_paid",
+            Self::Reasonable => "reasonable",
+            Self::KnownGood => "known_good",
+            Self::OutOfDate => "out_of_date",
+            Self::LowQuality => "low_quality",
+            Self::Erroneous => "erroneous",
+        };
+        write!(f, "{display}")
+    }
+}
+
+/// Judgement of an identity by a registrar.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IdentityJudgement {
+    pub registrar_index: u32,
+    pub judgement: JudgementType,
+    /// Fee held for the registrar in Planck, for `fee_paid` judgements.
+    pub fee_paid_amount: Option<u128>,
+}
+
+/// Identity registrar.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Registrar {
+    /// Index of the registrar, referenced by the judgements.
+    pub index: u32,
+    pub address: String,
+    /// Judgement fee in Planck.
+    pub fee: u128,
+    /// Bit flags of the identity fields the registrar judges.
+    pub fields: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Identity {
     pub address: String,
@@ -10,14 +89,128 @@ pub struct Identity {
     pub riot: Option<String>,
     pub twitter: Option<String>,
     pub web: Option<String>,
+    pub github: Option<String>,
+    pub discord: Option<String>,
+    pub image: Option<String>,
+    /// PGP key fingerprint hex.
+    pub pgp_fingerprint: Option<String>,
+    /// Hash types of the fields that are set as hashes on chain, by field name. The values
+    /// of these fields are the hash hexes.
+    pub hashed_fields: BTreeMap<String, IdentityDataHashType>,
+    /// Judgements of the identity by the registrars.
+    pub judgements: Vec<IdentityJudgement>,
+    /// Whether a registrar judged the identity `reasonable` or `known_good`.
     pub is_confirmed: bool,
+    /// Whether a registrar judged the identity `out_of_date`, `low_quality` or `erroneous`.
     pub is_invalid: bool,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SubIdentity {
     pub address: String,
     pub super_address: String,
     pub sub_display: Option<String>,
 }
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentityChangeType {
+    IdentitySet,
+    IdentityCleared,
+    SubIdentitySet,
+    SubIdentityCleared,
+}
+
+impl Display for IdentityChangeType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let display = match self {
+            Self::IdentitySet => "identity_set",
+            Self::IdentityCleared => "identity_cleared",
+            Self::SubIdentitySet => "sub_identity_set",
+            Self::SubIdentityCleared => "sub_identity_cleared",
+        };
+        write!(f, "{display}")
+    }
+}
+
+impl FromStr for IdentityChangeType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "identity_set" => Ok(Self::IdentitySet),
+            "identity_cleared" => Ok(Self::IdentityCleared),
+            "sub_identity_set" => Ok(Self::SubIdentitySet),
+            "sub_identity_cleared" => Ok(Self::SubIdentityCleared),
+            _ => Err(format!("Unknown identity change type {s}.")),
+        }
+    }
+}
+
+/// Chain on which an identity change was observed. The identities lived on the relay chain
+/// until they moved to the People chain.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentityChain {
+    Relay,
+    People,
+}
+
+impl Display for IdentityChain {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let display = match self {
+            Self::Relay => "relay",
+            Self::People => "people",
+        };
+        write!(f, "{display}")
+    }
+}
+
+impl FromStr for IdentityChain {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "relay" => Ok(Self::Relay),
+            "people" => Ok(Self::People),
+            _ => Err(format!("Unknown identity chain {s}.")),
+        }
+    }
+}
+
+/// Identity or sub identity change of an account, observed by the identity updater.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IdentityChange {
+    pub id: i32,
+    pub address: String,
+    pub change_type: IdentityChangeType,
+    /// The identity after an `identity_set` change.
+    pub identity: Option<Identity>,
+    /// The sub identity after a `sub_identity_set` change.
+    pub sub_identity: Option<SubIdentity>,
+    /// Chain of the block at which the change was observed.
+    pub chain: IdentityChain,
+    /// Whether the change was sampled from historical chain state by the identity backfill,
+    /// rather than observed by the identity updater.
+    pub is_backfill: bool,
+    /// Hash of the block at which the change was observed.
+    pub block_hash: String,
+    /// Number of the block at which the change was observed.
+    pub block_number: u64,
+    /// Timestamp (ms) of the block at which the change was observed.
+    pub block_timestamp: u64,
+}
+
+/// Identity and sub identity of an account at a point in history.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoricalIdentity {
+    pub address: String,
+    /// Whether the identity change log reaches back to the given block or time. The identity
+    /// and sub identity are unknown, and empty, if not.
+    pub is_indexed: bool,
+    pub identity: Option<Identity>,
+    pub sub_identity: Option<SubIdentity>,
+}
@@ -0,0 +1,111 @@
+use crate::substrate::chain::Chain;
+use frame_support::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelCategory {
+    Exchange,
+    Bridge,
+    Scam,
+    Treasury,
+    Validator,
+    Other,
+}
+
+impl Display for LabelCategory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let display = match self {
+            Self::Exchange => "exchange",
+            Self::Bridge => "bridge",
+            Self::Scam => "scam",
+            Self::Treasury => "treasury",
+            Self::Validator => "validator",
+            Self::Other => "other",
+        };
+        write!(f, "{display}")
+    }
+}
+
+impl FromStr for LabelCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "exchange" => Ok(Self::Exchange),
+            "bridge" => Ok(Self::Bridge),
+            "scam" => Ok(Self::Scam),
+            "treasury" => Ok(Self::Treasury),
+            "validator" => Ok(Self::Validator),
+            "other" => Ok(Self::Other),
+            _ => Err(format!("Unknown label category {s}.")),
+        }
+    }
+}
+
+/// Off-chain account label, such as an exchange hot wallet or a known scam account.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Label {
+    pub id: u32,
+    pub address: String,
+    pub name: String,
+    pub category: LabelCategory,
+    /// Where the label comes from, e.g. a team member, a partner or a public list.
+    pub source: String,
+    /// Between 0 and 1.
+    pub confidence: f32,
+    /// Labeling time in milliseconds.
+    pub labeled_at: u64,
+}
+
+/// Label in the API request bodies and the importer's CSV and JSON files. A label with the
+/// same address, name and source replaces the existing one.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NewLabel {
+    /// SS58 address or account id hex.
+    pub address: String,
+    pub name: String,
+    pub category: LabelCategory,
+    pub source: String,
+    /// Between 0 and 1, 1 by default.
+    pub confidence: Option<f32>,
+    /// Labeling time in milliseconds, the current time by default.
+    pub labeled_at: Option<u64>,
+}
+
+impl NewLabel {
+    /// Validates the label, converts the address to the SS58 format of the chain and trims the
+    /// texts.
+    pub fn normalize(&self, chain: &Chain) -> Result<NewLabel, String> {
+        let address = chain
+            .normalize_address(&self.address)
+            .ok_or_else(|| format!("Invalid address {}.", self.address))?;
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err("Label name should not be empty.".to_string());
+        }
+        let source = self.source.trim();
+        if source.is_empty() {
+            return Err("Label source should not be empty.".to_string());
+        }
+        if let Some(confidence) = self.confidence {
+            if !(0.0..=1.0).contains(&confidence) {
+                return Err(format!(
+                    "Label confidence should be between 0 and 1, got {confidence}."
+                ));
+            }
+        }
+        Ok(NewLabel {
+            address,
+            name: name.to_string(),
+            category: self.category,
+            source: source.to_string(),
+            confidence: self.confidence,
+            labeled_at: self.labeled_at,
+        })
+    }
+}
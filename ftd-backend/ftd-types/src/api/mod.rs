@@ -1,3 +1,7 @@
 pub mod account;
+pub mod alert;
+pub mod api_key;
 pub mod identity;
+pub mod label;
+pub mod relation;
 pub mod transfer;
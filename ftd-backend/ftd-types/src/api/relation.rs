@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+/// Type of an on-chain relationship between two accounts.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountRelationType {
+    /// The `from` account is a proxy of the `to` account.
+    Proxy,
+    /// The `from` account is a signatory of the `to` multisig account.
+    MultisigMember,
+    /// The `from` account is the staking controller of the `to` stash account.
+    StakingController,
+    /// The `from` account receives the staking rewards of the `to` stash account.
+    StakingPayee,
+}
+
+impl AccountRelationType {
+    /// Type of the relationship edges in the graph.
+    pub fn graph_edge_type(&self) -> &'static str {
+        match self {
+            Self::Proxy => "PROXY_OF",
+            Self::MultisigMember => "MULTISIG_MEMBER",
+            Self::StakingController => "CONTROLLER_OF",
+            Self::StakingPayee => "PAYEE_OF",
+        }
+    }
+
+    pub fn from_graph_edge_type(edge_type: &str) -> Result<Self, String> {
+        match edge_type {
+            "PROXY_OF" => Ok(Self::Proxy),
+            "MULTISIG_MEMBER" => Ok(Self::MultisigMember),
+            "CONTROLLER_OF" => Ok(Self::StakingController),
+            "PAYEE_OF" => Ok(Self::StakingPayee),
+            _ => Err(format!("Unknown account relation edge type {edge_type}.")),
+        }
+    }
+}
+
+impl Display for AccountRelationType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let display = match self {
+            Self::Proxy => "proxy",
+            Self::MultisigMember => "multisig_member",
+            Self::StakingController => "staking_controller",
+            Self::StakingPayee => "staking_payee",
+        };
+        write!(f, "{display}")
+    }
+}
+
+impl FromStr for AccountRelationType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "proxy" => Ok(Self::Proxy),
+            "multisig_member" => Ok(Self::MultisigMember),
+            "staking_controller" => Ok(Self::StakingController),
+            "staking_payee" => Ok(Self::StakingPayee),
+            _ => Err(format!("Unknown account relation type {s}.")),
+        }
+    }
+}
+
+/// On-chain relationship from an account that acts on behalf of, or belongs to, another account.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountRelation {
+    /// Proxy, multisig signatory, controller or payee account.
+    pub from: String,
+    /// Delegating, multisig or stash account.
+    pub to: String,
+    pub relation_type: AccountRelationType,
+    /// Proxy type of a `proxy` relation, such as `Any` or `Staking`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy_type: Option<String>,
+    /// Announcement delay in blocks of a `proxy` relation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy_delay: Option<u32>,
+    /// Number of approvals the multisig account of a `multisig_member` relation needs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multisig_threshold: Option<u16>,
+}
+
+/// Added or removed account relation, projected onto the graph by the graph updater.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountRelationChange {
+    pub id: i32,
+    pub relation: AccountRelation,
+    pub is_removed: bool,
+    /// Number of the block at which the change was observed.
+    pub block_number: u64,
+}
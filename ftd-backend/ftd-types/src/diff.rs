@@ -0,0 +1,88 @@
+//! Diffs of the stored entries against the chain state.
+use crate::substrate::account_id::AccountId;
+use crate::substrate::identity::{Identity, SubIdentity, Username};
+use crate::substrate::relation::{AccountRelation, AccountRelationKey};
+use rustc_hash::FxHashMap as HashMap;
+use std::hash::Hash;
+
+/// Chain state entry with a unique key, such as the account of an identity.
+pub trait Entry: Clone + Eq {
+    type Key: Eq + Hash;
+
+    fn key(&self) -> Self::Key;
+}
+
+impl Entry for Identity {
+    type Key = AccountId;
+
+    fn key(&self) -> AccountId {
+        self.account_id
+    }
+}
+
+impl Entry for SubIdentity {
+    type Key = AccountId;
+
+    fn key(&self) -> AccountId {
+        self.account_id
+    }
+}
+
+impl Entry for Username {
+    type Key = String;
+
+    fn key(&self) -> String {
+        self.username.clone()
+    }
+}
+
+impl Entry for AccountRelation {
+    type Key = AccountRelationKey;
+
+    fn key(&self) -> AccountRelationKey {
+        AccountRelation::key(self)
+    }
+}
+
+/// Changes that bring the stored entries in line with the chain state.
+pub struct Diff<T: Entry> {
+    pub inserted: Vec<T>,
+    pub changed: Vec<T>,
+    pub removed: Vec<T::Key>,
+}
+
+impl<T: Entry> Diff<T> {
+    pub fn new(stored: Vec<T>, current: Vec<T>) -> Self {
+        let mut stored: HashMap<T::Key, T> = stored
+            .into_iter()
+            .map(|entry| (entry.key(), entry))
+            .collect();
+        let mut inserted = Vec::new();
+        let mut changed = Vec::new();
+        for entry in current {
+            match stored.remove(&entry.key()) {
+                None => inserted.push(entry),
+                Some(stored_entry) if stored_entry != entry => changed.push(entry),
+                Some(_) => (),
+            }
+        }
+        Self {
+            inserted,
+            changed,
+            removed: stored.into_keys().collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inserted.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+
+    /// The inserted and changed entries.
+    pub fn saved(&self) -> Vec<T> {
+        self.inserted
+            .iter()
+            .chain(self.changed.iter())
+            .cloned()
+            .collect()
+    }
+}
@@ -1,20 +1,53 @@
 //! Error types.
 use serde::{Deserialize, Serialize};
 use sp_core::bytes::FromHexError;
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+/// Stable machine-readable error codes of the API.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ServiceErrorCode {
+    /// Invalid query, path or body parameter.
+    InvalidParameter,
+    /// Invalid SS58 address or account id hex.
+    InvalidAddress,
+    /// Missing or invalid API or admin key.
+    Unauthorized,
+    /// The API key doesn't have the scope required by the endpoint.
+    Forbidden,
+    NotFound,
+    RateLimited,
+    /// The Substrate node cannot be reached or didn't respond in time.
+    UpstreamNodeUnavailable,
+    SubscanQuotaExceeded,
+    DatabaseTimeout,
+    #[default]
+    InternalError,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema)]
 pub struct ServiceError {
+    pub code: ServiceErrorCode,
     pub description: String,
 }
 
 impl ServiceError {
-    pub fn from(description: &str) -> ServiceError {
+    pub fn new(code: ServiceErrorCode, description: &str) -> ServiceError {
         ServiceError {
+            code,
             description: description.to_string(),
         }
     }
 }
 
+#[derive(thiserror::Error, Clone, Debug)]
+pub enum SubscanError {
+    #[error("Subscan API quota exceeded.")]
+    QuotaExceeded,
+    #[error("Subscan API error with status {0}.")]
+    Status(u16),
+}
+
 #[derive(thiserror::Error, Clone, Debug)]
 pub enum BlockDataError {
     #[error("Block array not found.")]
@@ -47,6 +80,16 @@ pub enum TransferEventDataError {
     AmountNotFound,
 }
 
+#[derive(thiserror::Error, Clone, Debug)]
+pub enum MultisigCallDataError {
+    #[error("Multisig signatories not found.")]
+    SignatoriesNotFound,
+    #[error("Invalid multisig signatory address.")]
+    InvalidSignatory,
+    #[error("Multisig threshold not found.")]
+    ThresholdNotFound,
+}
+
 #[derive(thiserror::Error, Clone, Debug)]
 pub enum DecodeError {
     #[error("Decode error: {0}")]
@@ -1,12 +1,14 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct GraphUpdaterState {
     pub last_processed_transfer_id: i32,
     pub last_processed_identity_change_id: i32,
+    pub last_processed_account_relation_change_id: i32,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
 pub struct TransferVolume {
     pub id: u64,
     pub from: String,
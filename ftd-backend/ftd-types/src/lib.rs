@@ -1,5 +1,6 @@
 #![warn(clippy::disallowed_types)]
 pub mod api;
+pub mod diff;
 pub mod err;
 pub mod graph;
 pub mod subscan;
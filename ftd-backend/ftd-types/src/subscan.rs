@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct SubscanAccountListBody {
@@ -30,19 +31,21 @@ pub struct SubscanAccountData {
     pub account: SubscanAccount,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 pub struct SubscanAccount {
     pub address: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub display: Option<String>,
-    #[serde(rename(serialize = "accountDisplay"))]
+    #[serde(rename = "accountDisplay", alias = "account_display")]
+    #[schema(rename = "accountDisplay")]
     pub account_display: SubscanAccountDisplay,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 pub struct SubscanAccountDisplay {
     pub address: String,
-    #[serde(rename(serialize = "accountIndex"))]
+    #[serde(rename = "accountIndex", alias = "account_index")]
+    #[schema(rename = "accountIndex")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub account_index: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -55,28 +58,33 @@ pub struct SubscanAccountDisplay {
     pub merkle: Option<SubscanMerkleScienceAccountInfo>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 pub struct SubscanParentAccountDisplay {
     pub address: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub display: Option<String>,
-    #[serde(rename(serialize = "subSymbol"))]
+    #[serde(rename = "subSymbol", alias = "sub_symbol")]
+    #[schema(rename = "subSymbol")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sub_symbol: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub identity: Option<bool>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 pub struct SubscanMerkleScienceAccountInfo {
-    #[serde(rename(serialize = "addressType"))]
+    #[serde(rename = "addressType", alias = "address_type")]
+    #[schema(rename = "addressType")]
     pub address_type: String,
-    #[serde(rename(serialize = "tagType"))]
+    #[serde(rename = "tagType", alias = "tag_type")]
+    #[schema(rename = "tagType")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tag_type: Option<String>,
-    #[serde(rename(serialize = "tagSubtype"))]
+    #[serde(rename = "tagSubtype", alias = "tag_subtype")]
+    #[schema(rename = "tagSubtype")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tag_subtype: Option<String>,
-    #[serde(rename(serialize = "tagName"))]
+    #[serde(rename = "tagName", alias = "tag_name")]
+    #[schema(rename = "tagName")]
     pub tag_name: String,
 }
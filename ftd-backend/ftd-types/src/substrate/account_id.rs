@@ -5,7 +5,7 @@ use sp_core::crypto::{Ss58AddressFormat, Ss58Codec};
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug, Encode, Default, Decode, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Encode, Default, Decode, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct AccountId([u8; 32]);
 
 impl Display for AccountId {
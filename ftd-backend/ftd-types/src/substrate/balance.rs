@@ -1,9 +1,21 @@
 use frame_support::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Balance {
     pub free: u128,
     pub reserved: u128,
     pub frozen: u128,
 }
+
+impl Balance {
+    /// See `Block::convert_to_old_dot`.
+    pub fn convert_to_old_dot(&self) -> Balance {
+        Balance {
+            free: self.free / 100,
+            reserved: self.reserved / 100,
+            frozen: self.frozen / 100,
+        }
+    }
+}
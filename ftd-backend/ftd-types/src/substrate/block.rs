@@ -1,4 +1,5 @@
 use crate::substrate::event::TransferEvent;
+use crate::substrate::relation::MultisigCall;
 use frame_support::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -9,6 +10,8 @@ pub struct Block {
     pub parent_hash: String,
     pub author_address: Option<String>,
     pub transfers: Vec<TransferEvent>,
+    #[serde(default)]
+    pub multisig_calls: Vec<MultisigCall>,
 }
 
 impl Block {
@@ -1,3 +1,4 @@
+use crate::substrate::account_id::AccountId;
 use sp_core::crypto::Ss58AddressFormat;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
@@ -47,6 +48,14 @@ impl Chain {
         }
     }
 
+    /// Parses an SS58 address of any prefix or an account id hex, and encodes it in the SS58
+    /// format of the chain.
+    pub fn normalize_address(&self, address: &str) -> Option<String> {
+        AccountId::from_str(address.trim()).ok().map(|account_id| {
+            account_id.to_ss58_check_with_version(self.get_ss58_address_format().prefix())
+        })
+    }
+
     pub fn sp_core_set_default_ss58_version(&self) {
         sp_core::crypto::set_default_ss58_version(self.get_ss58_address_format())
     }
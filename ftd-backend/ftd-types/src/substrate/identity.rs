@@ -1,9 +1,11 @@
+use crate::api::identity::{IdentityChain, IdentityDataHashType, IdentityJudgement, JudgementType};
 use crate::substrate::account_id::AccountId;
 use frame_support::pallet_prelude::{ConstU32, Encode};
 use frame_support::BoundedVec;
 use pallet_identity::{Data, Judgement};
 use parity_scale_codec::Decode;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 pub fn identity_data_to_string(data: Data) -> Option<String> {
     match data {
@@ -15,6 +17,18 @@ pub fn identity_data_to_string(data: Data) -> Option<String> {
     }
 }
 
+/// Text of raw identity data, or the hash hex with the hash type of hashed identity data.
+pub fn decode_identity_data(data: Data) -> (Option<String>, Option<IdentityDataHashType>) {
+    let (hash, hash_type) = match data {
+        Data::BlakeTwo256(hash) => (hash, IdentityDataHashType::BlakeTwo256),
+        Data::Sha256(hash) => (hash, IdentityDataHashType::Sha256),
+        Data::Keccak256(hash) => (hash, IdentityDataHashType::Keccak256),
+        Data::ShaThree256(hash) => (hash, IdentityDataHashType::ShaThree256),
+        data => return (identity_data_to_string(data), None),
+    };
+    (Some(format!("0x{}", hex::encode(hash))), Some(hash_type))
+}
+
 #[derive(Clone, Default, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct Identity {
     pub account_id: AccountId,
@@ -24,6 +38,13 @@ pub struct Identity {
     pub riot: Option<String>,
     pub twitter: Option<String>,
     pub web: Option<String>,
+    pub github: Option<String>,
+    pub discord: Option<String>,
+    pub image: Option<String>,
+    pub pgp_fingerprint: Option<String>,
+    /// Hash types of the fields set as hashes, by field name.
+    pub hashed_fields: BTreeMap<String, IdentityDataHashType>,
+    pub judgements: Vec<IdentityJudgement>,
     pub is_confirmed: bool,
     pub is_invalid: bool,
 }
@@ -42,53 +63,113 @@ pub struct IdentityInfo {
     pub discord: Data,
 }
 
+/// Identity info of the relay chain identity pallet, before the identities moved to the People
+/// chain.
+#[derive(Clone, Debug, Decode, Encode)]
+pub struct LegacyIdentityInfo {
+    pub additional: BoundedVec<(Data, Data), ConstU32<{ u32::MAX }>>,
+    pub display: Data,
+    pub legal: Data,
+    pub web: Data,
+    pub riot: Data,
+    pub email: Data,
+    pub pgp_fingerprint: Option<[u8; 20]>,
+    pub image: Data,
+    pub twitter: Data,
+}
+
+impl From<LegacyIdentityInfo> for IdentityInfo {
+    fn from(info: LegacyIdentityInfo) -> Self {
+        IdentityInfo {
+            display: info.display,
+            legal: info.legal,
+            web: info.web,
+            riot: info.riot,
+            email: info.email,
+            pgp_fingerprint: info.pgp_fingerprint,
+            image: info.image,
+            twitter: info.twitter,
+            github: Data::None,
+            discord: Data::None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Decode, Encode)]
-struct Registration {
+struct Registration<Info> {
     pub judgements: BoundedVec<(u32, Judgement<u128>), ConstU32<{ u32::MAX }>>,
     pub deposit: u128,
-    pub info: IdentityInfo,
+    pub info: Info,
 }
 
 impl Identity {
     pub fn from_bytes(account_id: AccountId, mut bytes: &[u8]) -> anyhow::Result<Self> {
-        let registration: Registration = Decode::decode(&mut bytes)?;
-        let display = identity_data_to_string(registration.info.display);
-        let email = identity_data_to_string(registration.info.email);
-        let legal = identity_data_to_string(registration.info.legal);
-        let riot = identity_data_to_string(registration.info.riot);
-        let twitter = identity_data_to_string(registration.info.twitter);
-        let web = identity_data_to_string(registration.info.web);
+        let registration: Registration<IdentityInfo> = Decode::decode(&mut bytes)?;
+        Ok(Self::from_registration(account_id, registration))
+    }
+
+    /// Decodes a relay chain `Identity.IdentityOf` value, see `LegacyIdentityInfo`.
+    pub fn from_legacy_bytes(account_id: AccountId, mut bytes: &[u8]) -> anyhow::Result<Self> {
+        let registration: Registration<LegacyIdentityInfo> = Decode::decode(&mut bytes)?;
+        Ok(Self::from_registration(
+            account_id,
+            Registration {
+                judgements: registration.judgements,
+                deposit: registration.deposit,
+                info: registration.info.into(),
+            },
+        ))
+    }
+
+    fn from_registration(account_id: AccountId, registration: Registration<IdentityInfo>) -> Self {
+        let info = registration.info;
+        let mut hashed_fields = BTreeMap::new();
+        let mut decode = |field: &str, data: Data| {
+            let (value, maybe_hash_type) = decode_identity_data(data);
+            if let Some(hash_type) = maybe_hash_type {
+                hashed_fields.insert(field.to_string(), hash_type);
+            }
+            value
+        };
+        let display = decode("display", info.display);
+        let email = decode("email", info.email);
+        let legal = decode("legal", info.legal);
+        let riot = decode("riot", info.riot);
+        let twitter = decode("twitter", info.twitter);
+        let web = decode("web", info.web);
+        let github = decode("github", info.github);
+        let discord = decode("discord", info.discord);
+        let image = decode("image", info.image);
+        let pgp_fingerprint = info
+            .pgp_fingerprint
+            .map(|fingerprint| format!("0x{}", hex::encode(fingerprint)));
         let mut is_confirmed = false;
         let mut is_invalid = false;
-        for judgement in registration.judgements.iter() {
-            match judgement.1 {
-                Judgement::Reasonable | Judgement::KnownGood => {
-                    is_confirmed |= true;
-                    is_invalid |= false;
-                }
-                Judgement::Unknown => {
-                    is_confirmed |= false;
-                    is_invalid |= false;
-                }
-                Judgement::FeePaid(_) => {
-                    is_confirmed |= false;
-                    is_invalid |= false;
-                }
-                Judgement::OutOfDate => {
-                    is_confirmed |= false;
-                    is_invalid |= true;
-                }
-                Judgement::LowQuality => {
-                    is_confirmed |= false;
-                    is_invalid |= true;
-                }
-                Judgement::Erroneous => {
-                    is_confirmed |= false;
-                    is_invalid |= true;
-                }
+        let mut judgements = Vec::new();
+        for (registrar_index, judgement) in registration.judgements.iter() {
+            let (judgement, fee_paid_amount) = match judgement {
+                Judgement::Unknown => (JudgementType::Unknown, None),
+                Judgement::FeePaid(amount) => (JudgementType::FeePaid, Some(*amount)),
+                Judgement::Reasonable => (JudgementType::Reasonable, None),
+                Judgement::KnownGood => (JudgementType::KnownGood, None),
+                Judgement::OutOfDate => (JudgementType::OutOfDate, None),
+                Judgement::LowQuality => (JudgementType::LowQuality, None),
+                Judgement::Erroneous => (JudgementType::Erroneous, None),
             };
+            match judgement {
+                JudgementType::Reasonable | JudgementType::KnownGood => is_confirmed = true,
+                JudgementType::OutOfDate | JudgementType::LowQuality | JudgementType::Erroneous => {
+                    is_invalid = true
+                }
+                JudgementType::Unknown | JudgementType::FeePaid => (),
+            }
+            judgements.push(IdentityJudgement {
+                registrar_index: *registrar_index,
+                judgement,
+                fee_paid_amount,
+            });
         }
-        Ok(Identity {
+        Identity {
             account_id,
             display,
             email,
@@ -96,13 +177,64 @@ impl Identity {
             riot,
             twitter,
             web,
+            github,
+            discord,
+            image,
+            pgp_fingerprint,
+            hashed_fields,
+            judgements,
             is_confirmed,
             is_invalid,
-        })
+        }
+    }
+}
+
+#[derive(Clone, Debug, Decode, Encode)]
+struct RegistrarInfo {
+    pub account: AccountId,
+    pub fee: u128,
+    pub fields: u64,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Registrar {
+    pub index: u32,
+    pub account_id: AccountId,
+    pub fee: u128,
+    pub fields: u64,
+}
+
+impl Registrar {
+    /// Decodes the `Identity.Registrars` storage value. Removed registrars leave empty slots,
+    /// so the registrar indices are the slot positions.
+    pub fn list_from_bytes(mut bytes: &[u8]) -> anyhow::Result<Vec<Self>> {
+        let registrar_infos: Vec<Option<RegistrarInfo>> = Decode::decode(&mut bytes)?;
+        Ok(registrar_infos
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, maybe_registrar_info)| {
+                maybe_registrar_info.map(|registrar_info| Registrar {
+                    index: index as u32,
+                    account_id: registrar_info.account,
+                    fee: registrar_info.fee,
+                    fields: registrar_info.fields,
+                })
+            })
+            .collect())
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// Block at which the identity updater observed identity changes.
+#[derive(Clone, Debug)]
+pub struct IdentityChangeBlock {
+    pub chain: IdentityChain,
+    pub hash: String,
+    pub number: u64,
+    /// Block timestamp (ms).
+    pub timestamp: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct SubIdentity {
     pub account_id: AccountId,
     pub super_account_id: AccountId,
@@ -119,3 +251,113 @@ impl SubIdentity {
         })
     }
 }
+
+/// How a username was granted.
+#[derive(Clone, Copy, Debug, Decode, Deserialize, Encode, Eq, Hash, PartialEq, Serialize)]
+pub enum UsernameProvider {
+    /// Granted by an authority from its allocation.
+    Allocation,
+    /// Granted by an authority for the given deposit.
+    AuthorityDeposit(u128),
+    /// Granted by the system.
+    System,
+}
+
+impl UsernameProvider {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Allocation => "allocation",
+            Self::AuthorityDeposit(_) => "authority_deposit",
+            Self::System => "system",
+        }
+    }
+
+    pub fn deposit(&self) -> Option<u128> {
+        match self {
+            Self::AuthorityDeposit(deposit) => Some(*deposit),
+            _ => None,
+        }
+    }
+
+    pub fn from_name(name: &str, deposit: Option<u128>) -> anyhow::Result<Self> {
+        match name {
+            "allocation" => Ok(Self::Allocation),
+            "authority_deposit" => Ok(Self::AuthorityDeposit(deposit.unwrap_or_default())),
+            "system" => Ok(Self::System),
+            _ => Err(anyhow::anyhow!("Unknown username provider {name}.")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Decode, Encode)]
+struct UsernameInformation {
+    pub owner: AccountId,
+    pub provider: UsernameProvider,
+}
+
+pub fn username_from_bytes(mut bytes: &[u8]) -> anyhow::Result<String> {
+    let username: Vec<u8> = Decode::decode(&mut bytes)?;
+    Ok(String::from_utf8(username)?)
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Username {
+    pub username: String,
+    pub account_id: AccountId,
+    pub provider: UsernameProvider,
+    /// Whether this is the username the account chose to be displayed.
+    pub is_primary: bool,
+}
+
+impl Username {
+    /// `username` is the decoded `Identity.UsernameInfoOf` storage key, `bytes` the storage
+    /// value.
+    pub fn from_bytes(
+        username: String,
+        mut bytes: &[u8],
+        is_primary: bool,
+    ) -> anyhow::Result<Self> {
+        let information: UsernameInformation = Decode::decode(&mut bytes)?;
+        Ok(Username {
+            username,
+            account_id: information.owner,
+            provider: information.provider,
+            is_primary,
+        })
+    }
+
+    /// Usernames end with the suffix of the authority that granted them, e.g. `dot`.
+    pub fn suffix(&self) -> &str {
+        self.username
+            .rsplit_once('.')
+            .map(|(_, suffix)| suffix)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Debug, Decode, Encode)]
+struct AuthorityProperties {
+    pub account_id: AccountId,
+    pub allocation: u32,
+}
+
+/// Account that grants the usernames with its suffix.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UsernameAuthority {
+    pub suffix: String,
+    pub account_id: AccountId,
+    /// Number of usernames the authority can still grant from its allocation.
+    pub allocation: u32,
+}
+
+impl UsernameAuthority {
+    /// `suffix` is the decoded `Identity.AuthorityOf` storage key, `bytes` the storage value.
+    pub fn from_bytes(suffix: String, mut bytes: &[u8]) -> anyhow::Result<Self> {
+        let properties: AuthorityProperties = Decode::decode(&mut bytes)?;
+        Ok(UsernameAuthority {
+            suffix,
+            account_id: properties.account_id,
+            allocation: properties.allocation,
+        })
+    }
+}
@@ -0,0 +1,175 @@
+use crate::api::relation::AccountRelationType;
+use crate::substrate::account_id::AccountId;
+use crate::substrate::chain::Chain;
+use parity_scale_codec::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use sp_core::hashing::blake2_256;
+
+/// Account relation read from the proxy and staking storage, or derived from a multisig call.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct AccountRelation {
+    pub from: AccountId,
+    pub to: AccountId,
+    pub relation_type: AccountRelationType,
+    pub proxy_type: Option<String>,
+    pub proxy_delay: Option<u32>,
+    pub multisig_threshold: Option<u16>,
+}
+
+/// Proxies of different types between the same accounts are separate relations.
+pub type AccountRelationKey = (AccountId, AccountId, AccountRelationType, Option<String>);
+
+#[derive(Clone, Debug, Decode, Encode)]
+struct ProxyDefinition {
+    delegate: AccountId,
+    /// Variant index of the runtime's `ProxyType`.
+    proxy_type: u8,
+    delay: u32,
+}
+
+#[derive(Clone, Debug, Decode, Encode)]
+enum RewardDestination {
+    Staked,
+    Stash,
+    Controller,
+    Account(AccountId),
+    None,
+}
+
+/// Name of the `ProxyType` variant of the chain's runtime.
+fn proxy_type_name(chain: &Chain, index: u8) -> String {
+    let maybe_name = match (chain, index) {
+        (_, 0) => Some("Any"),
+        (_, 1) => Some("NonTransfer"),
+        (Chain::Polkadot | Chain::Kusama, 2) => Some("Governance"),
+        (Chain::Polkadot | Chain::Kusama, 3) => Some("Staking"),
+        (Chain::Polkadot, 6) => Some("CancelProxy"),
+        (Chain::Polkadot, 7) => Some("Auction"),
+        (Chain::Polkadot, 8) => Some("NominationPools"),
+        (Chain::Polkadot, 9) => Some("ParaRegistration"),
+        (Chain::Kusama, 5) => Some("CancelProxy"),
+        (Chain::Kusama, 6) => Some("Auction"),
+        (Chain::Kusama, 7) => Some("Society"),
+        (Chain::Kusama, 8) => Some("NominationPools"),
+        (Chain::Kusama, 9) => Some("Spokesperson"),
+        (Chain::Kusama, 10) => Some("ParaRegistration"),
+        (Chain::Westend, 2) => Some("Staking"),
+        (Chain::Westend, 5) => Some("CancelProxy"),
+        (Chain::Westend, 6) => Some("Auction"),
+        (Chain::Westend, 7) => Some("NominationPools"),
+        (Chain::Westend, 8) => Some("ParaRegistration"),
+        (Chain::PolkadotPeople, 2) => Some("CancelProxy"),
+        (Chain::PolkadotPeople, 3) => Some("Identity"),
+        (Chain::PolkadotPeople, 4) => Some("IdentityJudgement"),
+        (Chain::PolkadotPeople, 5) => Some("Collator"),
+        _ => None,
+    };
+    maybe_name
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Unknown{index}"))
+}
+
+impl AccountRelation {
+    pub fn key(&self) -> AccountRelationKey {
+        (
+            self.from,
+            self.to,
+            self.relation_type,
+            self.proxy_type.clone(),
+        )
+    }
+
+    /// Decodes the `Proxy.Proxies` storage value of the delegating account.
+    pub fn proxies_from_bytes(
+        chain: &Chain,
+        account_id: AccountId,
+        mut bytes: &[u8],
+    ) -> anyhow::Result<Vec<Self>> {
+        let (proxy_definitions, _deposit): (Vec<ProxyDefinition>, u128) =
+            Decode::decode(&mut bytes)?;
+        Ok(proxy_definitions
+            .into_iter()
+            .map(|proxy_definition| AccountRelation {
+                from: proxy_definition.delegate,
+                to: account_id,
+                relation_type: AccountRelationType::Proxy,
+                proxy_type: Some(proxy_type_name(chain, proxy_definition.proxy_type)),
+                proxy_delay: Some(proxy_definition.delay),
+                multisig_threshold: None,
+            })
+            .collect())
+    }
+
+    /// Decodes the `Staking.Bonded` storage value of the stash account. Stash accounts that are
+    /// their own controllers have no relation.
+    pub fn staking_controller_from_bytes(
+        account_id: AccountId,
+        mut bytes: &[u8],
+    ) -> anyhow::Result<Option<Self>> {
+        let controller: AccountId = Decode::decode(&mut bytes)?;
+        Ok((controller != account_id).then_some(AccountRelation {
+            from: controller,
+            to: account_id,
+            relation_type: AccountRelationType::StakingController,
+            proxy_type: None,
+            proxy_delay: None,
+            multisig_threshold: None,
+        }))
+    }
+
+    /// Decodes the `Staking.Payee` storage value of the stash account. Only the rewards paid to
+    /// an account other than the stash make a relation, the deprecated controller destination
+    /// is covered by the controller relation.
+    pub fn staking_payee_from_bytes(
+        account_id: AccountId,
+        mut bytes: &[u8],
+    ) -> anyhow::Result<Option<Self>> {
+        let reward_destination: RewardDestination = Decode::decode(&mut bytes)?;
+        Ok(match reward_destination {
+            RewardDestination::Account(payee) if payee != account_id => Some(AccountRelation {
+                from: payee,
+                to: account_id,
+                relation_type: AccountRelationType::StakingPayee,
+                proxy_type: None,
+                proxy_delay: None,
+                multisig_threshold: None,
+            }),
+            _ => None,
+        })
+    }
+}
+
+/// Multisig account of the given signatories and threshold, as derived by the multisig pallet.
+pub fn multisig_account_id(sorted_signatories: &[AccountId], threshold: u16) -> AccountId {
+    let entropy = (b"modlpy/utilisuba", sorted_signatories, threshold).using_encoded(blake2_256);
+    AccountId::new(entropy)
+}
+
+/// `Multisig` pallet call in a block, with the multisig account derived from its signatories.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MultisigCall {
+    pub extrinsic_index: u16,
+    pub multisig: String,
+    pub threshold: u16,
+    pub signatories: Vec<String>,
+}
+
+impl MultisigCall {
+    pub fn new(
+        extrinsic_index: u16,
+        signer: AccountId,
+        other_signatories: &[AccountId],
+        threshold: u16,
+    ) -> Self {
+        let mut signatories = other_signatories.to_vec();
+        signatories.push(signer);
+        signatories.sort();
+        signatories.dedup();
+        MultisigCall {
+            extrinsic_index,
+            multisig: multisig_account_id(&signatories, threshold).to_ss58_check(),
+            threshold,
+            signatories: signatories.iter().map(AccountId::to_ss58_check).collect(),
+        }
+    }
+}